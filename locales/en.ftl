@@ -0,0 +1,524 @@
+# Interface text. Keep the keys in the same order as in fr-CA.ftl.
+
+## Header
+usage-instructions = Usage instructions
+update-available = { $version } is available.
+update-download = Download
+
+## Schedule data
+schedule-data-heading = { $profile } Schedule Data
+load = Load...
+reset-profile = Reset to MyOntarioTech
+edit-regexes = Edit regexes...
+edit-subject-codes = Edit subject codes...
+edit-building-codes = Edit building codes...
+load-from-file = Load from file...
+paste-from-clipboard = Paste from clipboard
+fetch-with-browser = Fetch with a browser
+fetch-from-banner = Fetch from Banner
+remember-data = Remember pasted data
+summary-table = Registration summary table (optional)
+
+## Preview
+preview-heading = Preview
+week-view = Week view
+free-time = Free time
+
+## Excluded dates
+excluded-dates-heading = Excluded Dates
+add-single-date = ➕ Single
+add-date-range = ➕ Range
+add-swap-day = ➕ Swap day
+import = Import
+from-file = From file...
+
+## Custom events and exams
+custom-events-heading = Custom Events
+exam-schedule-heading = Exam Schedule
+
+## Output
+output-heading = Output
+select-output-folder = Select output folder...
+settings = Settings...
+export-settings = Export settings...
+import-settings = Import settings...
+compare = Compare
+status-no-classes = ✖ No classes were found in the schedule data
+status-detected = ✔ { $meetings ->
+        [one] { $meetings } meeting
+       *[other] { $meetings } meetings
+    } across { $classes ->
+        [one] { $classes } class
+       *[other] { $classes } classes
+    } detected
+status-failures = , but { $failures ->
+        [one] { $failures } class
+       *[other] { $failures } classes
+    } couldn't be read
+copy-diagnostic-report = Copy diagnostic report
+generate = Generate calendar files
+export-as = Export as...
+cancel = Cancel
+replace = Replace
+keep-both = Keep both
+retry = Retry
+merge-into-calendar = Merge into existing calendar...
+clear = Clear
+log = Log
+
+## Settings window
+language = Language:
+language-system = System
+interface-scale = Interface scale:
+reset = Reset
+theme = Theme:
+theme-system = System
+theme-dark = Dark
+theme-light = Light
+
+## Session
+previous-session = Changes from the previous session can be restored.
+restore-session = Restore previous session
+discard = Discard
+
+## Tabs
+tab-name = Schedule { $number }
+new-tab = Open another schedule in a new tab
+close-tab = Close tab
+
+## Event settings
+settings-calendars = Calendars:
+grouping-class-type = One per schedule type
+grouping-course = One per course
+grouping-combined = Single combined file
+settings-event-title = Event title
+settings-event-description = Event description
+settings-placeholders = Placeholders
+placeholder-name = Course name
+placeholder-code = Course code, eg. CSCI 1200U
+placeholder-type = Schedule type, eg. Lecture
+placeholder-crn = CRN
+placeholder-instructor = Instructor
+placeholder-url = Meeting link for online classes
+placeholder-campus = Campus
+placeholder-building = Building
+placeholder-room = Room
+placeholder-weekday = Day of the week
+placeholder-start = Start time
+placeholder-end = End time
+settings-example = eg. { $example }
+settings-async-events = All-day events for asynchronous sections:
+async-events-off = None
+async-events-first-day = First day only
+async-events-weekly = Weekly
+date-order-auto = Detect
+date-order-month-first = Month first (MM/DD/YYYY)
+date-order-day-first = Day first (DD/MM/YYYY)
+settings-milestones = All-day events for the first and last day of each course
+settings-maps-links = Add a Google Maps link to each event
+settings-maps-links-hover = Searches for the building, or its coordinates if they're in the profile
+settings-start-offset = Move class start by
+settings-start-offset-hover = Eg. +10 if classes actually start ten minutes after the listed time
+settings-end-offset = and end by
+settings-travel-first-class = Travel time before the first class of the day
+settings-travel-between-buildings = and between buildings
+settings-travel-hover = Adds "Walk to" events so the time isn't booked by other people or scheduling assistants
+settings-expand-occurrences = Separate event for each class instead of repeating events
+settings-expand-occurrences-hover = For calendar apps that don't handle repeating events well. Excluded dates are left out.
+settings-show-as-free = Show classes as free time
+settings-show-as-free-hover = For sharing your calendar without blocking off the time
+settings-private-events = Mark events as private
+settings-private-events-hover = People you share the calendar with only see when you're busy
+settings-skip-zero-credit = Skip 0 credit hour sections
+settings-skip-zero-credit-hover = Leave out sections like co-op preparation seminars. The hours come from the registration summary table.
+settings-instructor-attendees = Add instructors as attendees
+settings-instructor-attendees-hover = Only instructors with an email are added, and only if the primary instructor has one, since they become the organizer. Some calendar apps treat events with attendees as invitations.
+settings-instructor-emails-empty = Paste your schedule to add the instructors' emails.
+settings-email-hint = Email (optional)
+settings-reminders = Reminders
+settings-reminders-empty = Paste your schedule to set reminders for each schedule type.
+settings-minutes-before = minutes before
+settings-colors = Colors
+settings-colors-empty = Paste your schedule to set colors for each schedule type.
+
+## Weekdays
+weekday-mon = Mon
+weekday-tue = Tue
+weekday-wed = Wed
+weekday-thu = Thu
+weekday-fri = Fri
+weekday-sat = Sat
+weekday-sun = Sun
+
+## Preview table
+preview-name = Name
+preview-code = Code
+preview-crn = CRN
+preview-type = Type
+preview-instructor = Instructor
+preview-meeting-url = Meeting URL
+preview-weekday = Weekday
+preview-start = Start
+preview-end = End
+preview-biweekly = Biweekly
+preview-building = Building
+preview-room = Room
+preview-selected-hover = Include this class in the calendars
+preview-meeting-url-hover = Link for joining the online meetings, added to the events
+preview-biweekly-hover = Meets every other week
+
+## Regex editor
+regex-window = Regexes
+regex-test-line = Test line:
+regex-test-line-hint = Paste a line from the schedule data to test it.
+regex-course-summary = Course summary
+regex-course-name = Course name
+regex-date = Date
+regex-time = Time
+regex-message = Message
+regex-crn = CRN
+regex-instructor = Instructor
+regex-matches-test-line = ✔ Matches test line: [{ $groups }]
+regex-no-match-test-line = ✖ Doesn't match test line
+regex-matching-lines =
+    { $lines ->
+        [one] { $lines } matching line
+       *[other] { $lines } matching lines
+    } in pasted data, eg.:
+regex-no-matching-lines = ✖ No lines in the pasted data match
+reset-to-defaults = Reset to defaults
+
+## Subject codes
+subjects-window = Subject Codes
+subjects-description = Map long subject names from the schedule to their short codes, eg. "Computer Science" → "CSCI".
+subjects-subject = Subject
+subjects-code = Code
+subjects-add = ➕ Add subject
+subjects-built-in = Built-in subjects
+
+## Building codes
+buildings-window = Building Codes
+buildings-description = Map building codes in room numbers to their names, so eg. "ERC 1040" shows up as "Energy Research Centre – 1040".
+buildings-code = Code
+buildings-building = Building
+buildings-campus = Campus
+buildings-add = ➕ Add building
+buildings-campus-names = Campus names
+buildings-campus-names-description = Rename campuses from the schedule in the events, eg. "North Oshawa" → "North Campus".
+buildings-add-campus = ➕ Add campus
+
+## Custom events
+custom-events-add = ➕ Event
+custom-events-add-hover = A weekly event to add to the calendars, eg. office hours or a work shift
+custom-events-title = Title
+custom-events-location = Location
+date-range-to = to
+
+## Free time
+free-time-from = From
+free-time-to = to
+free-time-export = Export "Free time (VFREEBUSY)" to share the free time of each day of the term.
+free-time-compare = Compare with a friend's schedule
+free-time-compare-description = Paste their schedule the same way as yours.
+free-time-friend-hint = Friend's schedule
+free-time-friend-no-classes = No classes were found in their schedule.
+free-time-both = Free for both of you
+
+## Week view
+week-grid-empty = No meetings to show.
+week-grid-dates = { $start } to { $end }
+week-grid-save-png = Save as PNG...
+week-grid-png-filter = PNG image
+saved = ☑ Saved { $path }.
+week-grid-save-failed = ⚠ Failed to save the image: { $error }
+
+## Exam schedule
+exams-description = Paste the final exam schedule the same way as your schedule, or load its PDF. Each exam is added once to an "Exams" calendar.
+exams-load-pdf = Load PDF...
+
+## Log
+copy = Copy
+log-open-folder = Open log folder
+log-empty = Nothing has been logged yet.
+
+## Diagnostics
+diagnostics-line = Line { $line }:{" "}
+diagnostics-expected = Expected { $expected }
+diagnostics-show-line = Show this line in the schedule data
+
+## Configurations
+configs-label = Configuration:
+configs-unsaved = Unsaved
+configs-hover = Switch between saved output folders, settings, timezones, and subject codes
+configs-name-hint = Name, eg. Partner's schedule
+configs-save-as = Save as
+configs-delete = Delete
+configs-delete-hover = Forget this configuration. The current settings are kept.
+
+## Calendar services
+pushed-events =
+    ☑ Pushed { $events ->
+        [one] { $events } event
+       *[other] { $events } events
+    }.
+google-description = Create an OAuth client of type "Desktop app" in the Google Cloud console, enable the Google Calendar API for it, and enter its ID and secret here.
+client-id = Client ID:
+client-secret = Client secret:
+google-sign-in = Sign in with Google
+calendar = Calendar:
+push-events = Push events
+push-events-hover = Create or update the events in this calendar
+sign-out = Sign out
+waiting-for-browser = Waiting for the browser...
+pushing-events = Pushing events...
+
+## Outlook
+outlook-description = Register an app in the Azure portal with "Allow public client flows" turned on, and enter its client ID here. Use "organizations" or your university's domain as the tenant for a university account.
+outlook-tenant = Tenant:
+outlook-sign-in = Sign in with Microsoft
+outlook-go-to = Go to
+outlook-enter-code = and enter the code
+signing-in = Signing in...
+
+## CalDAV
+caldav-uploaded =
+    ☑ Uploaded { $events ->
+        [one] { $events } event
+       *[other] { $events } events
+    }.
+caldav-description = Enter the URL of the calendar itself, not just the server. Use an app password if your account has two-factor authentication, eg. on iCloud or Fastmail.
+caldav-url = Calendar URL:
+caldav-username = Username:
+caldav-password = Password:
+caldav-password-hint = Saved after uploading
+caldav-upload = Upload events
+caldav-upload-hover = Create or replace the events in this calendar
+caldav-forget-password = Forget password
+caldav-uploading = Uploading events...
+
+## Banner
+banner-fetched =
+    ☑ Fetched { $classes ->
+        [one] { $classes } class
+       *[other] { $classes } classes
+    }.
+banner-description = Sign in to the registration pages in your browser, then copy the JSESSIONID cookie from its developer tools (Storage or Application tab).
+banner-term-code = Term code:
+banner-term-code-hint = eg. 202409 for Fall 2024
+banner-session-cookie = Session cookie:
+banner-fetch = Fetch classes
+banner-fetch-hover = Replace the parsed schedule with the classes from Banner
+banner-fetching = Fetching classes...
+
+## Browser
+browser-fetched = ☑ Fetched the schedule.
+browser-description = Start chromedriver or geckodriver, then click Open browser. Sign in and open your schedule in the window that appears, and it will be read from the page.
+browser-webdriver-url = WebDriver URL:
+browser-open = Open browser
+browser-waiting = Waiting for the schedule to be opened...
+
+## Status messages
+holidays-imported-skipped =
+    { $imported ->
+        [one] Imported { $imported } date
+       *[other] Imported { $imported } dates
+    }, skipped { $skipped } outside of the schedule.
+holidays-imported =
+    { $imported ->
+        [one] Imported { $imported } date
+       *[other] Imported { $imported } dates
+    }.
+settings-exported = Exported to { $path }
+settings-export-failed = ⚠ Failed to export settings: { $error }
+settings-imported = Imported { $path }
+settings-import-failed = ⚠ Failed to import settings: { $error }
+deadlines-no-schedule = ⚠ Load your schedule first.
+deadlines-imported =
+    { $deadlines ->
+        [one] Imported { $deadlines } deadline
+       *[other] Imported { $deadlines } deadlines
+    } for { $courses ->
+        [one] { $courses } course
+       *[other] { $courses } courses
+    }.
+profile-invalid-regex = Invalid regex in institution profile: { $error }
+
+## Loading files
+drop-file = Drop a schedule .txt, saved .html page, screenshot or PDF to load it
+dropped-file-unavailable = its contents aren't available
+load-failed = Couldn't load { $name }: { $error }
+read-failed = Couldn't read { $name }: { $error }
+
+## Generating
+wrote-and-zipped = ☑ Wrote { $path } and { $zip }.
+wrote-zip-failed = ⚠ Wrote { $path }, but failed to update { $zip }: { $error }
+wrote = ☑ Wrote { $path }.
+export-failed = ⚠ An error occurred while exporting { $format }: { $error }
+generate-failed = ⚠ An error occurred while generating calendars: { $error }
+generate-cancelled =
+    ⚠ Cancelled after writing { $files ->
+        [one] { $files } file
+       *[other] { $files } files
+    }.
+generate-none = ⚠ No calendars were generated.
+generate-write-failures =
+    ⚠ Generated { $calendars ->
+        [one] { $calendars } calendar
+       *[other] { $calendars } calendars
+    }, but { $files ->
+        [one] { $files } file
+       *[other] { $files } files
+    } could not be written.
+generate-parse-failures =
+    ⚠ Generated { $calendars ->
+        [one] { $calendars } calendar
+       *[other] { $calendars } calendars
+    }, but { $classes ->
+        [one] { $classes } class
+       *[other] { $classes } classes
+    } could not be parsed.
+generated =
+    ☑ Generated { $calendars ->
+        [one] { $calendars } calendar
+       *[other] { $calendars } calendars
+    }.
+
+## Windows
+replace-title = Replace existing files?
+replace-description = These calendars are already in the output folder:
+keep-both-hover = Write eg. "Fall 2024 - Lecture (2).ics" instead
+settings-window = Settings
+export-settings-hover = Save these settings as JSON, eg. to use on another computer
+check-for-updates = Check for updates on startup
+check-for-updates-hover = Ask GitHub whether there's a newer version when the program starts.
+timezone = Timezone:
+timezone-hover = IANA name of the timezone the classes are in. Defaults to the institution profile's.
+date-order = Date order:
+date-order-hover = How dates like 04/09/2024 are read. Browsers in languages other than English (US) usually put the day first.
+course-catalog = Course catalog:
+course-catalog-hover = A CSV or JSON file of full course titles by code, for the titles that are cut off in the schedule.
+course-catalog-filter = Course catalog
+course-catalog-titles =
+    { $titles ->
+        [one] { $titles } title
+       *[other] { $titles } titles
+    }
+
+## Schedule data input
+dismiss = Dismiss
+institution-profile = Institution profile:
+filter-schedule = Schedule
+filter-screenshot = Screenshot
+filter-pdf-schedule = PDF schedule
+filter-previous-export = Previous export
+paste-from-clipboard-hover = Replace the data below with what's on the clipboard. Screenshots are read with Tesseract.
+the-screenshot = the screenshot
+clipboard-failed = Couldn't read the clipboard: { $error }
+schedule-data-hint = Paste the copied schedule data here, or drop a .txt file, saved .html page or screenshot onto the window.
+
+## Parsing
+reading-pdf = Reading the PDF...
+reading-screenshot = Reading text from the screenshot...
+remember-data-hover = Keep the pasted schedule data when the program is closed.
+summary-table-description = If some subject codes can't be found, paste the Title/Details/Hours/CRN table from the Registration Information page here.
+summary-table-hint = Paste the registration summary table here.
+parsing = Parsing...
+detected-format = Detected format: { $format }
+term = Term:
+all-terms = All terms
+term-hover = The data has classes from more than one term. Pick which term's calendars to generate.
+no-classes = No classes were found.
+
+## Excluded dates and deadlines
+add-preset = ➕ Holiday or study week
+import-holidays = Import from holidays calendar:
+add-swap-day-hover = A day that follows another weekday's schedule
+swap-day-follows = follows a
+swap-day-schedule = schedule
+deadlines = Deadlines from Canvas or Brightspace:
+deadlines-hover = The calendar feed link from Canvas's Calendar page, or Brightspace's calendar subscription. Assignments for your courses are added as all-day events.
+
+## Output options
+recent-output-folders = Recently used output folders
+merge-weekdays = Merge weekdays into one event
+merge-weekdays-hover = Write eg. a Monday/Wednesday lecture as one repeating event instead of two.
+zip = Bundle into { $zip }
+zip-hover = Also zip the calendars and exports in the output folder, eg. to email them.
+rename-existing = Keep existing calendar files
+rename-existing-hover = Write eg. "Fall 2024 - Lecture (2).ics" instead of replacing calendars that are already in the output folder.
+update-previous = Update from previous export
+update-previous-hover = Compare with the calendars already in the output folder, so re-importing only updates the meetings that changed.
+
+## Results
+no-changes = No changes since the previous export.
+previous-export-failed = ⚠ Failed to read the previous export: { $error }
+copy-diagnostic-report-hover = Copy a bug report to paste into a GitHub issue, with your name and CRNs taken out of the schedule data
+export-format-hover = Write { $file }
+write-failed = Failed to write { $path }
+weekly-hours =
+    { $hours ->
+        [one] { $hours } hour of classes per week
+       *[other] { $hours } hours of classes per week
+    }
+classes-generated =
+    { $classes ->
+        [one] { $classes } class generated
+       *[other] { $classes } classes generated
+    }
+files-written =
+    { $files ->
+        [one] { $files } file written
+       *[other] { $files } files written
+    }
+merge-into-calendar-hover = Write a copy of a calendar file with these events added, replacing any copies of them that are already in it
+filter-calendar = Calendar
+merged =
+    ☑ Wrote { $path }: { $added ->
+        [one] { $added } event added
+       *[other] { $added } events added
+    }, { $replaced } replaced, { $kept } kept.
+merge-failed = ⚠ Failed to merge: { $error }
+
+## Files
+statistics-by-course = By course
+statistics-by-type = By schedule type
+not-text-file = not a text file
+open-file = Open file
+open-file-failed = Failed to open { $path }: { $error }
+reveal-in-folder = Reveal in folder
+reveal-in-folder-failed = Failed to open the folder of { $path }: { $error }
+
+## Crashes
+worker-crashed = ⚠ Something went wrong in the background. The details are in the log.
+
+## Web
+downloaded = ☑ Downloaded { $file }.
+week-grid-download-png = Download as PNG
+
+## Changes since the previous export
+change-added = ➕ Added: { $meeting }
+change-removed = ➖ Removed: { $meeting }
+change-changed = ✏ Changed: { $meeting }: { $changes }
+change-meeting = { $name } ({ $time })
+change-all-day = all day { $weekday } { $date }
+change-name = name: { $from } → { $to }
+change-time = time: { $from } → { $to }
+change-location = location: { $from } → { $to }
+change-dates = dates changed
+change-description = description changed
+
+## Crash
+crash-title = mycampus-calendar-rs crashed
+crash-saved =
+    mycampus-calendar-rs ran into a problem. A crash report was saved to:
+    
+    { $path }
+    
+    Please attach it to a GitHub issue.
+crash-not-saved =
+    mycampus-calendar-rs ran into a problem, and the crash report couldn't be saved:
+    
+    { $report }
+
+## Regex groups
+regex-missing-group = ✖ Missing the { $group } capture group
@@ -0,0 +1,514 @@
+# Texte de l'interface. Garder les clés dans le même ordre que dans en.ftl.
+
+## En-tête
+usage-instructions = Mode d'emploi
+update-available = La version { $version } est disponible.
+update-download = Télécharger
+
+## Données de l'horaire
+schedule-data-heading = Données de l'horaire { $profile }
+load = Charger...
+reset-profile = Rétablir MyOntarioTech
+edit-regexes = Modifier les expressions régulières...
+edit-subject-codes = Modifier les codes de matière...
+edit-building-codes = Modifier les codes de bâtiment...
+load-from-file = Charger un fichier...
+paste-from-clipboard = Coller depuis le presse-papiers
+fetch-with-browser = Récupérer avec un navigateur
+fetch-from-banner = Récupérer depuis Banner
+remember-data = Mémoriser les données collées
+summary-table = Tableau récapitulatif de l'inscription (facultatif)
+
+## Aperçu
+preview-heading = Aperçu
+week-view = Vue de la semaine
+free-time = Temps libre
+
+## Dates exclues
+excluded-dates-heading = Dates exclues
+add-single-date = ➕ Date
+add-date-range = ➕ Période
+add-swap-day = ➕ Jour permuté
+import = Importer
+from-file = Depuis un fichier...
+
+## Événements personnalisés et examens
+custom-events-heading = Événements personnalisés
+exam-schedule-heading = Horaire des examens
+
+## Sortie
+output-heading = Sortie
+select-output-folder = Choisir le dossier de sortie...
+settings = Paramètres...
+export-settings = Exporter les paramètres...
+import-settings = Importer les paramètres...
+compare = Comparer
+status-no-classes = ✖ Aucun cours n'a été trouvé dans les données de l'horaire
+status-detected = ✔ { $meetings ->
+        [one] { $meetings } rencontre détectée
+       *[other] { $meetings } rencontres détectées
+    } dans { $classes } cours
+status-failures = , mais { $failures ->
+        [one] { $failures } cours n'a pas pu être lu
+       *[other] { $failures } cours n'ont pas pu être lus
+    }
+copy-diagnostic-report = Copier le rapport de diagnostic
+generate = Générer les fichiers de calendrier
+export-as = Exporter en...
+cancel = Annuler
+replace = Remplacer
+keep-both = Garder les deux
+retry = Réessayer
+merge-into-calendar = Fusionner dans un calendrier existant...
+clear = Effacer
+log = Journal
+
+## Fenêtre des paramètres
+language = Langue :
+language-system = Système
+interface-scale = Échelle de l'interface :
+reset = Réinitialiser
+theme = Thème :
+theme-system = Système
+theme-dark = Sombre
+theme-light = Clair
+
+## Session
+previous-session = Les modifications de la session précédente peuvent être restaurées.
+restore-session = Restaurer la session précédente
+discard = Ignorer
+
+## Onglets
+tab-name = Horaire { $number }
+new-tab = Ouvrir un autre horaire dans un nouvel onglet
+close-tab = Fermer l'onglet
+
+## Paramètres des événements
+settings-calendars = Calendriers :
+grouping-class-type = Un par type de cours
+grouping-course = Un par cours
+grouping-combined = Un seul fichier combiné
+settings-event-title = Titre de l'événement
+settings-event-description = Description de l'événement
+settings-placeholders = Espaces réservés
+placeholder-name = Nom du cours
+placeholder-code = Code du cours, p. ex. CSCI 1200U
+placeholder-type = Type de cours, p. ex. Cours magistral
+placeholder-crn = CRN
+placeholder-instructor = Professeur
+placeholder-url = Lien de la réunion pour les cours en ligne
+placeholder-campus = Campus
+placeholder-building = Bâtiment
+placeholder-room = Salle
+placeholder-weekday = Jour de la semaine
+placeholder-start = Heure de début
+placeholder-end = Heure de fin
+settings-example = p. ex. { $example }
+settings-async-events = Événements d'une journée pour les sections asynchrones :
+async-events-off = Aucun
+async-events-first-day = Premier jour seulement
+async-events-weekly = Chaque semaine
+date-order-auto = Détecter
+date-order-month-first = Mois en premier (MM/JJ/AAAA)
+date-order-day-first = Jour en premier (JJ/MM/AAAA)
+settings-milestones = Événements d'une journée pour le premier et le dernier jour de chaque cours
+settings-maps-links = Ajouter un lien Google Maps à chaque événement
+settings-maps-links-hover = Recherche le bâtiment, ou ses coordonnées si elles sont dans le profil
+settings-start-offset = Décaler le début des cours de
+settings-start-offset-hover = P. ex. +10 si les cours commencent en fait dix minutes après l'heure indiquée
+settings-end-offset = et la fin de
+settings-travel-first-class = Temps de déplacement avant le premier cours de la journée
+settings-travel-between-buildings = et entre les bâtiments
+settings-travel-hover = Ajoute des événements « Marcher jusqu'à » pour que ce temps ne soit pas réservé par d'autres personnes ou des assistants de planification
+settings-expand-occurrences = Un événement par cours au lieu d'événements récurrents
+settings-expand-occurrences-hover = Pour les applications de calendrier qui gèrent mal les événements récurrents. Les dates exclues sont omises.
+settings-show-as-free = Afficher les cours comme temps libre
+settings-show-as-free-hover = Pour partager votre calendrier sans bloquer ce temps
+settings-private-events = Marquer les événements comme privés
+settings-private-events-hover = Les personnes avec qui vous partagez le calendrier voient seulement quand vous êtes occupé
+settings-skip-zero-credit = Ignorer les sections à 0 crédit
+settings-skip-zero-credit-hover = Omet les sections comme les séminaires de préparation aux stages. Les crédits viennent du tableau récapitulatif de l'inscription.
+settings-instructor-attendees = Ajouter les professeurs comme participants
+settings-instructor-attendees-hover = Seuls les professeurs avec un courriel sont ajoutés, et seulement si le professeur principal en a un, puisqu'il devient l'organisateur. Certaines applications de calendrier traitent les événements avec des participants comme des invitations.
+settings-instructor-emails-empty = Collez votre horaire pour ajouter les courriels des professeurs.
+settings-email-hint = Courriel (facultatif)
+settings-reminders = Rappels
+settings-reminders-empty = Collez votre horaire pour choisir des rappels pour chaque type de cours.
+settings-minutes-before = minutes avant
+settings-colors = Couleurs
+settings-colors-empty = Collez votre horaire pour choisir des couleurs pour chaque type de cours.
+
+## Jours de la semaine
+weekday-mon = lun
+weekday-tue = mar
+weekday-wed = mer
+weekday-thu = jeu
+weekday-fri = ven
+weekday-sat = sam
+weekday-sun = dim
+
+## Tableau de l'aperçu
+preview-name = Nom
+preview-code = Code
+preview-crn = CRN
+preview-type = Type
+preview-instructor = Professeur
+preview-meeting-url = Lien de la réunion
+preview-weekday = Jour
+preview-start = Début
+preview-end = Fin
+preview-biweekly = Aux deux semaines
+preview-building = Bâtiment
+preview-room = Salle
+preview-selected-hover = Inclure ce cours dans les calendriers
+preview-meeting-url-hover = Lien pour rejoindre les réunions en ligne, ajouté aux événements
+preview-biweekly-hover = A lieu une semaine sur deux
+
+## Éditeur des expressions régulières
+regex-window = Expressions régulières
+regex-test-line = Ligne de test :
+regex-test-line-hint = Collez une ligne des données de l'horaire pour la tester.
+regex-course-summary = Résumé du cours
+regex-course-name = Nom du cours
+regex-date = Date
+regex-time = Heure
+regex-message = Message
+regex-crn = CRN
+regex-instructor = Professeur
+regex-matches-test-line = ✔ Correspond à la ligne de test : [{ $groups }]
+regex-no-match-test-line = ✖ Ne correspond pas à la ligne de test
+regex-matching-lines =
+    { $lines ->
+        [one] { $lines } ligne correspondante
+       *[other] { $lines } lignes correspondantes
+    } dans les données collées, p. ex. :
+regex-no-matching-lines = ✖ Aucune ligne des données collées ne correspond
+reset-to-defaults = Rétablir les valeurs par défaut
+
+## Codes de matière
+subjects-window = Codes de matière
+subjects-description = Associe les noms de matière longs de l'horaire à leurs codes courts, p. ex. « Computer Science » → « CSCI ».
+subjects-subject = Matière
+subjects-code = Code
+subjects-add = ➕ Ajouter une matière
+subjects-built-in = Matières intégrées
+
+## Codes de bâtiment
+buildings-window = Codes de bâtiment
+buildings-description = Associe les codes de bâtiment des numéros de salle à leurs noms, pour que p. ex. « ERC 1040 » devienne « Energy Research Centre – 1040 ».
+buildings-code = Code
+buildings-building = Bâtiment
+buildings-campus = Campus
+buildings-add = ➕ Ajouter un bâtiment
+buildings-campus-names = Noms des campus
+buildings-campus-names-description = Renomme les campus de l'horaire dans les événements, p. ex. « North Oshawa » → « Campus nord ».
+buildings-add-campus = ➕ Ajouter un campus
+
+## Événements personnalisés
+custom-events-add = ➕ Événement
+custom-events-add-hover = Un événement hebdomadaire à ajouter aux calendriers, p. ex. des heures de bureau ou un quart de travail
+custom-events-title = Titre
+custom-events-location = Lieu
+date-range-to = au
+
+## Temps libre
+free-time-from = De
+free-time-to = à
+free-time-export = Exportez « Free time (VFREEBUSY) » pour partager le temps libre de chaque jour de la session.
+free-time-compare = Comparer avec l'horaire d'un ami
+free-time-compare-description = Collez son horaire de la même façon que le vôtre.
+free-time-friend-hint = Horaire de l'ami
+free-time-friend-no-classes = Aucun cours n'a été trouvé dans son horaire.
+free-time-both = Libre pour vous deux
+
+## Vue de la semaine
+week-grid-empty = Aucune rencontre à afficher.
+week-grid-dates = Du { $start } au { $end }
+week-grid-save-png = Enregistrer en PNG...
+week-grid-png-filter = Image PNG
+saved = ☑ { $path } a été enregistré.
+week-grid-save-failed = ⚠ Impossible d'enregistrer l'image : { $error }
+
+## Horaire des examens
+exams-description = Collez l'horaire des examens finaux de la même façon que votre horaire, ou chargez son PDF. Chaque examen est ajouté une fois à un calendrier « Exams ».
+exams-load-pdf = Charger le PDF...
+
+## Journal
+copy = Copier
+log-open-folder = Ouvrir le dossier du journal
+log-empty = Rien n'a encore été journalisé.
+
+## Diagnostic
+diagnostics-line = Ligne { $line } :{" "}
+diagnostics-expected = Attendu : { $expected }
+diagnostics-show-line = Afficher cette ligne dans les données de l'horaire
+
+## Configurations
+configs-label = Configuration :
+configs-unsaved = Non enregistrée
+configs-hover = Passer d'un ensemble enregistré de dossier de sortie, de paramètres, de fuseau horaire et de codes de matière à un autre
+configs-name-hint = Nom, p. ex. Horaire de mon partenaire
+configs-save-as = Enregistrer sous
+configs-delete = Supprimer
+configs-delete-hover = Oublier cette configuration. Les paramètres actuels sont conservés.
+
+## Services de calendrier
+pushed-events =
+    ☑ { $events ->
+        [one] { $events } événement envoyé
+       *[other] { $events } événements envoyés
+    }.
+google-description = Créez un client OAuth de type « Application de bureau » dans la console Google Cloud, activez-y l'API Google Calendar, puis entrez son identifiant et son secret ici.
+client-id = ID client :
+client-secret = Secret client :
+google-sign-in = Se connecter avec Google
+calendar = Calendrier :
+push-events = Envoyer les événements
+push-events-hover = Créer ou mettre à jour les événements dans ce calendrier
+sign-out = Se déconnecter
+waiting-for-browser = En attente du navigateur...
+pushing-events = Envoi des événements...
+
+## Outlook
+outlook-description = Inscrivez une application dans le portail Azure avec « Autoriser les flux de clients publics » activé, puis entrez son ID client ici. Utilisez « organizations » ou le domaine de votre université comme locataire pour un compte universitaire.
+outlook-tenant = Locataire :
+outlook-sign-in = Se connecter avec Microsoft
+outlook-go-to = Allez à
+outlook-enter-code = et entrez le code
+signing-in = Connexion...
+
+## CalDAV
+caldav-uploaded =
+    ☑ { $events ->
+        [one] { $events } événement téléversé
+       *[other] { $events } événements téléversés
+    }.
+caldav-description = Entrez l'URL du calendrier lui-même, pas seulement celle du serveur. Utilisez un mot de passe d'application si votre compte a l'authentification à deux facteurs, p. ex. sur iCloud ou Fastmail.
+caldav-url = URL du calendrier :
+caldav-username = Nom d'utilisateur :
+caldav-password = Mot de passe :
+caldav-password-hint = Enregistré après le téléversement
+caldav-upload = Téléverser les événements
+caldav-upload-hover = Créer ou remplacer les événements dans ce calendrier
+caldav-forget-password = Oublier le mot de passe
+caldav-uploading = Téléversement des événements...
+
+## Banner
+banner-fetched =
+    ☑ { $classes ->
+        [one] { $classes } cours récupéré
+       *[other] { $classes } cours récupérés
+    }.
+banner-description = Connectez-vous aux pages d'inscription dans votre navigateur, puis copiez le témoin JSESSIONID depuis ses outils de développement (onglet Stockage ou Application).
+banner-term-code = Code de session :
+banner-term-code-hint = p. ex. 202409 pour l'automne 2024
+banner-session-cookie = Témoin de session :
+banner-fetch = Récupérer les cours
+banner-fetch-hover = Remplacer l'horaire lu par les cours de Banner
+banner-fetching = Récupération des cours...
+
+## Navigateur
+browser-fetched = ☑ L'horaire a été récupéré.
+browser-description = Lancez chromedriver ou geckodriver, puis cliquez sur Ouvrir le navigateur. Connectez-vous et ouvrez votre horaire dans la fenêtre qui apparaît; il sera lu depuis la page.
+browser-webdriver-url = URL WebDriver :
+browser-open = Ouvrir le navigateur
+browser-waiting = En attente de l'ouverture de l'horaire...
+
+## Messages d'état
+holidays-imported-skipped =
+    { $imported ->
+        [one] { $imported } date importée
+       *[other] { $imported } dates importées
+    }, { $skipped } hors de la session ignorées.
+holidays-imported =
+    { $imported ->
+        [one] { $imported } date importée
+       *[other] { $imported } dates importées
+    }.
+settings-exported = Exporté vers { $path }
+settings-export-failed = ⚠ Impossible d'exporter les paramètres : { $error }
+settings-imported = { $path } a été importé
+settings-import-failed = ⚠ Impossible d'importer les paramètres : { $error }
+deadlines-no-schedule = ⚠ Chargez d'abord votre horaire.
+deadlines-imported =
+    { $deadlines ->
+        [one] { $deadlines } échéance importée
+       *[other] { $deadlines } échéances importées
+    } pour { $courses } cours.
+profile-invalid-regex = Expression régulière invalide dans le profil de l'établissement : { $error }
+
+## Chargement des fichiers
+drop-file = Déposez un horaire .txt, une page .html enregistrée, une capture d'écran ou un PDF pour le charger
+dropped-file-unavailable = son contenu n'est pas disponible
+load-failed = Impossible de charger { $name } : { $error }
+read-failed = Impossible de lire { $name } : { $error }
+
+## Génération
+wrote-and-zipped = ☑ { $path } et { $zip } ont été écrits.
+wrote-zip-failed = ⚠ { $path } a été écrit, mais { $zip } n'a pas pu être mis à jour : { $error }
+wrote = ☑ { $path } a été écrit.
+export-failed = ⚠ Une erreur s'est produite lors de l'exportation en { $format } : { $error }
+generate-failed = ⚠ Une erreur s'est produite lors de la génération des calendriers : { $error }
+generate-cancelled =
+    ⚠ Annulé après l'écriture de { $files ->
+        [one] { $files } fichier
+       *[other] { $files } fichiers
+    }.
+generate-none = ⚠ Aucun calendrier n'a été généré.
+generate-write-failures =
+    ⚠ { $calendars ->
+        [one] { $calendars } calendrier généré
+       *[other] { $calendars } calendriers générés
+    }, mais { $files ->
+        [one] { $files } fichier n'a pas pu être écrit
+       *[other] { $files } fichiers n'ont pas pu être écrits
+    }.
+generate-parse-failures =
+    ⚠ { $calendars ->
+        [one] { $calendars } calendrier généré
+       *[other] { $calendars } calendriers générés
+    }, mais { $classes ->
+        [one] { $classes } cours n'a pas pu être lu
+       *[other] { $classes } cours n'ont pas pu être lus
+    }.
+generated =
+    ☑ { $calendars ->
+        [one] { $calendars } calendrier généré
+       *[other] { $calendars } calendriers générés
+    }.
+
+## Fenêtres
+replace-title = Remplacer les fichiers existants?
+replace-description = Ces calendriers sont déjà dans le dossier de sortie :
+keep-both-hover = Écrire plutôt p. ex. « Fall 2024 - Lecture (2).ics »
+settings-window = Paramètres
+export-settings-hover = Enregistrer ces paramètres en JSON, p. ex. pour les utiliser sur un autre ordinateur
+check-for-updates = Vérifier les mises à jour au démarrage
+check-for-updates-hover = Demander à GitHub s'il y a une nouvelle version au démarrage du programme.
+timezone = Fuseau horaire :
+timezone-hover = Nom IANA du fuseau horaire des cours. Celui du profil de l'établissement par défaut.
+date-order = Ordre des dates :
+date-order-hover = Comment les dates comme 04/09/2024 sont lues. Les navigateurs dans d'autres langues que l'anglais (É.-U.) mettent habituellement le jour en premier.
+course-catalog = Catalogue des cours :
+course-catalog-hover = Un fichier CSV ou JSON des titres complets des cours par code, pour les titres tronqués dans l'horaire.
+course-catalog-filter = Catalogue des cours
+course-catalog-titles =
+    { $titles ->
+        [one] { $titles } titre
+       *[other] { $titles } titres
+    }
+
+## Saisie des données de l'horaire
+dismiss = Fermer
+institution-profile = Profil de l'établissement :
+filter-schedule = Horaire
+filter-screenshot = Capture d'écran
+filter-pdf-schedule = Horaire PDF
+filter-previous-export = Exportation précédente
+paste-from-clipboard-hover = Remplacer les données ci-dessous par le contenu du presse-papiers. Les captures d'écran sont lues avec Tesseract.
+the-screenshot = la capture d'écran
+clipboard-failed = Impossible de lire le presse-papiers : { $error }
+schedule-data-hint = Collez les données de l'horaire copiées ici, ou déposez un fichier .txt, une page .html enregistrée ou une capture d'écran sur la fenêtre.
+
+## Lecture
+reading-pdf = Lecture du PDF...
+reading-screenshot = Lecture du texte de la capture d'écran...
+remember-data-hover = Conserver les données de l'horaire collées à la fermeture du programme.
+summary-table-description = Si certains codes de matière sont introuvables, collez ici le tableau Title/Details/Hours/CRN de la page Registration Information.
+summary-table-hint = Collez le tableau récapitulatif de l'inscription ici.
+parsing = Lecture...
+detected-format = Format détecté : { $format }
+term = Session :
+all-terms = Toutes les sessions
+term-hover = Les données contiennent des cours de plusieurs sessions. Choisissez la session dont les calendriers seront générés.
+no-classes = Aucun cours n'a été trouvé.
+
+## Dates exclues et échéances
+add-preset = ➕ Congé ou semaine de lecture
+import-holidays = Importer depuis un calendrier des congés :
+add-swap-day-hover = Un jour qui suit l'horaire d'un autre jour de la semaine
+swap-day-follows = suit l'horaire du
+swap-day-schedule = {""}
+deadlines = Échéances de Canvas ou Brightspace :
+deadlines-hover = Le lien du flux de la page Calendrier de Canvas, ou l'abonnement au calendrier de Brightspace. Les travaux de vos cours sont ajoutés comme événements d'une journée.
+
+## Options de sortie
+recent-output-folders = Dossiers de sortie récents
+merge-weekdays = Fusionner les jours de la semaine en un événement
+merge-weekdays-hover = Écrire p. ex. un cours magistral du lundi et du mercredi comme un seul événement récurrent plutôt que deux.
+zip = Regrouper dans { $zip }
+zip-hover = Compresser aussi les calendriers et les exportations du dossier de sortie, p. ex. pour les envoyer par courriel.
+rename-existing = Garder les fichiers de calendrier existants
+rename-existing-hover = Écrire p. ex. « Fall 2024 - Lecture (2).ics » plutôt que de remplacer les calendriers déjà dans le dossier de sortie.
+update-previous = Mettre à jour depuis l'exportation précédente
+update-previous-hover = Comparer avec les calendriers déjà dans le dossier de sortie, pour que la réimportation ne mette à jour que les rencontres modifiées.
+
+## Résultats
+no-changes = Aucune modification depuis l'exportation précédente.
+previous-export-failed = ⚠ Impossible de lire l'exportation précédente : { $error }
+copy-diagnostic-report-hover = Copier un rapport de bogue à coller dans un ticket GitHub, sans votre nom ni vos CRN dans les données de l'horaire
+export-format-hover = Écrire { $file }
+write-failed = Impossible d'écrire { $path }
+weekly-hours = { $hours } h de cours par semaine
+classes-generated =
+    { $classes ->
+        [one] { $classes } cours généré
+       *[other] { $classes } cours générés
+    }
+files-written =
+    { $files ->
+        [one] { $files } fichier écrit
+       *[other] { $files } fichiers écrits
+    }
+merge-into-calendar-hover = Écrire une copie d'un fichier de calendrier avec ces événements ajoutés, en remplaçant les copies qui s'y trouvent déjà
+filter-calendar = Calendrier
+merged =
+    ☑ { $path } a été écrit : { $added ->
+        [one] { $added } événement ajouté
+       *[other] { $added } événements ajoutés
+    }, { $replaced } remplacés, { $kept } conservés.
+merge-failed = ⚠ Impossible de fusionner : { $error }
+
+## Fichiers
+statistics-by-course = Par cours
+statistics-by-type = Par type de cours
+not-text-file = ce n'est pas un fichier texte
+open-file = Ouvrir le fichier
+open-file-failed = Impossible d'ouvrir { $path } : { $error }
+reveal-in-folder = Afficher dans le dossier
+reveal-in-folder-failed = Impossible d'ouvrir le dossier de { $path } : { $error }
+
+## Plantages
+worker-crashed = ⚠ Une erreur s'est produite en arrière-plan. Les détails sont dans le journal.
+
+## Web
+downloaded = ☑ { $file } a été téléchargé.
+week-grid-download-png = Télécharger en PNG
+
+## Changes since the previous export
+change-added = ➕ Ajouté : { $meeting }
+change-removed = ➖ Retiré : { $meeting }
+change-changed = ✏ Modifié : { $meeting } : { $changes }
+change-meeting = { $name } ({ $time })
+change-all-day = toute la journée { $weekday } { $date }
+change-name = nom : { $from } → { $to }
+change-time = heure : { $from } → { $to }
+change-location = lieu : { $from } → { $to }
+change-dates = dates modifiées
+change-description = description modifiée
+
+## Crash
+crash-title = mycampus-calendar-rs a planté
+crash-saved =
+    mycampus-calendar-rs a rencontré un problème. Un rapport de plantage a été enregistré ici :
+    
+    { $path }
+    
+    Veuillez le joindre à un ticket GitHub.
+crash-not-saved =
+    mycampus-calendar-rs a rencontré un problème, et le rapport de plantage n'a pas pu être enregistré :
+    
+    { $report }
+
+## Regex groups
+regex-missing-group = ✖ Il manque le groupe de capture { $group }
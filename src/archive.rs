@@ -0,0 +1,141 @@
+//! Bundling the calendars and exports in the output folder into one `schedule.zip`, so they can
+//! be sent as a single attachment.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{Datelike, Local, Timelike};
+use flate2::{write::DeflateEncoder, Compression};
+
+use crate::export::ExportFormat;
+
+pub const ZIP_FILE_NAME: &str = "schedule.zip";
+
+/// Zips the `.ics` files and the exports in `output_folder`, and returns the path of the archive.
+pub fn zip_outputs(output_folder: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let output_folder = output_folder.as_ref();
+    let mut names = fs::read_dir(output_folder)?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| {
+            name.ends_with(".ics")
+                || ExportFormat::ALL
+                    .iter()
+                    .any(|format| format.file_name() == name)
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+
+    let files = names
+        .into_iter()
+        .map(|name| Ok((fs::read(output_folder.join(&name))?, name)))
+        .collect::<io::Result<Vec<_>>>()?;
+    let path = output_folder.join(ZIP_FILE_NAME);
+    fs::write(
+        &path,
+        zip(files
+            .iter()
+            .map(|(data, name)| (name.as_str(), data.as_slice())))?,
+    )?;
+    Ok(path)
+}
+
+/// A zip archive of `files`, given as names and contents.
+pub fn zip<'a>(files: impl IntoIterator<Item = (&'a str, &'a [u8])>) -> io::Result<Vec<u8>> {
+    let now = Local::now();
+    // MS-DOS format, in two second steps and years since 1980
+    let time = ((now.hour() << 11) | (now.minute() << 5) | (now.second() / 2)) as u16;
+    let date = ((((now.year() - 1980) as u32) << 9) | (now.month() << 5) | now.day()) as u16;
+
+    let mut archive = Vec::new();
+    let mut central_directory = Vec::new();
+    let mut count = 0u16;
+    for (name, data) in files {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data)?;
+        let compressed = encoder.finish()?;
+        let offset = archive.len() as u32;
+
+        // fields shared by the local header and the central directory entry, from "version
+        // needed to extract" up to the file name length
+        let mut fields = Vec::new();
+        fields.extend(20u16.to_le_bytes());
+        // UTF-8 file names
+        fields.extend((1u16 << 11).to_le_bytes());
+        // deflate
+        fields.extend(8u16.to_le_bytes());
+        fields.extend(time.to_le_bytes());
+        fields.extend(date.to_le_bytes());
+        fields.extend(crc32fast::hash(data).to_le_bytes());
+        fields.extend((compressed.len() as u32).to_le_bytes());
+        fields.extend((data.len() as u32).to_le_bytes());
+        fields.extend((name.len() as u16).to_le_bytes());
+
+        archive.extend(0x04034b50u32.to_le_bytes());
+        archive.extend(&fields);
+        // extra field length
+        archive.extend(0u16.to_le_bytes());
+        archive.extend(name.as_bytes());
+        archive.extend(&compressed);
+
+        central_directory.extend(0x02014b50u32.to_le_bytes());
+        // version made by
+        central_directory.extend(20u16.to_le_bytes());
+        central_directory.extend(&fields);
+        // extra field, comment, disk number, internal and external attributes
+        central_directory.extend([0; 12]);
+        central_directory.extend(offset.to_le_bytes());
+        central_directory.extend(name.as_bytes());
+        count += 1;
+    }
+
+    let central_directory_offset = archive.len() as u32;
+    archive.extend(&central_directory);
+    archive.extend(0x06054b50u32.to_le_bytes());
+    // disk numbers
+    archive.extend([0; 4]);
+    archive.extend(count.to_le_bytes());
+    archive.extend(count.to_le_bytes());
+    archive.extend((central_directory.len() as u32).to_le_bytes());
+    archive.extend(central_directory_offset.to_le_bytes());
+    // comment length
+    archive.extend(0u16.to_le_bytes());
+    Ok(archive)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::DeflateDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_zip_outputs() {
+        let output_folder = crate::test_folder("archive");
+        fs::write(output_folder.join("Lecture.ics"), "BEGIN:VCALENDAR").unwrap();
+        fs::write(output_folder.join("schedule.csv"), "Course,Type").unwrap();
+        fs::write(output_folder.join("notes.txt"), "not an output").unwrap();
+
+        let archive = fs::read(zip_outputs(&output_folder).unwrap()).unwrap();
+        fs::remove_dir_all(&output_folder).ok();
+
+        // the end of central directory record
+        let end = &archive[archive.len() - 22..];
+        assert_eq!(end[..4], 0x06054b50u32.to_le_bytes());
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 2);
+
+        // the first local file header
+        assert_eq!(archive[..4], 0x04034b50u32.to_le_bytes());
+        let compressed_len = u32::from_le_bytes(archive[18..22].try_into().unwrap()) as usize;
+        let name_len = u16::from_le_bytes([archive[26], archive[27]]) as usize;
+        assert_eq!(&archive[30..30 + name_len], b"Lecture.ics");
+        let data = &archive[30 + name_len..30 + name_len + compressed_len];
+        let mut contents = String::new();
+        DeflateDecoder::new(data)
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "BEGIN:VCALENDAR");
+    }
+}
@@ -0,0 +1,10 @@
+//! Running slow work without freezing the window, where that's possible.
+
+/// Runs `work` on a background thread, which sends its result back over a channel. The web build
+/// has no threads, so it runs right away there instead, and the result is already waiting.
+pub fn spawn(work: impl FnOnce() + Send + 'static) {
+    #[cfg(not(target_arch = "wasm32"))]
+    std::thread::spawn(work);
+    #[cfg(target_arch = "wasm32")]
+    work();
+}
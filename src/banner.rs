@@ -0,0 +1,291 @@
+//! Fetching the schedule from the JSON endpoints behind Banner 9's registration pages, which
+//! gives the classes directly instead of going through the text [`Parser`](crate::Parser).
+//!
+//! Signing in is left to the browser, since it goes through single sign-on. The user copies the
+//! session cookie from it, which is sent with the request.
+
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use serde::Deserialize;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    html::decode_entities,
+    parser::{merge_biweekly, ClassFailure, DateRangeKind},
+    term, Class, DateRange, InstitutionProfile, ParseError, Schedule,
+};
+
+const DATE_FORMAT: &str = "%m/%d/%Y";
+const TIME_FORMAT: &str = "%H%M";
+
+#[derive(Debug, Error)]
+pub enum BannerError {
+    #[error("Request to Banner failed: {0}")]
+    Http(#[from] ureq::Error),
+    #[error("Unexpected response from Banner: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("The session has expired or the cookie is wrong. Sign in again and copy a new one.")]
+    SignedOut,
+    #[error("No classes were found for term {0}")]
+    NoClasses(String),
+}
+
+/// A registered section, as returned by Banner.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Section {
+    course_reference_number: String,
+    subject: String,
+    course_number: String,
+    course_title: String,
+    schedule_type_description: String,
+    term_desc: Option<String>,
+    credit_hours: Option<f32>,
+    #[serde(default)]
+    faculty: Vec<Faculty>,
+    #[serde(default)]
+    meetings_faculty: Vec<MeetingFaculty>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Faculty {
+    display_name: String,
+    #[serde(default)]
+    primary_indicator: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MeetingFaculty {
+    meeting_time: MeetingTime,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MeetingTime {
+    begin_time: Option<String>,
+    end_time: Option<String>,
+    start_date: String,
+    end_date: String,
+    building: Option<String>,
+    building_description: Option<String>,
+    room: Option<String>,
+    campus_description: Option<String>,
+    #[serde(default)]
+    monday: bool,
+    #[serde(default)]
+    tuesday: bool,
+    #[serde(default)]
+    wednesday: bool,
+    #[serde(default)]
+    thursday: bool,
+    #[serde(default)]
+    friday: bool,
+    #[serde(default)]
+    saturday: bool,
+    #[serde(default)]
+    sunday: bool,
+}
+
+impl MeetingTime {
+    fn weekdays(&self) -> impl Iterator<Item = Weekday> {
+        [
+            (self.monday, Weekday::Mon),
+            (self.tuesday, Weekday::Tue),
+            (self.wednesday, Weekday::Wed),
+            (self.thursday, Weekday::Thu),
+            (self.friday, Weekday::Fri),
+            (self.saturday, Weekday::Sat),
+            (self.sunday, Weekday::Sun),
+        ]
+        .into_iter()
+        .filter_map(|(meets, weekday)| meets.then_some(weekday))
+    }
+}
+
+/// Fetches the classes registered in `term`, a Banner term code like `202409`, using the session
+/// `cookie` of a signed in browser. The cookie can be the whole `Cookie` header or just the value
+/// of `JSESSIONID`.
+pub fn fetch_schedule(
+    profile: &InstitutionProfile,
+    term: &str,
+    cookie: &str,
+) -> Result<Schedule, BannerError> {
+    let cookie = cookie.trim().trim_start_matches("Cookie:").trim();
+    let cookie = if cookie.contains('=') {
+        cookie.to_owned()
+    } else {
+        format!("JSESSIONID={cookie}")
+    };
+    let body = ureq::get(format!(
+        "{}/ssb/registrationHistory/reset",
+        profile.banner_url.trim_end_matches('/')
+    ))
+    .query("term", term.trim())
+    .header("Cookie", &cookie)
+    .header("Accept", "application/json")
+    .header("X-Requested-With", "XMLHttpRequest")
+    .call()?
+    .body_mut()
+    .read_to_string()?;
+
+    // Banner redirects to the sign in page instead of returning an error
+    if body.trim_start().starts_with('<') {
+        return Err(BannerError::SignedOut);
+    }
+    let schedule = parse_registrations(&body)?;
+    if schedule.classes.is_empty() && schedule.failures.is_empty() {
+        return Err(BannerError::NoClasses(term.trim().to_owned()));
+    }
+    Ok(schedule)
+}
+
+/// Reads the registered sections from a Banner JSON response. Sections whose meetings can't be
+/// read are listed in [`Schedule::failures`] like with the text parser.
+pub fn parse_registrations(json: &str) -> Result<Schedule, BannerError> {
+    let mut response = serde_json::from_str::<Value>(json)?;
+    // the sections are either the data itself or a list inside it, depending on the page
+    let data = response["data"].take();
+    let sections = match data {
+        Value::Array(_) => data,
+        mut data => data["registrations"].take(),
+    };
+    let sections = serde_json::from_value::<Option<Vec<Section>>>(sections)?.unwrap_or_default();
+
+    let mut schedule = Schedule::default();
+    for section in sections {
+        if schedule.term.is_empty() {
+            schedule.term = term::normalize(section.term_desc.as_deref().unwrap_or_default());
+        }
+        let heading = format!(
+            "{} | {} {}",
+            decode_entities(&section.course_title),
+            section.subject,
+            section.course_number
+        );
+        match section_class(section) {
+            Ok(class) => schedule.classes.push(class),
+            Err(error) => schedule.failures.push(ClassFailure {
+                heading,
+                error,
+                line: None,
+            }),
+        }
+    }
+    Ok(schedule)
+}
+
+fn section_class(section: Section) -> Result<Class, ParseError> {
+    let mut date_ranges = Vec::new();
+    let mut asynchronous = Vec::new();
+    for MeetingFaculty { meeting_time } in &section.meetings_faculty {
+        let start_date = parse_date(&meeting_time.start_date)?;
+        let end_date = parse_date(&meeting_time.end_date)?;
+        let (Some(begin_time), Some(end_time)) = (&meeting_time.begin_time, &meeting_time.end_time)
+        else {
+            asynchronous.push((start_date, end_date));
+            continue;
+        };
+        let start_time = parse_time(begin_time)?;
+        let end_time = parse_time(end_time)?;
+
+        // the text page shows the building code with the room number
+        let room = match (&meeting_time.building, &meeting_time.room) {
+            (Some(building), Some(room)) => Some(format!("{building} {room}")),
+            (_, room) => room.clone(),
+        };
+        for weekday in meeting_time.weekdays() {
+            date_ranges.push(DateRange {
+                start_date,
+                end_date,
+                start_time,
+                end_time,
+                weekday,
+                location: meeting_time.campus_description.clone(),
+                building: meeting_time
+                    .building_description
+                    .as_deref()
+                    .map(decode_entities),
+                room: room.clone(),
+                biweekly: false,
+                kind: DateRangeKind::Weekly,
+            });
+        }
+    }
+    date_ranges.sort_by_key(|range| (range.weekday.num_days_from_monday(), range.start_date));
+
+    let instructor = section
+        .faculty
+        .iter()
+        .find(|faculty| faculty.primary_indicator)
+        .map(|faculty| format!("{} (Primary)", faculty.display_name))
+        .or_else(|| section.faculty.first().map(|f| f.display_name.clone()));
+
+    Ok(Class {
+        name: decode_entities(&section.course_title),
+        code: format!("{} {}", section.subject, section.course_number),
+        date_ranges: merge_biweekly(date_ranges),
+        instructor: instructor.map(|instructor| format!("Instructor: {instructor}")),
+        crn: format!("CRN: {}", section.course_reference_number),
+        class_type: section.schedule_type_description,
+        credit_hours: section.credit_hours,
+        asynchronous,
+        term: String::new(),
+        meeting_url: None,
+    })
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, ParseError> {
+    NaiveDate::parse_from_str(date, DATE_FORMAT).map_err(|e| ParseError::Date(date.to_owned(), e))
+}
+
+fn parse_time(time: &str) -> Result<NaiveTime, ParseError> {
+    NaiveTime::parse_from_str(time, TIME_FORMAT).map_err(|e| ParseError::Time(time.to_owned(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    #[test]
+    fn test_parse_registrations() {
+        let schedule = parse_registrations(include_str!("../tests/data/banner.json")).unwrap();
+        let want = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+
+        assert!(schedule.failures.is_empty());
+        assert_eq!(schedule.term, want.term);
+        assert_eq!(
+            serde_json::to_value(&schedule.classes).unwrap(),
+            serde_json::to_value(&want.classes).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_bad_meeting() {
+        let schedule = parse_registrations(
+            r#"{"success": true, "data": [{
+                "courseReferenceNumber": "40001",
+                "subject": "CSCI",
+                "courseNumber": "1200U",
+                "courseTitle": "Discrete Mathematics",
+                "scheduleTypeDescription": "Lecture",
+                "meetingsFaculty": [{"meetingTime": {
+                    "beginTime": "1110", "endTime": "1230",
+                    "startDate": "2024-09-04", "endDate": "12/03/2024", "monday": true
+                }}]
+            }]}"#,
+        )
+        .unwrap();
+
+        assert!(schedule.classes.is_empty());
+        assert_eq!(
+            schedule.failures[0].heading,
+            "Discrete Mathematics | CSCI 1200U"
+        );
+        assert!(matches!(schedule.failures[0].error, ParseError::Date(..)));
+    }
+}
@@ -0,0 +1,104 @@
+use std::{
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use eframe::egui::{self, Button, Grid, TextEdit};
+use mycampus_calendar_rs::{
+    banner::{self, BannerError},
+    InstitutionProfile, Schedule,
+};
+
+use crate::{
+    crash,
+    i18n::{tr, tr_args},
+};
+
+/// Fetching the classes straight from Banner with a session cookie. The request runs on a
+/// background thread.
+#[derive(Default)]
+pub struct BannerFetch {
+    /// Banner term code, eg. `202409` for Fall 2024.
+    pub term: String,
+    cookie: String,
+    status: Option<String>,
+    pending: Option<Receiver<Result<Schedule, BannerError>>>,
+}
+
+impl BannerFetch {
+    pub fn new(term: String) -> Self {
+        Self {
+            term,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the schedule once it's been fetched.
+    pub fn ui(&mut self, ui: &mut egui::Ui, profile: &InstitutionProfile) -> Option<Schedule> {
+        let mut schedule = None;
+        if let Some(result) = crash::try_recv(&mut self.pending, &mut self.status) {
+            self.pending = None;
+            self.status = Some(match result {
+                Ok(fetched) => {
+                    let status = tr_args(
+                        "banner-fetched",
+                        &[("classes", fetched.classes.len().into())],
+                    );
+                    schedule = Some(fetched);
+                    status
+                }
+                Err(e) => format!("⚠ {e}"),
+            });
+        }
+        let busy = self.pending.is_some();
+
+        ui.label(tr("banner-description"));
+        Grid::new("banner_fetch").num_columns(2).show(ui, |ui| {
+            ui.label(tr("banner-term-code"));
+            ui.add(
+                TextEdit::singleline(&mut self.term)
+                    .hint_text(tr("banner-term-code-hint"))
+                    .desired_width(200.0),
+            );
+            ui.end_row();
+            ui.label(tr("banner-session-cookie"));
+            ui.add(
+                TextEdit::singleline(&mut self.cookie)
+                    .password(true)
+                    .desired_width(200.0),
+            );
+            ui.end_row();
+        });
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !busy && !self.term.trim().is_empty() && !self.cookie.trim().is_empty(),
+                    Button::new(tr("banner-fetch")),
+                )
+                .on_hover_text(tr("banner-fetch-hover"))
+                .clicked()
+            {
+                self.fetch(ui.ctx().clone(), profile.clone());
+            }
+            if busy {
+                ui.spinner();
+            }
+            if let Some(status) = &self.status {
+                ui.label(status);
+            }
+        });
+        schedule
+    }
+
+    fn fetch(&mut self, ctx: egui::Context, profile: InstitutionProfile) {
+        let (tx, rx) = mpsc::channel();
+        let (term, cookie) = (self.term.clone(), self.cookie.clone());
+        thread::spawn(move || {
+            tx.send(banner::fetch_schedule(&profile, &term, &cookie))
+                .ok();
+            ctx.request_repaint();
+        });
+        self.status = Some(tr("banner-fetching"));
+        self.pending = Some(rx);
+    }
+}
@@ -0,0 +1,131 @@
+//! Generating calendars for a folder of saved schedules at once, eg. for each member of a club.
+
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDate;
+use thiserror::Error;
+
+use crate::{write_calendars, GenerateOptions, InstitutionProfile, ParseError, Parser, Report};
+
+#[derive(Debug, Error)]
+pub enum BatchError {
+    #[error("Failed to read or write files: {0}")]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+}
+
+/// What happened with one schedule file.
+#[derive(Debug)]
+pub struct BatchResult {
+    pub input: PathBuf,
+    /// The subfolder its calendars were written to.
+    pub output_folder: PathBuf,
+    pub report: Result<Report, BatchError>,
+}
+
+/// The `.txt` files in `folder`, sorted by name.
+pub fn schedule_files(folder: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+    let mut files = fs::read_dir(folder)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .filter(|path| {
+            path.as_ref().map_or(true, |path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .is_some_and(|extension| extension.eq_ignore_ascii_case("txt"))
+            })
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    files.sort();
+    Ok(files)
+}
+
+/// Generates calendars for each `.txt` schedule in `input_folder`, into a subfolder of
+/// `output_folder` named after the file, eg. `alice.txt` into `output_folder/alice`. A file that
+/// can't be parsed doesn't stop the others.
+pub fn generate_batch(
+    input_folder: impl AsRef<Path>,
+    output_folder: impl AsRef<Path>,
+    exdate: &HashSet<NaiveDate>,
+    profile: &InstitutionProfile,
+    options: &GenerateOptions,
+) -> Result<Vec<BatchResult>, BatchError> {
+    let parser = Parser::from_profile(profile).map_err(ParseError::from)?;
+    Ok(schedule_files(input_folder)?
+        .into_iter()
+        .map(|input| {
+            let stem = input.file_stem().unwrap_or_default();
+            let output_folder = output_folder.as_ref().join(stem);
+            let report = fs::read_to_string(&input)
+                .map_err(BatchError::from)
+                .and_then(|data| Ok(parser.parse_data(&data)?))
+                .and_then(|schedule| {
+                    fs::create_dir_all(&output_folder)?;
+                    Ok(write_calendars(
+                        &output_folder,
+                        &schedule,
+                        exdate.clone(),
+                        profile,
+                        options,
+                    ))
+                });
+            BatchResult {
+                input,
+                output_folder,
+                report,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_batch() {
+        let folder = crate::test_folder("batch");
+        let (input, output) = (folder.join("input"), folder.join("output"));
+        fs::create_dir_all(&input).unwrap();
+        fs::write(
+            input.join("bob.txt"),
+            include_str!("../tests/data/firefox.txt"),
+        )
+        .unwrap();
+        fs::write(
+            input.join("alice.TXT"),
+            include_str!("../tests/data/chromium.txt"),
+        )
+        .unwrap();
+        fs::write(input.join("notes.md"), "not a schedule").unwrap();
+        fs::write(input.join("empty.txt"), "").unwrap();
+
+        let results = generate_batch(
+            &input,
+            &output,
+            &HashSet::new(),
+            &InstitutionProfile::default(),
+            &GenerateOptions::default(),
+        )
+        .unwrap();
+        let lecture_exists =
+            |name: &str| output.join(name).join("Fall 2024 - Lecture.ics").is_file();
+        let (alice, bob) = (lecture_exists("alice"), lecture_exists("bob"));
+        fs::remove_dir_all(&folder).ok();
+
+        let names = results
+            .iter()
+            .map(|result| result.input.file_name().unwrap().to_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["alice.TXT", "bob.txt", "empty.txt"]);
+        assert!(results[0].report.is_ok());
+        assert!(results[1].report.is_ok());
+        assert!(results[2].report.is_err());
+        assert!(alice && bob);
+    }
+}
@@ -0,0 +1,113 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver},
+        Arc,
+    },
+    thread,
+};
+
+use eframe::egui::{self, Button, TextEdit};
+use mycampus_calendar_rs::{
+    webdriver::{self, FetchError},
+    InstitutionProfile,
+};
+
+use crate::{crash, i18n::tr};
+
+/// Fetching the schedule data by opening a browser through WebDriver. The browser is driven from
+/// a background thread while the user signs in.
+pub struct BrowserFetch {
+    pub webdriver_url: String,
+    status: Option<String>,
+    cancelled: Arc<AtomicBool>,
+    pending: Option<Receiver<Result<String, FetchError>>>,
+}
+
+impl Default for BrowserFetch {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl BrowserFetch {
+    pub fn new(webdriver_url: Option<String>) -> Self {
+        Self {
+            webdriver_url: webdriver_url
+                .unwrap_or_else(|| webdriver::DEFAULT_WEBDRIVER_URL.to_owned()),
+            status: None,
+            cancelled: Default::default(),
+            pending: None,
+        }
+    }
+
+    /// Returns the schedule data once it's been fetched.
+    pub fn ui(&mut self, ui: &mut egui::Ui, profile: &InstitutionProfile) -> Option<String> {
+        let mut data = None;
+        if let Some(result) = crash::try_recv(&mut self.pending, &mut self.status) {
+            self.pending = None;
+            self.status = match result {
+                Ok(text) => {
+                    data = Some(text);
+                    Some(tr("browser-fetched"))
+                }
+                Err(FetchError::Cancelled) => None,
+                Err(e) => Some(format!("⚠ {e}")),
+            };
+        }
+
+        ui.label(tr("browser-description"));
+        ui.horizontal(|ui| {
+            ui.label(tr("browser-webdriver-url"));
+            ui.add_enabled(
+                self.pending.is_none(),
+                TextEdit::singleline(&mut self.webdriver_url)
+                    .hint_text(webdriver::DEFAULT_WEBDRIVER_URL)
+                    .desired_width(200.0),
+            );
+        });
+        ui.horizontal(|ui| {
+            if self.pending.is_some() {
+                if ui
+                    .add_enabled(
+                        !self.cancelled.load(Ordering::Relaxed),
+                        Button::new(tr("cancel")),
+                    )
+                    .clicked()
+                {
+                    self.cancelled.store(true, Ordering::Relaxed);
+                }
+                ui.spinner();
+            } else if ui
+                .add_enabled(
+                    !self.webdriver_url.trim().is_empty(),
+                    Button::new(tr("browser-open")),
+                )
+                .clicked()
+            {
+                self.fetch(ui.ctx().clone(), profile.clone());
+            }
+            if let Some(status) = &self.status {
+                ui.label(status);
+            }
+        });
+        data
+    }
+
+    fn fetch(&mut self, ctx: egui::Context, profile: InstitutionProfile) {
+        let (tx, rx) = mpsc::channel();
+        self.cancelled = Arc::new(AtomicBool::new(false));
+        let (webdriver_url, cancelled) = (self.webdriver_url.clone(), self.cancelled.clone());
+        thread::spawn(move || {
+            tx.send(webdriver::fetch_schedule(
+                &webdriver_url,
+                &profile,
+                &cancelled,
+            ))
+            .ok();
+            ctx.request_repaint();
+        });
+        self.status = Some(tr("browser-waiting"));
+        self.pending = Some(rx);
+    }
+}
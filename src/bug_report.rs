@@ -0,0 +1,105 @@
+//! A bug report to paste into a GitHub issue, with the schedule data that failed to parse and the
+//! student's personal details and CRNs taken out of it.
+
+use std::{collections::HashMap, fmt::Write};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::sanitize::Sanitizer;
+
+/// CRNs are replaced with numbers counting up from this.
+const FIRST_CRN: u32 = 10001;
+
+/// CRNs on their own lines, eg. "CRN: 40001", and in the summary table's CRN column.
+static CRN_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"CRN:?\s*(\d{5})\b|\t(\d{5})\t").unwrap());
+
+/// The schedule data sanitized with `sanitizer`, and with the CRNs replaced. The CRNs are
+/// renumbered in the order they appear instead of being blanked out, so the data can still be
+/// parsed, and a class's CRN matches between the summary table and its details.
+pub fn redact(data: &str, sanitizer: &Sanitizer) -> String {
+    let mut crns = HashMap::new();
+    for caps in CRN_RE.captures_iter(data) {
+        let crn = caps.get(1).or_else(|| caps.get(2)).unwrap().as_str();
+        let next = FIRST_CRN + crns.len() as u32;
+        crns.entry(crn).or_insert(next);
+    }
+
+    let redacted = sanitizer.sanitize(data);
+    if crns.is_empty() {
+        return redacted;
+    }
+    let crn_re = Regex::new(&format!(
+        r"\b({})\b",
+        crns.keys().copied().collect::<Vec<_>>().join("|")
+    ))
+    .unwrap();
+    crn_re
+        .replace_all(&redacted, |caps: &regex::Captures| {
+            crns[&caps[1]].to_string()
+        })
+        .into_owned()
+}
+
+/// A GitHub issue body describing `problem`, with the program's `version`, the platform, and the
+/// redacted schedule data.
+pub fn bug_report(version: &str, problem: &str, data: &str) -> String {
+    let sanitizer = Sanitizer::new(data);
+    let mut report = String::new();
+    writeln!(
+        report,
+        "### What happened\n\n{}\n",
+        sanitizer.sanitize(problem.trim())
+    )
+    .ok();
+    writeln!(
+        report,
+        "### Version\n\nmycampus-calendar-rs {version} on {} ({})\n",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )
+    .ok();
+    writeln!(
+        report,
+        "### Schedule data\n\nThe student's name, student number, email addresses, and CRNs \
+        were replaced.\n\n<details>\n\
+        <summary>Pasted data</summary>\n\n```\n{}\n```\n\n</details>",
+        redact(data, &sanitizer).trim_end()
+    )
+    .ok();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    #[test]
+    fn test_redact() {
+        let data = include_str!("../tests/data/chromium.txt");
+        let redacted = redact(data, &Sanitizer::new(data));
+        assert!(!redacted.contains("Jane Student"));
+        assert!(redacted.contains("Student Schedule\nStudent Name\n"));
+        assert!(!redacted.contains("40001"));
+        assert!(redacted.contains("CSCI 1200U, 001\t3\t10001\tLecture"));
+        assert!(redacted.contains("CRN: 10001\n"));
+
+        let schedule = Parser::new().parse_data(&redacted).unwrap();
+        assert_eq!(schedule.classes.len(), 3);
+        assert_eq!(schedule.classes[1].crn, "CRN: 10002");
+        assert_eq!(schedule.classes[1].code, "CSCI 1060U");
+    }
+
+    #[test]
+    fn test_bug_report() {
+        let report = bug_report(
+            "1.2.3",
+            "Failed to find start of schedule",
+            include_str!("../tests/data/registration_information.txt"),
+        );
+        assert!(report.starts_with("### What happened\n\nFailed to find start of schedule\n"));
+        assert!(report.contains("mycampus-calendar-rs 1.2.3 on "));
+        assert!(!report.contains("Jane Student"));
+    }
+}
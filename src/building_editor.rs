@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+
+use eframe::egui::{self, Grid, TextEdit};
+use mycampus_calendar_rs::profile::Building;
+
+use crate::i18n::tr;
+
+/// Editor for the institution profile's building codes and campus aliases, which are used for the
+/// event locations. Returns true if anything was changed.
+pub fn building_editor(
+    ui: &mut egui::Ui,
+    buildings: &mut BTreeMap<String, Building>,
+    campus_aliases: &mut BTreeMap<String, String>,
+) -> bool {
+    let mut changed = false;
+
+    ui.label(tr("buildings-description"));
+    ui.add_space(6.0);
+
+    // the codes are the map keys, so edit a copy and rebuild the map if anything changed
+    let mut rows = buildings
+        .iter()
+        .map(|(code, building)| {
+            (
+                code.clone(),
+                building.name.clone(),
+                building.campus.clone().unwrap_or_default(),
+                // coordinates can only be set in the profile file
+                building.geo,
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut rows_changed = false;
+    if !rows.is_empty() {
+        Grid::new("buildings")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong(tr("buildings-code"));
+                ui.strong(tr("buildings-building"));
+                ui.strong(tr("buildings-campus"));
+                ui.end_row();
+
+                rows.retain_mut(|(code, name, campus, _)| {
+                    rows_changed |= ui
+                        .add(TextEdit::singleline(code).desired_width(50.0))
+                        .changed();
+                    rows_changed |= ui
+                        .add(TextEdit::singleline(name).desired_width(250.0))
+                        .changed();
+                    rows_changed |= ui
+                        .add(TextEdit::singleline(campus).desired_width(120.0))
+                        .changed();
+                    let should_delete = ui.button("❌").clicked();
+                    ui.end_row();
+
+                    rows_changed |= should_delete;
+                    !should_delete
+                });
+            });
+        ui.add_space(6.0);
+    }
+    if ui.button(tr("buildings-add")).clicked() {
+        rows.push(Default::default());
+        rows_changed = true;
+    }
+    if rows_changed {
+        *buildings = rows
+            .into_iter()
+            .map(|(code, name, campus, geo)| {
+                let campus = (!campus.is_empty()).then_some(campus);
+                (code, Building { name, campus, geo })
+            })
+            .collect();
+        changed = true;
+    }
+
+    ui.add_space(6.0);
+    ui.strong(tr("buildings-campus-names"));
+    ui.label(tr("buildings-campus-names-description"));
+    let mut aliases = campus_aliases
+        .iter()
+        .map(|(campus, alias)| (campus.clone(), alias.clone()))
+        .collect::<Vec<_>>();
+    let mut aliases_changed = false;
+    if !aliases.is_empty() {
+        Grid::new("campus_aliases")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                aliases.retain_mut(|(campus, alias)| {
+                    aliases_changed |= ui
+                        .add(TextEdit::singleline(campus).desired_width(150.0))
+                        .changed();
+                    aliases_changed |= ui
+                        .add(TextEdit::singleline(alias).desired_width(150.0))
+                        .changed();
+                    let should_delete = ui.button("❌").clicked();
+                    ui.end_row();
+
+                    aliases_changed |= should_delete;
+                    !should_delete
+                });
+            });
+        ui.add_space(6.0);
+    }
+    if ui.button(tr("buildings-add-campus")).clicked() {
+        aliases.push(Default::default());
+        aliases_changed = true;
+    }
+    if aliases_changed {
+        *campus_aliases = aliases.into_iter().collect();
+        changed = true;
+    }
+
+    changed
+}
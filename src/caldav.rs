@@ -0,0 +1,158 @@
+//! Uploading the events straight to a CalDAV calendar, eg. on Nextcloud, iCloud or Fastmail.
+//!
+//! Each event is its own calendar resource named after its stable UID, so uploading again
+//! replaces the events instead of adding duplicates. The password is kept in the system keyring.
+
+use std::{collections::HashSet, io};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    http::{percent_encode, KEYRING_SERVICE},
+    parser::{default_sequence, finish_calendar, meetings, write_event, GenerateOptions, Meeting},
+    InstitutionProfile, Schedule,
+};
+
+#[derive(Debug, Error)]
+pub enum CalDavError {
+    #[error("Request to the CalDAV server failed: {0}")]
+    Http(#[from] ureq::Error),
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("Failed to access the system keyring: {0}")]
+    Keyring(#[from] keyring::Error),
+    #[error("No password was entered or saved")]
+    NoPassword,
+}
+
+/// A calendar collection on a CalDAV server.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CalDavServer {
+    /// The URL of the calendar itself, eg.
+    /// `https://cloud.example.com/remote.php/dav/calendars/user/personal/`.
+    pub url: String,
+    pub username: String,
+}
+
+impl CalDavServer {
+    fn keyring_entry(&self) -> Result<keyring::Entry, CalDavError> {
+        Ok(keyring::Entry::new(
+            KEYRING_SERVICE,
+            &format!("caldav:{}@{}", self.username, self.url),
+        )?)
+    }
+
+    /// The password saved by a previous upload.
+    pub fn saved_password(&self) -> Result<Option<String>, CalDavError> {
+        match self.keyring_entry()?.get_password() {
+            Ok(password) => Ok(Some(password)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Forgets the saved password.
+    pub fn forget_password(&self) -> Result<(), CalDavError> {
+        match self.keyring_entry()?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Puts every event of `schedule` into the calendar, and returns how many there were. Uses
+    /// the saved password if `password` is empty, and otherwise saves it once an upload works. The
+    /// other arguments are used the same way as by [`write_calendars`](crate::write_calendars).
+    pub fn upload(
+        &self,
+        password: &str,
+        schedule: &Schedule,
+        exdate: &HashSet<NaiveDate>,
+        profile: &InstitutionProfile,
+        options: &GenerateOptions,
+    ) -> Result<usize, CalDavError> {
+        let password = match password {
+            "" => self.saved_password()?.ok_or(CalDavError::NoPassword)?,
+            password => password.to_owned(),
+        };
+        let authorization = format!(
+            "Basic {}",
+            STANDARD.encode(format!("{}:{password}", self.username))
+        );
+
+        let mut meetings = meetings(schedule, profile, options);
+        let sequence = default_sequence();
+        for meeting in &mut meetings {
+            let calendar = event_calendar(meeting, exdate, profile, options, sequence);
+            ureq::put(self.resource_url(&meeting.uid))
+                .header("Authorization", &authorization)
+                .header("Content-Type", "text/calendar; charset=utf-8")
+                .send(calendar)?;
+        }
+        self.keyring_entry()?.set_password(&password)?;
+        Ok(meetings.len())
+    }
+
+    fn resource_url(&self, uid: &str) -> String {
+        format!(
+            "{}/{}.ics",
+            self.url.trim_end_matches('/'),
+            percent_encode(uid)
+        )
+    }
+}
+
+/// A calendar with only the event for `meeting`, as CalDAV stores one event per resource.
+fn event_calendar(
+    meeting: &mut Meeting,
+    exdate: &HashSet<NaiveDate>,
+    profile: &InstitutionProfile,
+    options: &GenerateOptions,
+    sequence: i64,
+) -> String {
+    let mut calendar = String::from(
+        "BEGIN:VCALENDAR\nVERSION:2.0\nPRODID:MYCAMPUS-CALENDAR-RS\nCALSCALE:GREGORIAN\n",
+    );
+    if let Some(vtimezone) = profile.vtimezone() {
+        calendar.push_str(vtimezone);
+    }
+    meeting.event.sequence = sequence;
+    write_event(&mut calendar, meeting, exdate, options, &profile.timezone);
+    finish_calendar(&mut calendar);
+    calendar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    #[test]
+    fn test_event_calendar() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let profile = InstitutionProfile::default();
+        let options = GenerateOptions::default();
+        let mut meetings = meetings(&schedule, &profile, &options);
+        let uid = meetings[0].uid.clone();
+
+        let calendar = event_calendar(&mut meetings[0], &HashSet::new(), &profile, &options, 7);
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR"));
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 1);
+        assert!(calendar.contains(&format!("UID:{uid}\r\n")));
+        assert!(calendar.contains("SEQUENCE:7\r\n"));
+
+        let server = CalDavServer {
+            url: "https://dav.example.com/calendars/me/school/".to_owned(),
+            username: "me".to_owned(),
+        };
+        assert_eq!(
+            server.resource_url("fall-2024-csci-1200u@mycampus-calendar-rs"),
+            "https://dav.example.com/calendars/me/school/fall-2024-csci-1200u%40mycampus-calendar-rs.ics"
+        );
+    }
+}
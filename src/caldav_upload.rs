@@ -0,0 +1,120 @@
+use std::{
+    collections::HashSet,
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use chrono::NaiveDate;
+use eframe::egui::{self, Button, Grid, TextEdit};
+use mycampus_calendar_rs::{
+    caldav::CalDavServer, parser::Schedule, GenerateOptions, InstitutionProfile,
+};
+
+use crate::{
+    crash,
+    i18n::{tr, tr_args},
+};
+
+/// Uploading the events to a CalDAV calendar. The requests run on a background thread.
+#[derive(Default)]
+pub struct CalDavUpload {
+    pub server: CalDavServer,
+    password: String,
+    status: Option<String>,
+    pending: Option<Receiver<Result<usize, String>>>,
+}
+
+impl CalDavUpload {
+    pub fn new(server: CalDavServer) -> Self {
+        Self {
+            server,
+            ..Default::default()
+        }
+    }
+
+    /// Returns true if the events should be uploaded with [`CalDavUpload::upload`].
+    pub fn ui(&mut self, ui: &mut egui::Ui, can_upload: bool) -> bool {
+        if let Some(result) = crash::try_recv(&mut self.pending, &mut self.status) {
+            self.pending = None;
+            self.status = Some(match result {
+                Ok(n) => {
+                    self.password.clear();
+                    tr_args("caldav-uploaded", &[("events", n.into())])
+                }
+                Err(e) => format!("⚠ {e}"),
+            });
+        }
+        let busy = self.pending.is_some();
+
+        ui.label(tr("caldav-description"));
+        Grid::new("caldav_server").num_columns(2).show(ui, |ui| {
+            ui.label(tr("caldav-url"));
+            ui.add(
+                TextEdit::singleline(&mut self.server.url)
+                    .hint_text("https://cloud.example.com/remote.php/dav/calendars/me/personal/")
+                    .desired_width(300.0),
+            );
+            ui.end_row();
+            ui.label(tr("caldav-username"));
+            ui.add(TextEdit::singleline(&mut self.server.username).desired_width(300.0));
+            ui.end_row();
+            ui.label(tr("caldav-password"));
+            ui.add(
+                TextEdit::singleline(&mut self.password)
+                    .password(true)
+                    .hint_text(tr("caldav-password-hint"))
+                    .desired_width(300.0),
+            );
+            ui.end_row();
+        });
+
+        let mut upload = false;
+        ui.horizontal(|ui| {
+            upload = ui
+                .add_enabled(
+                    !busy && can_upload && !self.server.url.is_empty(),
+                    Button::new(tr("caldav-upload")),
+                )
+                .on_hover_text(tr("caldav-upload-hover"))
+                .clicked();
+            if ui
+                .add_enabled(!busy, Button::new(tr("caldav-forget-password")))
+                .clicked()
+            {
+                self.status = self
+                    .server
+                    .forget_password()
+                    .err()
+                    .map(|e| format!("⚠ {e}"));
+            }
+            if busy {
+                ui.spinner();
+            }
+            if let Some(status) = &self.status {
+                ui.label(status);
+            }
+        });
+        upload
+    }
+
+    pub fn upload(
+        &mut self,
+        ctx: egui::Context,
+        schedule: Schedule,
+        exdate: HashSet<NaiveDate>,
+        profile: InstitutionProfile,
+        options: GenerateOptions,
+    ) {
+        let (tx, rx) = mpsc::channel();
+        let (server, password) = (self.server.clone(), self.password.clone());
+        thread::spawn(move || {
+            let result = server
+                .upload(&password, &schedule, &exdate, &profile, &options)
+                .map_err(|e| e.to_string());
+            tx.send(result).ok();
+            ctx.request_repaint();
+        });
+        self.status = Some(tr("caldav-uploading"));
+        self.pending = Some(rx);
+    }
+}
@@ -0,0 +1,152 @@
+//! Full course titles from a course catalog, since the schedule cuts long ones off, eg.
+//! "Discrete Mathm. for Comp. Scie".
+
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CatalogError {
+    #[error("Failed to read course catalog: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid course catalog CSV: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("Invalid course catalog JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("No courses were found in the course catalog")]
+    Empty,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonCatalog {
+    /// `{"CSCI 1200U": "Discrete Mathematics for Computer Science"}`
+    Map(BTreeMap<String, String>),
+    /// `[{"code": "CSCI 1200U", "title": "Discrete Mathematics for Computer Science"}]`
+    List(Vec<JsonCourse>),
+}
+
+#[derive(Deserialize)]
+struct JsonCourse {
+    code: String,
+    #[serde(alias = "name")]
+    title: String,
+}
+
+/// Reads the full titles by course code from a `.json` or `.csv` catalog. See [`parse_csv`] and
+/// [`parse_json`].
+pub fn load(path: impl AsRef<Path>) -> Result<BTreeMap<String, String>, CatalogError> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)?;
+    let is_json = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+    if is_json {
+        parse_json(&contents)
+    } else {
+        parse_csv(&contents)
+    }
+}
+
+/// Reads a CSV with a header row. The codes and titles are taken from the columns with "code" and
+/// "title" (or "name") in their headers, or else the first two.
+pub fn parse_csv(contents: &str) -> Result<BTreeMap<String, String>, CatalogError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+    let headers = reader
+        .headers()?
+        .iter()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    let column = |names: &[&str], default| {
+        headers
+            .iter()
+            .position(|header| names.iter().any(|name| header.contains(name)))
+            .unwrap_or(default)
+    };
+    let (code, title) = (column(&["code"], 0), column(&["title", "name"], 1));
+
+    let mut titles = BTreeMap::new();
+    for record in reader.records() {
+        let record = record?;
+        if let (Some(code), Some(title)) = (record.get(code), record.get(title)) {
+            titles.insert(code.to_owned(), title.to_owned());
+        }
+    }
+    finish(titles)
+}
+
+/// Reads a JSON object of titles by code, or a list of objects with `code` and `title` fields.
+pub fn parse_json(contents: &str) -> Result<BTreeMap<String, String>, CatalogError> {
+    let titles = match serde_json::from_str(contents)? {
+        JsonCatalog::Map(titles) => titles,
+        JsonCatalog::List(courses) => courses
+            .into_iter()
+            .map(|course| (course.code, course.title))
+            .collect(),
+    };
+    finish(titles)
+}
+
+/// Normalizes the codes and drops empty titles.
+fn finish(titles: BTreeMap<String, String>) -> Result<BTreeMap<String, String>, CatalogError> {
+    let titles = titles
+        .into_iter()
+        .map(|(code, title)| (normalize_code(&code), title.trim().to_owned()))
+        .filter(|(code, title)| !code.is_empty() && !title.is_empty())
+        .collect::<BTreeMap<_, _>>();
+    if titles.is_empty() {
+        return Err(CatalogError::Empty);
+    }
+    Ok(titles)
+}
+
+/// The full title of the course with `code` in `titles`, if it's there. Codes match regardless of
+/// case and spacing, so "CSCI 1200U" finds "csci1200u".
+pub fn full_title<'a>(titles: &'a BTreeMap<String, String>, code: &str) -> Option<&'a str> {
+    titles.get(&normalize_code(code)).map(String::as_str)
+}
+
+pub(crate) fn normalize_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_csv() {
+        let titles = parse_csv(indoc! {"
+            Subject,Course Code,Title,Credits
+            CSCI,CSCI 1200U,Discrete Mathematics for Computer Science,3
+            CSCI,CSCI 1060U,Programming Workshop I,3
+            CSCI,,,
+        "})
+        .unwrap();
+        assert_eq!(titles.len(), 2);
+        assert_eq!(
+            full_title(&titles, "csci 1200u"),
+            Some("Discrete Mathematics for Computer Science")
+        );
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let map =
+            parse_json(r#"{"CSCI 1200U": "Discrete Mathematics for Computer Science"}"#).unwrap();
+        let list = parse_json(indoc! {r#"
+            [{"code": "CSCI1200U", "title": "Discrete Mathematics for Computer Science"}]
+        "#})
+        .unwrap();
+        assert_eq!(map, list);
+        assert!(matches!(parse_json("[]"), Err(CatalogError::Empty)));
+    }
+}
@@ -0,0 +1,102 @@
+//! Generating without opening the window, with the default settings:
+//! `mycampus-calendar-rs <schedule.txt or folder of them> <output folder>`.
+
+use std::{collections::HashSet, ffi::OsString, fs, path::Path};
+
+use mycampus_calendar_rs::{
+    batch::{self, BatchResult},
+    GenerateOptions, InstitutionProfile, Report,
+};
+
+const USAGE: &str = "\
+Usage: mycampus-calendar-rs [<schedule> <output folder>]
+
+With no arguments, the window is opened.
+
+<schedule> is a text file of copied schedule data, or a folder of them. For a folder, the calendars
+for each .txt file are written to a subfolder of <output folder> named after it.";
+
+/// Runs with the command line arguments (not including the program), returning the exit code.
+pub fn run(args: &[OsString]) -> i32 {
+    let [input, output] = args else {
+        if args
+            .first()
+            .is_some_and(|arg| arg == "-h" || arg == "--help")
+        {
+            println!("{USAGE}");
+            return 0;
+        }
+        eprintln!("{USAGE}");
+        return 2;
+    };
+    let (input, output) = (Path::new(input), Path::new(output));
+    let profile = InstitutionProfile::default();
+    let options = GenerateOptions::default();
+
+    let results = if input.is_dir() {
+        match batch::generate_batch(input, output, &HashSet::new(), &profile, &options) {
+            Ok(results) if results.is_empty() => {
+                eprintln!("No .txt files in {}", input.display());
+                return 1;
+            }
+            Ok(results) => results,
+            Err(e) => {
+                eprintln!("{e}");
+                return 1;
+            }
+        }
+    } else {
+        let report = fs::read_to_string(input)
+            .map_err(batch::BatchError::from)
+            .and_then(|data| {
+                fs::create_dir_all(output)?;
+                Ok(mycampus_calendar_rs::generate(
+                    output,
+                    &data,
+                    HashSet::new(),
+                )?)
+            });
+        vec![BatchResult {
+            input: input.to_owned(),
+            output_folder: output.to_owned(),
+            report,
+        }]
+    };
+
+    let mut failed = false;
+    for result in &results {
+        match &result.report {
+            Ok(report) => {
+                failed |= !report.write_failures.is_empty();
+                print_report(result, report);
+            }
+            Err(e) => {
+                failed = true;
+                eprintln!("{}: {e}", result.input.display());
+            }
+        }
+    }
+    i32::from(failed)
+}
+
+fn print_report(result: &BatchResult, report: &Report) {
+    println!(
+        "{}: {} calendar(s) in {}",
+        result.input.display(),
+        report.calendars,
+        result.output_folder.display()
+    );
+    for failure in &report.failed {
+        println!("  ⚠ Skipped {}: {}", failure.heading, failure.error);
+    }
+    for warning in &report.warnings {
+        println!("  ⚠ {warning}");
+    }
+    for failure in &report.write_failures {
+        eprintln!(
+            "  ✖ Failed to write {}: {}",
+            failure.path.display(),
+            failure.error
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use eframe::egui::{self, Button, ComboBox, TextEdit};
+use serde::{Deserialize, Serialize};
+
+use crate::{i18n::tr, settings::Settings};
+
+/// A named set of settings to switch between, eg. "My schedule" and "Partner's schedule".
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub settings: Settings,
+    pub output_folder: Option<PathBuf>,
+    pub custom_subjects: Vec<(String, String)>,
+}
+
+/// The saved configurations. The active one is whatever's currently set in the app, so it's only
+/// saved here when switching away from it or closing the app.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Configs {
+    /// Empty if the current settings haven't been given a name.
+    pub active: String,
+    pub saved: BTreeMap<String, Config>,
+    #[serde(skip)]
+    new_name: String,
+}
+
+pub enum ConfigAction {
+    Switch(String),
+    SaveAs(String),
+    Delete,
+}
+
+/// The configuration dropdown, with buttons to save the current settings under a new name and to
+/// delete the active configuration.
+pub fn configs_bar(ui: &mut egui::Ui, configs: &mut Configs) -> Option<ConfigAction> {
+    let mut action = None;
+    ui.horizontal(|ui| {
+        ui.label(tr("configs-label"));
+        let selected_text = if configs.active.is_empty() {
+            tr("configs-unsaved")
+        } else {
+            configs.active.clone()
+        };
+        ComboBox::from_id_source("configs")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                for name in configs.saved.keys() {
+                    if ui.selectable_label(*name == configs.active, name).clicked()
+                        && *name != configs.active
+                    {
+                        action = Some(ConfigAction::Switch(name.clone()));
+                    }
+                }
+            })
+            .response
+            .on_hover_text(tr("configs-hover"));
+
+        ui.add(
+            TextEdit::singleline(&mut configs.new_name)
+                .hint_text(tr("configs-name-hint"))
+                .desired_width(160.0),
+        );
+        let new_name = configs.new_name.trim();
+        if ui
+            .add_enabled(!new_name.is_empty(), Button::new(tr("configs-save-as")))
+            .clicked()
+        {
+            action = Some(ConfigAction::SaveAs(new_name.to_owned()));
+            configs.new_name.clear();
+        }
+        if ui
+            .add_enabled(
+                !configs.active.is_empty(),
+                Button::new(tr("configs-delete")),
+            )
+            .on_hover_text(tr("configs-delete-hover"))
+            .clicked()
+        {
+            action = Some(ConfigAction::Delete);
+        }
+    });
+    action
+}
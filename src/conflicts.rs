@@ -0,0 +1,224 @@
+//! Finding classes with overlapping meetings, eg. from a registration mistake, and back-to-back
+//! meetings at different campuses.
+
+use std::fmt;
+
+use chrono::{NaiveDate, NaiveTime, Weekday};
+
+use crate::{
+    parser::{Class, DateRange},
+    InstitutionProfile,
+};
+
+/// Two meetings that happen at the same time on at least one date.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// Course code and schedule type of each class, eg. "CSCI 1200U Lecture".
+    pub classes: [String; 2],
+    pub weekday: Weekday,
+    /// The overlapping part of the two meetings.
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    /// The first date both meetings happen on.
+    pub first_date: NaiveDate,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} and {} overlap on {} {}–{}, starting {}",
+            self.classes[0],
+            self.classes[1],
+            self.weekday,
+            self.start_time.format("%H:%M"),
+            self.end_time.format("%H:%M"),
+            self.first_date,
+        )
+    }
+}
+
+/// Every pair of meetings from different classes that overlap, in schedule order.
+pub fn find_conflicts<'a>(classes: impl IntoIterator<Item = &'a Class>) -> Vec<Conflict> {
+    let meetings = classes
+        .into_iter()
+        .enumerate()
+        .flat_map(|(i, class)| class.date_ranges.iter().map(move |d| (i, class, d)))
+        .collect::<Vec<_>>();
+
+    let mut conflicts = Vec::new();
+    for (n, &(i, class, a)) in meetings.iter().enumerate() {
+        for &(j, other, b) in &meetings[n + 1..] {
+            if i == j {
+                continue;
+            }
+            if let Some(conflict) = conflict(a, b) {
+                conflicts.push(Conflict {
+                    classes: [label(class), label(other)],
+                    ..conflict
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+/// Two meetings on the same day at different campuses, with no other meeting in between.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CampusChange {
+    /// Course code and schedule type of each class, in the order they meet.
+    pub classes: [String; 2],
+    pub campuses: [String; 2],
+    pub weekday: Weekday,
+    /// Minutes between the end of the first meeting and the start of the second.
+    pub gap: i64,
+    /// The first date both meetings happen on.
+    pub first_date: NaiveDate,
+}
+
+impl fmt::Display for CampusChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} minutes to get from {} at {} to {} at {} on {}, starting {}",
+            self.gap,
+            self.classes[0],
+            self.campuses[0],
+            self.classes[1],
+            self.campuses[1],
+            self.weekday,
+            self.first_date,
+        )
+    }
+}
+
+/// Every meeting that's followed on the same day by one at a different campus. Online meetings
+/// and ones without a known campus are left out.
+pub fn find_campus_changes<'a>(
+    classes: impl IntoIterator<Item = &'a Class>,
+    profile: &InstitutionProfile,
+) -> Vec<CampusChange> {
+    let mut meetings = classes
+        .into_iter()
+        .flat_map(|class| class.date_ranges.iter().map(move |d| (class, d)))
+        .filter(|(_, d)| !d.is_online())
+        .filter_map(|(class, d)| Some((class, d, profile.campus(d)?)))
+        .collect::<Vec<_>>();
+    meetings.sort_by_key(|(_, d, _)| (d.weekday.num_days_from_monday(), d.start_time));
+
+    let mut changes = Vec::new();
+    for (n, (class, a, campus)) in meetings.iter().enumerate() {
+        // the next meeting on one of the same dates
+        let next = meetings[n + 1..]
+            .iter()
+            .find_map(|(other, b, other_campus)| {
+                if b.weekday != a.weekday || b.start_time < a.end_time {
+                    return None;
+                }
+                let first_date = a
+                    .dates()
+                    .find(|date| b.dates().any(|other| other == *date))?;
+                Some((other, b, other_campus, first_date))
+            });
+        if let Some((other, b, other_campus, first_date)) = next {
+            if other_campus != campus {
+                changes.push(CampusChange {
+                    classes: [label(class), label(other)],
+                    campuses: [campus.clone(), other_campus.clone()],
+                    weekday: a.weekday,
+                    gap: (b.start_time - a.end_time).num_minutes(),
+                    first_date,
+                });
+            }
+        }
+    }
+    changes
+}
+
+fn conflict(a: &DateRange, b: &DateRange) -> Option<Conflict> {
+    if a.weekday != b.weekday || a.start_time >= b.end_time || b.start_time >= a.end_time {
+        return None;
+    }
+    let first_date = a
+        .dates()
+        .find(|date| b.dates().any(|other| other == *date))?;
+    Some(Conflict {
+        classes: Default::default(),
+        weekday: a.weekday,
+        start_time: a.start_time.max(b.start_time),
+        end_time: a.end_time.min(b.end_time),
+        first_date,
+    })
+}
+
+fn label(class: &Class) -> String {
+    format!("{} {}", class.code, class.class_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use chrono::Duration;
+
+    #[test]
+    fn test_find_conflicts() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        assert_eq!(find_conflicts(&schedule.classes), []);
+
+        let meeting = schedule.classes[0].date_ranges[0].clone();
+        let mut overlapping = schedule.classes[1].clone();
+        overlapping.date_ranges = vec![DateRange {
+            start_time: meeting.start_time + Duration::minutes(30),
+            end_time: meeting.end_time + Duration::minutes(30),
+            ..meeting.clone()
+        }];
+        let conflicts = find_conflicts(&[schedule.classes[0].clone(), overlapping.clone()]);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].weekday, meeting.weekday);
+        assert_eq!(
+            conflicts[0].start_time,
+            meeting.start_time + Duration::minutes(30)
+        );
+        assert_eq!(conflicts[0].end_time, meeting.end_time);
+
+        // biweekly meetings on alternating weeks never overlap
+        overlapping.date_ranges[0].biweekly = true;
+        overlapping.date_ranges[0].start_date = meeting.dates().nth(1).unwrap();
+        let mut alternating = schedule.classes[0].clone();
+        alternating.date_ranges = vec![DateRange {
+            biweekly: true,
+            ..meeting
+        }];
+        assert_eq!(find_conflicts(&[alternating, overlapping]), []);
+    }
+
+    #[test]
+    fn test_find_campus_changes() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let profile = InstitutionProfile::default();
+        assert_eq!(find_campus_changes(&schedule.classes, &profile), []);
+
+        let meeting = schedule.classes[0].date_ranges[0].clone();
+        let mut downtown = schedule.classes[1].clone();
+        downtown.date_ranges = vec![DateRange {
+            start_time: meeting.end_time + Duration::minutes(10),
+            end_time: meeting.end_time + Duration::minutes(90),
+            location: Some("Downtown Oshawa".to_owned()),
+            ..meeting.clone()
+        }];
+        let changes = find_campus_changes(&[schedule.classes[0].clone(), downtown], &profile);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(
+            changes[0].to_string(),
+            "10 minutes to get from CSCI 1200U Lecture at North Oshawa to CSCI 1060U Laboratory \
+            at Downtown Oshawa on Mon, starting 2024-09-09"
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! Writing a crash report when the program panics, instead of the window just disappearing.
+
+use std::{
+    backtrace::Backtrace,
+    fmt::Write as _,
+    fs,
+    panic::{self, PanicHookInfo},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{Receiver, TryRecvError},
+    },
+    thread,
+};
+
+use chrono::Local;
+use rfd::{MessageDialog, MessageLevel};
+
+use crate::{
+    i18n::{tr, tr_args},
+    logging,
+};
+
+/// Length of the pasted schedule data. Only the length goes in the report, since the data has the
+/// student's name in it.
+static INPUT_LEN: AtomicUsize = AtomicUsize::new(0);
+
+pub fn set_input_len(len: usize) {
+    INPUT_LEN.store(len, Ordering::Relaxed);
+}
+
+/// Installs a panic hook that writes a report to the log folder and shows where it is, after the
+/// default hook has printed the panic. Panics on background threads are only logged, since the
+/// window keeps running; see [`try_recv`].
+pub fn install(version: &'static str) {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        let report = logging::sanitize(&crash_report(version, info));
+        if let Some(panicked) = report.lines().find(|line| line.starts_with("Panicked")) {
+            tracing::error!("{panicked}");
+        }
+
+        let path = write_report(&report);
+        if thread::current().name() != Some("main") {
+            match &path {
+                Some(path) => tracing::error!("Crash report saved to {}", path.display()),
+                None => tracing::error!("Failed to save the crash report"),
+            }
+            return;
+        }
+
+        let description = match path {
+            Some(path) => tr_args(
+                "crash-saved",
+                &[("path", path.display().to_string().into())],
+            ),
+            None => tr_args("crash-not-saved", &[("report", report.into())]),
+        };
+        MessageDialog::new()
+            .set_level(MessageLevel::Error)
+            .set_title(tr("crash-title"))
+            .set_description(description)
+            .show();
+    }));
+}
+
+/// Receives the result of a background thread, if it's done. If the thread panicked before
+/// sending one, `pending` is cleared and `status` says so.
+pub fn try_recv<T>(pending: &mut Option<Receiver<T>>, status: &mut Option<String>) -> Option<T> {
+    match pending.as_ref()?.try_recv() {
+        Ok(result) => Some(result),
+        Err(TryRecvError::Empty) => None,
+        Err(TryRecvError::Disconnected) => {
+            *pending = None;
+            *status = Some(tr("worker-crashed"));
+            None
+        }
+    }
+}
+
+fn crash_report(version: &str, info: &PanicHookInfo) -> String {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_default();
+    let location = info
+        .location()
+        .map(|location| format!(" at {location}"))
+        .unwrap_or_default();
+
+    let mut report = String::new();
+    writeln!(
+        report,
+        "mycampus-calendar-rs {version} on {} ({})",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )
+    .ok();
+    writeln!(report, "{}", Local::now().format("%Y-%m-%d %H:%M:%S")).ok();
+    writeln!(report, "Panicked{location}: {message}").ok();
+    writeln!(
+        report,
+        "Pasted data: {} byte(s)",
+        INPUT_LEN.load(Ordering::Relaxed)
+    )
+    .ok();
+    writeln!(report, "\n{}", Backtrace::force_capture()).ok();
+    report
+}
+
+/// Writes `report` to a new file in the log folder, returning its path.
+fn write_report(report: &str) -> Option<PathBuf> {
+    let folder = logging::log_folder()?;
+    fs::create_dir_all(&folder).ok()?;
+    let path = folder.join(format!(
+        "crash-{}.txt",
+        Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    fs::write(&path, report).ok()?;
+    Some(path)
+}
@@ -0,0 +1,127 @@
+use chrono::{NaiveDate, NaiveTime, Timelike, Weekday};
+use eframe::egui::{self, ComboBox, DragValue, Grid, TextEdit};
+use egui_extras::DatePickerButton;
+use mycampus_calendar_rs::CustomEvent;
+
+use crate::i18n::{tr, weekday_name};
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Editor for weekly events that aren't in the schedule, eg. office hours or work shifts. New
+/// events span `term_dates`, if they're known. Returns true if anything was changed.
+pub fn custom_events(
+    ui: &mut egui::Ui,
+    events: &mut Vec<CustomEvent>,
+    term_dates: Option<(NaiveDate, NaiveDate)>,
+) -> bool {
+    let mut changed = false;
+
+    if ui
+        .button(tr("custom-events-add"))
+        .on_hover_text(tr("custom-events-add-hover"))
+        .clicked()
+    {
+        let today = chrono::Local::now().date_naive();
+        let (start_date, end_date) = term_dates.unwrap_or((today, today));
+        events.push(CustomEvent {
+            title: String::new(),
+            weekday: Weekday::Mon,
+            start_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            end_time: NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            start_date,
+            end_date,
+            location: String::new(),
+        });
+        changed = true;
+    }
+    if events.is_empty() {
+        return changed;
+    }
+
+    Grid::new("custom_events").striped(true).show(ui, |ui| {
+        let mut i = 0;
+        events.retain_mut(|event| {
+            let should_delete = ui.button("❌").clicked();
+            changed |= ui
+                .add(
+                    TextEdit::singleline(&mut event.title)
+                        .hint_text(tr("custom-events-title"))
+                        .desired_width(140.0),
+                )
+                .changed();
+            ComboBox::from_id_source(("custom_weekday", i))
+                .selected_text(weekday_name(event.weekday))
+                .width(60.0)
+                .show_ui(ui, |ui| {
+                    for weekday in WEEKDAYS {
+                        changed |= ui
+                            .selectable_value(&mut event.weekday, weekday, weekday_name(weekday))
+                            .changed();
+                    }
+                });
+            ui.horizontal(|ui| {
+                changed |= time_edit(ui, &mut event.start_time);
+                ui.label("–");
+                changed |= time_edit(ui, &mut event.end_time);
+            });
+            ui.horizontal(|ui| {
+                changed |= date_picker(ui, &mut event.start_date, &format!("{i}_custom_start"));
+                ui.label(tr("date-range-to"));
+                changed |= date_picker(ui, &mut event.end_date, &format!("{i}_custom_end"));
+            });
+            changed |= ui
+                .add(
+                    TextEdit::singleline(&mut event.location)
+                        .hint_text(tr("custom-events-location"))
+                        .desired_width(100.0),
+                )
+                .changed();
+            ui.end_row();
+
+            i += 1;
+            changed |= should_delete;
+            !should_delete
+        });
+    });
+
+    changed
+}
+
+fn date_picker(ui: &mut egui::Ui, date: &mut NaiveDate, id_source: &str) -> bool {
+    ui.add(
+        DatePickerButton::new(date)
+            .id_source(id_source)
+            .calendar_week(false)
+            .show_icon(false),
+    )
+    .changed()
+}
+
+fn time_edit(ui: &mut egui::Ui, time: &mut NaiveTime) -> bool {
+    let (mut hour, mut minute) = (time.hour(), time.minute());
+    let changed = ui
+        .add(
+            DragValue::new(&mut hour)
+                .range(0..=23)
+                .custom_formatter(|n, _| format!("{n:02}")),
+        )
+        .changed()
+        | ui.add(
+            DragValue::new(&mut minute)
+                .range(0..=59)
+                .custom_formatter(|n, _| format!(":{n:02}")),
+        )
+        .changed();
+    if changed {
+        *time = NaiveTime::from_hms_opt(hour, minute, 0).unwrap_or(*time);
+    }
+    changed
+}
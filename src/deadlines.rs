@@ -0,0 +1,250 @@
+//! Assignment deadlines from a learning management system's calendar feed, eg. Canvas or
+//! Brightspace, added to the calendars as all-day events.
+
+use std::fmt::Write;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{fs, io};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Utc};
+use indoc::indoc;
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use thiserror::Error;
+
+use crate::{catalog::normalize_code, parser::escape_text};
+
+/// UIDs of deadline events start with this, so they don't clash with the feed's own events.
+pub const UID_PREFIX: &str = "deadline-";
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Error)]
+pub enum DeadlinesError {
+    #[error("Failed to read deadlines calendar: {0}")]
+    Io(#[from] io::Error),
+    #[error("Failed to download deadlines calendar: {0}")]
+    Http(#[from] ureq::Error),
+}
+
+/// An assignment or assessment that's due on a date.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deadline {
+    /// UID of the event in the feed.
+    pub uid: String,
+    pub title: String,
+    /// Code of the course it's for, as in the schedule.
+    pub course: String,
+    pub date: NaiveDate,
+}
+
+/// Reads a calendar feed from a file path or a URL. `webcal://` URLs, which the feeds are often
+/// shared as, are downloaded over HTTPS.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load(source: &str) -> Result<String, DeadlinesError> {
+    let source = match source.strip_prefix("webcal://") {
+        Some(rest) => format!("https://{rest}"),
+        None => source.to_owned(),
+    };
+    if source.starts_with("http://") || source.starts_with("https://") {
+        Ok(ureq::get(&source).call()?.body_mut().read_to_string()?)
+    } else {
+        Ok(fs::read_to_string(source)?)
+    }
+}
+
+/// The events in `calendar` whose titles mention one of `codes`, eg.
+/// "Assignment 1 [CSCI 1200U - Discrete Mathematics]", sorted by date. Codes match regardless of
+/// case and spacing, and the bracketed course is left out of the titles.
+pub fn parse_deadlines<'a>(
+    calendar: &str,
+    codes: impl IntoIterator<Item = &'a str>,
+) -> Vec<Deadline> {
+    let codes = codes
+        .into_iter()
+        .filter(|code| !code.is_empty())
+        .map(|code| (code, normalize_code(code)))
+        .collect::<Vec<_>>();
+    let unfolded = calendar.replace("\r\n", "\n").replace("\n ", "");
+
+    let mut deadlines = Vec::new();
+    let mut event: Option<(String, String, String)> = None;
+    for line in unfolded.lines() {
+        match line {
+            "BEGIN:VEVENT" => event = Some(Default::default()),
+            "END:VEVENT" => {
+                let Some((uid, summary, start)) = event.take() else {
+                    continue;
+                };
+                let summary = unescape(&summary);
+                let normalized = normalize_code(&summary);
+                let (Some(date), Some((course, _))) = (
+                    due_date(&start),
+                    codes.iter().find(|(_, code)| normalized.contains(code)),
+                ) else {
+                    continue;
+                };
+                deadlines.push(Deadline {
+                    uid,
+                    title: title(&summary).to_owned(),
+                    course: course.to_string(),
+                    date,
+                });
+            }
+            _ => {
+                let (Some((uid, summary, start)), Some(split)) =
+                    (&mut event, line.find([';', ':']))
+                else {
+                    continue;
+                };
+                let value = line[split + 1..].to_owned();
+                match &line[..split] {
+                    "UID" => *uid = value,
+                    "SUMMARY" => *summary = value,
+                    "DTSTART" => *start = value,
+                    _ => (),
+                }
+            }
+        }
+    }
+    deadlines.sort_by(|a, b| (a.date, &a.title).cmp(&(b.date, &b.title)));
+    deadlines
+}
+
+/// The local date of a `DTSTART` value. Times in UTC are converted, since eg. 11:59 PM in Toronto
+/// is already the next day in UTC.
+fn due_date(value: &str) -> Option<NaiveDate> {
+    let value = value.rsplit(':').next()?;
+    if let Some(utc) = value.strip_suffix('Z') {
+        let date_time = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(
+            DateTime::<Utc>::from_naive_utc_and_offset(date_time, Utc)
+                .with_timezone(&Local)
+                .date_naive(),
+        );
+    }
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+/// `summary` without a trailing bracketed course, eg. " [CSCI 1200U - Discrete Mathematics]".
+fn title(summary: &str) -> &str {
+    summary
+        .rsplit_once(" [")
+        .filter(|(_, course)| course.ends_with(']'))
+        .map_or(summary, |(title, _)| title)
+        .trim()
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\n", " ")
+        .replace("\\\\", "\\")
+}
+
+/// Appends the all-day `VEVENT` for `deadline` to `calendar`.
+pub(crate) fn write_deadline(calendar: &mut String, deadline: &Deadline) {
+    write!(
+        calendar,
+        indoc! {"
+            BEGIN:VEVENT
+            DTSTAMP:{dtstamp}
+            UID:{prefix}{uid}
+            DTSTART;VALUE=DATE:{start}
+            DTEND;VALUE=DATE:{end}
+            SUMMARY:{summary}
+            DESCRIPTION:{description}
+            TRANSP:TRANSPARENT
+            CATEGORIES:Deadline
+            END:VEVENT
+        "},
+        dtstamp = Utc::now().format("%Y%m%dT%H%M%SZ"),
+        prefix = UID_PREFIX,
+        uid = deadline.uid,
+        start = deadline.date.format("%Y%m%d"),
+        end = deadline.date.succ_opt().unwrap().format("%Y%m%d"),
+        summary = escape_text(&format!("Due: {}", deadline.title)),
+        description = escape_text(&format!("Course: {}", deadline.course)),
+    )
+    .ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, fs};
+
+    use indoc::indoc;
+
+    use super::*;
+    use crate::{write_calendars, GenerateOptions, InstitutionProfile, Parser};
+
+    const FEED: &str = indoc! {r"
+        BEGIN:VCALENDAR
+        VERSION:2.0
+        PRODID:-//Instructure//Canvas//EN
+        BEGIN:VEVENT
+        UID:event-assignment-101
+        DTSTART;VALUE=DATE:20241011
+        SUMMARY:Assignment 1 [CSCI1200U-001-Discrete Mathematics for Computer Science]
+        END:VEVENT
+        BEGIN:VEVENT
+        UID:event-assignment-102
+        DTSTART;TZID=America/Toronto:20240927T170000
+        SUMMARY:Lab 2 report\, part A [CSCI 1060U]
+        END:VEVENT
+        BEGIN:VEVENT
+        UID:event-assignment-103
+        DTSTART;VALUE=DATE:20241004
+        SUMMARY:Quiz 1 [MATH 1010U]
+        END:VEVENT
+        END:VCALENDAR
+    "};
+
+    #[test]
+    fn test_parse_deadlines() {
+        let deadlines = parse_deadlines(FEED, ["CSCI 1200U", "CSCI 1060U", "SCCO 0999U"]);
+        assert_eq!(
+            deadlines,
+            [
+                Deadline {
+                    uid: "event-assignment-102".to_owned(),
+                    title: "Lab 2 report, part A".to_owned(),
+                    course: "CSCI 1060U".to_owned(),
+                    date: NaiveDate::from_ymd_opt(2024, 9, 27).unwrap(),
+                },
+                Deadline {
+                    uid: "event-assignment-101".to_owned(),
+                    title: "Assignment 1".to_owned(),
+                    course: "CSCI 1200U".to_owned(),
+                    date: NaiveDate::from_ymd_opt(2024, 10, 11).unwrap(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_deadlines() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let folder = crate::test_folder("deadlines");
+        let options = GenerateOptions {
+            deadlines: parse_deadlines(FEED, ["CSCI 1200U", "CSCI 1060U"]),
+            ..Default::default()
+        };
+        write_calendars(
+            &folder,
+            &schedule,
+            HashSet::new(),
+            &InstitutionProfile::default(),
+            &options,
+        );
+        let lecture = fs::read_to_string(folder.join("Fall 2024 - Lecture.ics")).unwrap();
+        let lab = fs::read_to_string(folder.join("Fall 2024 - Laboratory.ics")).unwrap();
+        fs::remove_dir_all(&folder).ok();
+
+        assert!(lecture.contains("UID:deadline-event-assignment-101\r\n"));
+        assert!(lecture.contains("DTSTART;VALUE=DATE:20241011\r\nDTEND;VALUE=DATE:20241012\r\n"));
+        assert!(lecture.contains("SUMMARY:Due: Assignment 1\r\n"));
+        assert!(lab.contains("SUMMARY:Due: Lab 2 report\\, part A\r\n"));
+        assert!(!lab.contains("Assignment 1"));
+    }
+}
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use eframe::egui::{
+    self,
+    epaint::text::cursor::PCursor,
+    text::{LayoutJob, TextFormat},
+    text_edit::TextEditOutput,
+    Align, Galley, TextStyle,
+};
+use mycampus_calendar_rs::parser::ClassFailure;
+
+use crate::i18n::{tr, tr_args};
+
+/// Lays out the pasted data with the lines in `highlighted` (starting from 1) tinted, for
+/// [`TextEdit::layouter`](egui::TextEdit::layouter).
+pub fn highlight_lines(
+    ui: &egui::Ui,
+    text: &str,
+    wrap_width: f32,
+    highlighted: &[usize],
+) -> Arc<Galley> {
+    let font_id = TextStyle::Body.resolve(ui.style());
+    let color = ui.visuals().text_color();
+    let tint = ui.visuals().error_fg_color.gamma_multiply(0.25);
+
+    let mut job = LayoutJob::default();
+    for (i, line) in text.split_inclusive('\n').enumerate() {
+        let mut format = TextFormat::simple(font_id.clone(), color);
+        if highlighted.contains(&(i + 1)) {
+            format.background = tint;
+        }
+        job.append(line, 0.0, format);
+    }
+    job.wrap.max_width = wrap_width;
+    ui.fonts(|fonts| fonts.layout_job(job))
+}
+
+/// Scrolls the enclosing scroll area so `line` (starting from 1) of the text edit is visible.
+pub fn scroll_to_line(ui: &egui::Ui, output: &TextEditOutput, line: usize) {
+    let rect = output.galley.pos_from_pcursor(PCursor {
+        paragraph: line.saturating_sub(1),
+        offset: 0,
+        prefer_next_row: false,
+    });
+    ui.scroll_to_rect(
+        rect.translate(output.galley_pos.to_vec2()),
+        Some(Align::Center),
+    );
+}
+
+/// The classes that couldn't be parsed, with where and what the parser expected. Returns the line
+/// of the one that was clicked, to scroll to it.
+pub fn failures(ui: &mut egui::Ui, failures: &[ClassFailure]) -> Option<usize> {
+    let mut clicked = None;
+    for failure in failures {
+        let location = match failure.line {
+            Some(line) => tr_args("diagnostics-line", &[("line", line.into())]),
+            None => String::new(),
+        };
+        let mut text = format!("❌ {location}{}\n    {}", failure.heading, failure.error);
+        if let Some(expected) = failure.error.expected() {
+            text += "\n    ";
+            text += &tr_args("diagnostics-expected", &[("expected", expected.into())]);
+        }
+        let response = ui.colored_label(ui.visuals().error_fg_color, text);
+        if let Some(line) = failure.line {
+            if response
+                .on_hover_text(tr("diagnostics-show-line"))
+                .interact(egui::Sense::click())
+                .clicked()
+            {
+                clicked = Some(line);
+            }
+        }
+    }
+    clicked
+}
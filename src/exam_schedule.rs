@@ -0,0 +1,108 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+
+use eframe::egui::{self, Grid, TextEdit};
+use mycampus_calendar_rs::{
+    exams::{self, ExamError},
+    Class, InstitutionProfile,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use rfd::FileDialog;
+
+use crate::i18n::tr;
+
+/// The final exam schedule, pasted or loaded from its PDF.
+#[derive(Default)]
+pub struct ExamSchedule {
+    data: String,
+    exams: Option<Result<Vec<Class>, ExamError>>,
+    load_error: Option<String>,
+}
+
+impl ExamSchedule {
+    /// The exams that were read, if any.
+    pub fn exams(&self) -> &[Class] {
+        match &self.exams {
+            Some(Ok(exams)) => exams,
+            _ => &[],
+        }
+    }
+
+    fn parse(&mut self, profile: &InstitutionProfile) {
+        self.exams =
+            (!self.data.trim().is_empty()).then(|| exams::parse_exams(&self.data, profile));
+    }
+}
+
+/// Input for the exam schedule, with the exams that were found. Returns true if they changed.
+pub fn exam_schedule(
+    ui: &mut egui::Ui,
+    exam_schedule: &mut ExamSchedule,
+    profile: &InstitutionProfile,
+) -> bool {
+    let mut changed = false;
+
+    ui.label(tr("exams-description"));
+    ui.horizontal(|ui| {
+        // the web build can't pick files, but the schedule can still be pasted
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui.button(tr("exams-load-pdf")).clicked() {
+            if let Some(path) = FileDialog::new().add_filter("PDF", &["pdf"]).pick_file() {
+                match fs::read(&path) {
+                    Ok(pdf) => {
+                        exam_schedule.data.clear();
+                        exam_schedule.exams = Some(exams::parse_exam_pdf(&pdf, profile));
+                        exam_schedule.load_error = None;
+                    }
+                    Err(e) => exam_schedule.load_error = Some(e.to_string()),
+                }
+                changed = true;
+            }
+        }
+        if ui.button(tr("clear")).clicked() {
+            *exam_schedule = ExamSchedule::default();
+            changed = true;
+        }
+    });
+    if ui
+        .add(
+            TextEdit::multiline(&mut exam_schedule.data)
+                .hint_text(tr("exam-schedule-heading"))
+                .desired_rows(4),
+        )
+        .changed()
+    {
+        exam_schedule.parse(profile);
+        exam_schedule.load_error = None;
+        changed = true;
+    }
+
+    let error = match &exam_schedule.exams {
+        Some(Err(e)) => Some(e.to_string()),
+        _ => exam_schedule.load_error.clone(),
+    };
+    if let Some(error) = error {
+        ui.colored_label(ui.visuals().error_fg_color, error);
+    }
+
+    let exams = exam_schedule.exams();
+    if !exams.is_empty() {
+        Grid::new("exams").striped(true).show(ui, |ui| {
+            for exam in exams {
+                ui.label(&exam.code);
+                for date_range in &exam.date_ranges {
+                    ui.label(date_range.start_date.format("%a, %b %-d").to_string());
+                    ui.label(format!(
+                        "{}–{}",
+                        date_range.start_time.format("%H:%M"),
+                        date_range.end_time.format("%H:%M")
+                    ));
+                    ui.label(date_range.place());
+                }
+                ui.end_row();
+            }
+        });
+    }
+
+    changed
+}
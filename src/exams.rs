@@ -0,0 +1,192 @@
+//! Reading the final exam schedule, so each exam can be added to the calendars as a one-off
+//! event.
+//!
+//! The exam schedule is a table with a row per exam, either copied from its page or extracted
+//! from its PDF. Each row is read as one line, eg.
+//! `CSCI 1200U  001  Discrete Mathematics  Monday, December 9, 2024  9:00 AM - 12:00 PM  CRWC Gym`.
+
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use thiserror::Error;
+
+use crate::{
+    parser::{is_tba, DateRangeKind},
+    Class, DateRange, InstitutionProfile,
+};
+
+/// Schedule type of exams, see [`Class::is_exam`].
+pub const EXAM_TYPE: &str = "Exam";
+
+#[derive(Debug, Error)]
+pub enum ExamError {
+    #[error("Failed to read the PDF: {0}")]
+    Pdf(#[from] pdf_extract::OutputError),
+    #[error("No exams were found in the exam schedule")]
+    NoExams,
+}
+
+static EXAM_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?x)
+        (?P<code>\b[A-Z]{3,4}\s?\d{4}[A-Z]?\b)
+        .*?
+        (?P<date>
+            [A-Z][a-z]{2,8}\.?\s+\d{1,2},?\s+\d{4}
+            | \d{4}-\d{2}-\d{2}
+            | \d{1,2}/\d{1,2}/\d{4}
+        )
+        \s+
+        (?P<start>\d{1,2}:\d{2}(?:\s*[AaPp]\.?[Mm]\.?)?)
+        \s*(?:-|–|to)\s*
+        (?P<end>\d{1,2}:\d{2}(?:\s*[AaPp]\.?[Mm]\.?)?)
+        \s*(?P<location>.*)",
+    )
+    .unwrap()
+});
+
+/// Reads the exams from the text of an exam schedule. Lines without a course code, date, and
+/// time, like headers, are skipped. Numeric dates are read with the profile's `date_format`.
+pub fn parse_exams(text: &str, profile: &InstitutionProfile) -> Result<Vec<Class>, ExamError> {
+    let exams = text
+        .lines()
+        .filter_map(|line| parse_exam(line, profile))
+        .collect::<Vec<_>>();
+    if exams.is_empty() {
+        return Err(ExamError::NoExams);
+    }
+    Ok(exams)
+}
+
+/// Reads the exams from the contents of an exam schedule PDF.
+pub fn parse_exam_pdf(pdf: &[u8], profile: &InstitutionProfile) -> Result<Vec<Class>, ExamError> {
+    let text = pdf_extract::extract_text_from_mem(pdf)?;
+    parse_exams(&text, profile)
+}
+
+fn parse_exam(line: &str, profile: &InstitutionProfile) -> Option<Class> {
+    let caps = EXAM_RE.captures(line)?;
+    let date = parse_date(&caps["date"], &profile.date_format)?;
+    let start_time = parse_time(&caps["start"])?;
+    let end_time = parse_time(&caps["end"])?;
+    let location = caps["location"].trim();
+    let code = caps["code"].to_owned();
+
+    Some(Class {
+        // replaced with the course's name by `Schedule::add_exams`, if it's in the schedule
+        name: code.clone(),
+        code,
+        date_ranges: vec![DateRange {
+            start_date: date,
+            end_date: date,
+            start_time,
+            end_time,
+            weekday: date.weekday(),
+            location: None,
+            building: None,
+            room: (!is_tba(location)).then(|| location.to_owned()),
+            biweekly: false,
+            kind: DateRangeKind::Single,
+        }],
+        instructor: None,
+        crn: String::new(),
+        class_type: EXAM_TYPE.to_owned(),
+        credit_hours: None,
+        asynchronous: Vec::new(),
+        term: String::new(),
+        meeting_url: None,
+    })
+}
+
+/// Dates like "Monday, December 9, 2024", "Dec. 9, 2024", or "2024-12-09".
+fn parse_date(date: &str, date_format: &str) -> Option<NaiveDate> {
+    let date = date.replace(['.', ','], "");
+    ["%B %d %Y", "%b %d %Y", "%Y-%m-%d"]
+        .into_iter()
+        .find_map(|format| NaiveDate::parse_from_str(&date, format).ok())
+        .or_else(|| NaiveDate::parse_from_str(&date, date_format).ok())
+}
+
+/// Times like "2:00 PM", "2:00 p.m.", or "14:00".
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    let time = time.replace(['.', ' '], "").to_uppercase();
+    NaiveTime::parse_from_str(&time, "%I:%M%p")
+        .or_else(|_| NaiveTime::parse_from_str(&time, "%H:%M"))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, fs};
+
+    use super::*;
+    use crate::{write_calendars, Parser};
+
+    #[test]
+    fn test_parse_exams() {
+        let profile = InstitutionProfile::default();
+        let exams = parse_exams(include_str!("../tests/data/exams.txt"), &profile).unwrap();
+        assert_eq!(exams.len(), 3);
+
+        let discrete = &exams[0];
+        assert_eq!(discrete.code, "CSCI 1200U");
+        assert!(discrete.is_exam());
+        let date_range = &discrete.date_ranges[0];
+        assert_eq!(
+            date_range.start_date,
+            NaiveDate::from_ymd_opt(2024, 12, 9).unwrap()
+        );
+        assert_eq!(
+            date_range.end_time,
+            NaiveTime::from_hms_opt(12, 0, 0).unwrap()
+        );
+        assert_eq!(date_range.room.as_deref(), Some("CRWC Gym"));
+        assert_eq!(date_range.dates().count(), 1);
+
+        assert_eq!(
+            exams[1].date_ranges[0].start_date,
+            NaiveDate::from_ymd_opt(2024, 12, 13).unwrap()
+        );
+        assert_eq!(exams[2].date_ranges[0].room, None);
+
+        assert!(matches!(
+            parse_exams("Final Examination Schedule", &profile),
+            Err(ExamError::NoExams)
+        ));
+    }
+
+    #[test]
+    fn test_write_exams() {
+        let profile = InstitutionProfile::default();
+        let mut schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let exams = parse_exams(include_str!("../tests/data/exams.txt"), &profile).unwrap();
+        schedule.add_exams(&exams);
+        assert_eq!(
+            schedule.classes.last().unwrap().name,
+            "MATH 1010U",
+            "exams for courses that aren't in the schedule keep their code"
+        );
+
+        let folder = crate::test_folder("exams");
+        write_calendars(
+            &folder,
+            &schedule,
+            HashSet::new(),
+            &profile,
+            &Default::default(),
+        );
+        let exams = fs::read_to_string(folder.join("Fall 2024 - Exams.ics")).unwrap();
+        let lecture = fs::read_to_string(folder.join("Fall 2024 - Lecture.ics")).unwrap();
+        fs::remove_dir_all(&folder).ok();
+
+        let events = exams.split("BEGIN:VEVENT").skip(1).collect::<String>();
+        assert_eq!(exams.matches("BEGIN:VEVENT").count(), 3);
+        assert!(!events.contains("RRULE"));
+        assert!(exams.contains("SUMMARY:Discrete Mathematics for Comp. Sci (Exam)\r\n"));
+        assert!(exams.contains("DTSTART;TZID=America/Toronto:20241209T090000\r\n"));
+        assert!(exams.contains("X-WR-CALNAME:Fall 2024 – Exams\r\n"));
+        assert!(!lecture.contains("(Exam)"));
+    }
+}
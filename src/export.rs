@@ -0,0 +1,652 @@
+//! Exporting the parsed schedule in formats other than `.ics`, for use in other tools.
+
+use std::{
+    collections::HashSet,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Duration, NaiveDate, NaiveTime};
+
+use crate::{
+    free_time,
+    parser::{meetings, GenerateOptions, Meeting, Schedule},
+    template, InstitutionProfile,
+};
+
+/// Columns of the CSV export, as `(header, placeholder)`.
+const CSV_COLUMNS: [(&str, &str); 11] = [
+    ("Course", "name"),
+    ("Code", "code"),
+    ("CRN", "crn"),
+    ("Type", "type"),
+    ("Weekday", "weekday"),
+    ("Start", "start"),
+    ("End", "end"),
+    ("Location", "campus"),
+    ("Building", "building"),
+    ("Room", "room"),
+    ("Instructor", "instructor"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// The parsed classes, serialized as-is.
+    Json,
+    /// One row per weekly meeting, for spreadsheets.
+    Csv,
+    /// One row per occurrence, in Google Calendar's import format.
+    GoogleCsv,
+    /// One row per occurrence, in Outlook's import format.
+    OutlookCsv,
+    /// A table of the weekly meetings, eg. for notes apps.
+    Markdown,
+    /// A printable page with a weekly timetable and the course details. Not in the web build,
+    /// since the PDF library doesn't build for it.
+    #[cfg(not(target_arch = "wasm32"))]
+    Pdf,
+    /// The free time between classes on each day of the term, as a `VFREEBUSY` calendar.
+    FreeBusy,
+}
+
+impl ExportFormat {
+    pub const ALL: &'static [Self] = &[
+        Self::Json,
+        Self::Csv,
+        Self::GoogleCsv,
+        Self::OutlookCsv,
+        Self::Markdown,
+        #[cfg(not(target_arch = "wasm32"))]
+        Self::Pdf,
+        Self::FreeBusy,
+    ];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Json => "schedule.json",
+            Self::Csv => "schedule.csv",
+            Self::GoogleCsv => "google-calendar.csv",
+            Self::OutlookCsv => "outlook.csv",
+            Self::Markdown => "schedule.md",
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Pdf => "schedule.pdf",
+            Self::FreeBusy => "free-time.ics",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Json => "JSON",
+            Self::Csv => "CSV (one row per meeting)",
+            Self::GoogleCsv => "Google Calendar CSV",
+            Self::OutlookCsv => "Outlook CSV",
+            Self::Markdown => "Markdown table",
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Pdf => "PDF",
+            Self::FreeBusy => "Free time (VFREEBUSY)",
+        })
+    }
+}
+
+/// Writes `schedule` to [`ExportFormat::file_name`] in `output_folder`, and returns its path.
+///
+/// `exdate`, `profile`, and `options` are used the same way as by
+/// [`write_calendars`](crate::write_calendars), for the formats with one row per occurrence.
+pub fn export(
+    output_folder: impl AsRef<Path>,
+    schedule: &Schedule,
+    exdate: &HashSet<NaiveDate>,
+    profile: &InstitutionProfile,
+    options: &GenerateOptions,
+    format: ExportFormat,
+) -> io::Result<PathBuf> {
+    let path = output_folder.as_ref().join(format.file_name());
+    fs::write(
+        &path,
+        export_contents(schedule, exdate, profile, options, format)?,
+    )?;
+    Ok(path)
+}
+
+/// The contents of the [`export`] of `schedule`, without writing it anywhere.
+pub fn export_contents(
+    schedule: &Schedule,
+    exdate: &HashSet<NaiveDate>,
+    profile: &InstitutionProfile,
+    options: &GenerateOptions,
+    format: ExportFormat,
+) -> io::Result<Vec<u8>> {
+    let mut schedule = schedule.clone();
+    schedule.classes.retain(|class| !options.skips(class));
+    let schedule = &schedule;
+    Ok(match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&schedule.classes)?.into_bytes(),
+        ExportFormat::Csv => csv(schedule)?.into_bytes(),
+        ExportFormat::GoogleCsv => google_csv(schedule, exdate, profile, options)?.into_bytes(),
+        ExportFormat::OutlookCsv => outlook_csv(schedule, exdate, profile, options)?.into_bytes(),
+        ExportFormat::Markdown => markdown(schedule).into_bytes(),
+        #[cfg(not(target_arch = "wasm32"))]
+        ExportFormat::Pdf => pdf(schedule, options).map_err(io::Error::other)?,
+        ExportFormat::FreeBusy => {
+            free_time::freebusy_calendar(schedule, exdate, options).into_bytes()
+        }
+    })
+}
+
+fn csv(schedule: &Schedule) -> io::Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(CSV_COLUMNS.map(|(header, _)| header))?;
+    for class in &schedule.classes {
+        for date_range in &class.date_ranges {
+            writer.write_record(CSV_COLUMNS.map(|(_, placeholder)| {
+                template::event_value(class, Some(date_range), placeholder).unwrap_or_default()
+            }))?;
+        }
+    }
+    into_string(writer)
+}
+
+/// The weekly meetings sorted by weekday and time, as a Markdown table.
+fn markdown(schedule: &Schedule) -> String {
+    let mut meetings = schedule
+        .classes
+        .iter()
+        .flat_map(|class| class.date_ranges.iter().map(move |d| (class, d)))
+        .filter(|(_, d)| d.is_weekly())
+        .collect::<Vec<_>>();
+    meetings.sort_by_key(|(_, d)| (d.weekday.num_days_from_monday(), d.start_time));
+
+    let mut table = String::new();
+    if !schedule.term.is_empty() {
+        table.push_str(&format!("## {}\n\n", schedule.term));
+    }
+    table.push_str("| Day | Time | Course | Type | Location |\n");
+    table.push_str("| --- | --- | --- | --- | --- |\n");
+    for (class, date_range) in meetings {
+        let cells = [
+            date_range.weekday.to_string(),
+            format!(
+                "{}–{}",
+                date_range.start_time.format("%H:%M"),
+                date_range.end_time.format("%H:%M")
+            ),
+            format!("{} ({})", class.name, class.code),
+            class.class_type.clone(),
+            date_range.place(),
+        ];
+        let cells = cells.map(|cell| cell.replace('|', "\\|"));
+        table.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    table
+}
+
+/// A landscape letter page with the weekly timetable on top and a table of the classes below.
+#[cfg(not(target_arch = "wasm32"))]
+fn pdf(schedule: &Schedule, options: &GenerateOptions) -> Result<Vec<u8>, printpdf::Error> {
+    use chrono::{Timelike, Weekday};
+    use printpdf::{path::PaintMode, BuiltinFont, Color, Line, Mm, PdfDocument, Point, Rect, Rgb};
+
+    use crate::parser::TBA;
+
+    const WIDTH: f32 = 279.4;
+    const HEIGHT: f32 = 215.9;
+    const MARGIN: f32 = 12.0;
+    const GRID_HEIGHT: f32 = 105.0;
+    const HOUR_LABEL_WIDTH: f32 = 12.0;
+    const LINE_HEIGHT: f32 = 3.6;
+
+    let title = if schedule.term.is_empty() {
+        "Schedule"
+    } else {
+        &schedule.term
+    };
+    let (document, page, layer) = PdfDocument::new(title, Mm(WIDTH), Mm(HEIGHT), "Schedule");
+    let layer = document.get_page(page).get_layer(layer);
+    let regular = document.add_builtin_font(BuiltinFont::Helvetica)?;
+    let bold = document.add_builtin_font(BuiltinFont::HelveticaBold)?;
+    let gray = |level: f32| Color::Rgb(Rgb::new(level, level, level, None));
+    let line = |from: (f32, f32), to: (f32, f32)| Line {
+        points: vec![
+            (Point::new(Mm(from.0), Mm(from.1)), false),
+            (Point::new(Mm(to.0), Mm(to.1)), false),
+        ],
+        is_closed: false,
+    };
+
+    layer.use_text(title, 16.0, Mm(MARGIN), Mm(HEIGHT - MARGIN - 4.0), &bold);
+
+    let meetings = schedule
+        .classes
+        .iter()
+        .flat_map(|class| class.date_ranges.iter().map(move |d| (class, d)))
+        .filter(|(_, d)| d.is_weekly())
+        .collect::<Vec<_>>();
+    let weekend = meetings
+        .iter()
+        .any(|(_, d)| d.weekday.num_days_from_monday() >= 5);
+    let days = if weekend { 7 } else { 5 };
+    let first_hour = meetings
+        .iter()
+        .map(|(_, d)| d.start_time.hour())
+        .min()
+        .unwrap_or(8)
+        .min(8);
+    let last_hour = meetings
+        .iter()
+        .map(|(_, d)| d.end_time.hour() + (d.end_time.minute() > 0) as u32)
+        .max()
+        .unwrap_or(18)
+        .max(18);
+
+    let top = HEIGHT - MARGIN - 16.0;
+    let bottom = top - GRID_HEIGHT;
+    let left = MARGIN + HOUR_LABEL_WIDTH;
+    let day_width = (WIDTH - MARGIN - left) / days as f32;
+    let hour_height = GRID_HEIGHT / (last_hour - first_hour) as f32;
+    let y = |time: NaiveTime| {
+        top - (time.num_seconds_from_midnight() as f32 / 3600.0 - first_hour as f32) * hour_height
+    };
+
+    layer.set_outline_color(gray(0.75));
+    layer.set_outline_thickness(0.5);
+    for day in 0..days {
+        let x = left + day as f32 * day_width;
+        layer.add_line(line((x, top), (x, bottom)));
+        let weekday =
+            Weekday::try_from(day as u8).map_or_else(|_| String::new(), |w| w.to_string());
+        layer.use_text(
+            weekday,
+            9.0,
+            Mm(x + day_width / 2.0 - 3.0),
+            Mm(top + 2.0),
+            &bold,
+        );
+    }
+    layer.add_line(line((WIDTH - MARGIN, top), (WIDTH - MARGIN, bottom)));
+    for hour in first_hour..=last_hour {
+        let y = top - (hour - first_hour) as f32 * hour_height;
+        layer.add_line(line((MARGIN, y), (WIDTH - MARGIN, y)));
+        if hour < last_hour {
+            layer.use_text(format!("{hour}:00"), 7.0, Mm(MARGIN), Mm(y - 3.0), &regular);
+        }
+    }
+
+    for (class, date_range) in &meetings {
+        let day = date_range.weekday.num_days_from_monday();
+        if day >= days {
+            continue;
+        }
+        // lighten the calendar color so the text stays readable when printed
+        let [r, g, b] = options
+            .colors
+            .get(&class.class_type)
+            .map_or([0.85; 3], |color| {
+                color.map(|c| 0.6 + c as f32 / 255.0 * 0.4)
+            });
+        let x = left + day as f32 * day_width + 0.8;
+        let (block_top, block_bottom) = (y(date_range.start_time), y(date_range.end_time));
+        layer.set_fill_color(Color::Rgb(Rgb::new(r, g, b, None)));
+        layer.set_outline_color(gray(0.4));
+        layer.add_rect(
+            Rect::new(
+                Mm(x),
+                Mm(block_bottom),
+                Mm(x + day_width - 1.6),
+                Mm(block_top),
+            )
+            .with_mode(PaintMode::FillStroke),
+        );
+        layer.set_fill_color(gray(0.0));
+        for (i, text) in [
+            class.code.clone(),
+            class.class_type.clone(),
+            date_range.room.clone().unwrap_or_else(|| TBA.to_owned()),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let text_y = block_top - 3.0 - i as f32 * LINE_HEIGHT;
+            if text_y > block_bottom + 0.5 {
+                layer.use_text(text, 7.0, Mm(x + 1.0), Mm(text_y), &regular);
+            }
+        }
+    }
+
+    // course details
+    let columns = [
+        ("Course", 0.0),
+        ("Code", 75.0),
+        ("Type", 100.0),
+        ("CRN", 125.0),
+        ("Instructor", 140.0),
+        ("Meetings", 190.0),
+    ];
+    let mut row_y = bottom - 10.0;
+    for (header, x) in columns {
+        layer.use_text(header, 8.0, Mm(MARGIN + x), Mm(row_y), &bold);
+    }
+    for class in &schedule.classes {
+        let meetings = class
+            .date_ranges
+            .iter()
+            .map(|d| {
+                format!(
+                    "{} {}-{} {}",
+                    d.weekday,
+                    d.start_time.format("%H:%M"),
+                    d.end_time.format("%H:%M"),
+                    d.room.as_deref().unwrap_or(TBA)
+                )
+            })
+            .collect::<Vec<_>>();
+        let rows = meetings.len().max(1) as f32;
+        if row_y - (rows + 0.5) * LINE_HEIGHT < MARGIN {
+            layer.use_text(
+                "(more classes not shown)",
+                8.0,
+                Mm(MARGIN),
+                Mm(row_y - LINE_HEIGHT * 1.5),
+                &regular,
+            );
+            break;
+        }
+        row_y -= LINE_HEIGHT * 1.5;
+        let value =
+            |placeholder| template::event_value(class, None, placeholder).unwrap_or_default();
+        for ((_, x), text) in columns.iter().zip([
+            class.name.clone(),
+            class.code.clone(),
+            class.class_type.clone(),
+            value("crn"),
+            value("instructor"),
+        ]) {
+            layer.use_text(text, 8.0, Mm(MARGIN + x), Mm(row_y), &regular);
+        }
+        for (i, meeting) in meetings.into_iter().enumerate() {
+            let y = row_y - i as f32 * LINE_HEIGHT;
+            layer.use_text(meeting, 8.0, Mm(MARGIN + columns[5].1), Mm(y), &regular);
+        }
+        row_y -= (rows - 1.0) * LINE_HEIGHT;
+    }
+
+    document.save_to_bytes()
+}
+
+fn google_csv(
+    schedule: &Schedule,
+    exdate: &HashSet<NaiveDate>,
+    profile: &InstitutionProfile,
+    options: &GenerateOptions,
+) -> io::Result<String> {
+    let headers = [
+        "Subject",
+        "Start Date",
+        "Start Time",
+        "End Date",
+        "End Time",
+        "All Day Event",
+        "Description",
+        "Location",
+    ];
+    occurrences_csv(
+        schedule,
+        exdate,
+        profile,
+        options,
+        &headers,
+        |meeting, date| {
+            let (start, end) = times(meeting, options);
+            let date = date.format("%m/%d/%Y").to_string();
+            vec![
+                meeting.event.summary.clone(),
+                date.clone(),
+                start,
+                date,
+                end,
+                bool_value(meeting.date_range.is_none()).to_owned(),
+                meeting.event.description.clone(),
+                meeting.event.location.clone(),
+            ]
+        },
+    )
+}
+
+/// Outlook's import format, which has a reminder and free/busy status for each row.
+fn outlook_csv(
+    schedule: &Schedule,
+    exdate: &HashSet<NaiveDate>,
+    profile: &InstitutionProfile,
+    options: &GenerateOptions,
+) -> io::Result<String> {
+    let headers = [
+        "Subject",
+        "Start Date",
+        "Start Time",
+        "End Date",
+        "End Time",
+        "All day event",
+        "Reminder on/off",
+        "Reminder Date",
+        "Reminder Time",
+        "Categories",
+        "Description",
+        "Location",
+        "Show time as",
+    ];
+    occurrences_csv(
+        schedule,
+        exdate,
+        profile,
+        options,
+        &headers,
+        |meeting, date| {
+            let (start, end) = times(meeting, options);
+            let reminder = options
+                .reminders
+                .get(&meeting.class.class_type)
+                .map(|&minutes| {
+                    let start_time = meeting
+                        .date_range
+                        .map_or(NaiveTime::MIN, |d| options.event_times(d).0);
+                    date.and_time(start_time) - Duration::minutes(minutes.into())
+                });
+            let all_day = meeting.date_range.is_none();
+            vec![
+                meeting.event.summary.clone(),
+                date.format("%m/%d/%Y").to_string(),
+                start,
+                date.format("%m/%d/%Y").to_string(),
+                end,
+                bool_value(all_day).to_owned(),
+                bool_value(reminder.is_some()).to_owned(),
+                reminder.map_or_else(String::new, |r| r.format("%m/%d/%Y").to_string()),
+                reminder.map_or_else(String::new, |r| r.format("%I:%M %p").to_string()),
+                meeting.class.class_type.clone(),
+                meeting.event.description.clone(),
+                meeting.event.location.clone(),
+                // free for all-day events, busy otherwise
+                if all_day { "0" } else { "2" }.to_owned(),
+            ]
+        },
+    )
+}
+
+/// A CSV with `headers`, then one `row` for each occurrence of each meeting.
+fn occurrences_csv(
+    schedule: &Schedule,
+    exdate: &HashSet<NaiveDate>,
+    profile: &InstitutionProfile,
+    options: &GenerateOptions,
+    headers: &[&str],
+    row: impl Fn(&Meeting, NaiveDate) -> Vec<String>,
+) -> io::Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(headers)?;
+    for meeting in meetings(schedule, profile, options) {
+        for date in meeting.occurrences(exdate, &options.swap_days) {
+            writer.write_record(row(&meeting, date))?;
+        }
+    }
+    into_string(writer)
+}
+
+/// The start and end time of a meeting in 12-hour format, or empty for all-day events.
+fn times(meeting: &Meeting, options: &GenerateOptions) -> (String, String) {
+    meeting.date_range.map_or_else(Default::default, |d| {
+        let (start_time, end_time) = options.event_times(d);
+        (
+            start_time.format("%I:%M %p").to_string(),
+            end_time.format("%I:%M %p").to_string(),
+        )
+    })
+}
+
+fn bool_value(value: bool) -> &'static str {
+    if value {
+        "True"
+    } else {
+        "False"
+    }
+}
+
+fn into_string(writer: csv::Writer<Vec<u8>>) -> io::Result<String> {
+    String::from_utf8(writer.into_inner().map_err(|e| e.into_error())?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Class, Parser};
+
+    #[test]
+    fn test_export() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let folder = crate::test_folder("export");
+
+        let export = |format| {
+            let path = export(
+                &folder,
+                &schedule,
+                &HashSet::new(),
+                &Default::default(),
+                &Default::default(),
+                format,
+            );
+            fs::read_to_string(path.unwrap())
+        };
+        let json = export(ExportFormat::Json);
+        let csv = export(ExportFormat::Csv);
+        fs::remove_dir_all(&folder).ok();
+
+        let classes: Vec<Class> = serde_json::from_str(&json.unwrap()).unwrap();
+        assert_eq!(classes.len(), schedule.classes.len());
+        assert_eq!(classes[0].date_ranges, schedule.classes[0].date_ranges);
+
+        let csv = csv.unwrap();
+        let meetings = schedule
+            .classes
+            .iter()
+            .map(|c| c.date_ranges.len())
+            .sum::<usize>();
+        assert_eq!(csv.lines().count(), meetings + 1);
+        assert!(csv.starts_with("Course,Code,CRN,Type,Weekday,Start,End,"));
+    }
+    #[test]
+    fn test_google_csv() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let date_ranges = schedule
+            .classes
+            .iter()
+            .flat_map(|class| &class.date_ranges)
+            .collect::<Vec<_>>();
+        let rows = |exdate: &HashSet<NaiveDate>| {
+            let csv = google_csv(&schedule, exdate, &Default::default(), &Default::default());
+            csv::Reader::from_reader(csv.unwrap().as_bytes())
+                .records()
+                .map(Result::unwrap)
+                .collect::<Vec<_>>()
+        };
+
+        let all = rows(&HashSet::new());
+        let occurrences = date_ranges.iter().map(|d| d.dates().count()).sum::<usize>();
+        assert_eq!(all.len(), occurrences);
+        assert_eq!(&all[0][5], "False");
+
+        let first = date_ranges[0].dates().next().unwrap();
+        let on_first = date_ranges
+            .iter()
+            .filter(|d| d.dates().any(|date| date == first))
+            .count();
+        assert_eq!(rows(&HashSet::from([first])).len(), occurrences - on_first);
+    }
+
+    #[test]
+    fn test_outlook_csv() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let class = &schedule.classes[0];
+        let options = GenerateOptions {
+            reminders: [(class.class_type.clone(), 15)].into(),
+            ..Default::default()
+        };
+        let csv = outlook_csv(&schedule, &HashSet::new(), &Default::default(), &options).unwrap();
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        let row = reader.records().next().unwrap().unwrap();
+
+        let start = class.date_ranges[0].start_time;
+        assert_eq!(&row[1], &row[3]);
+        assert_eq!(&row[5], "False");
+        assert_eq!(&row[6], "True");
+        assert_eq!(&row[7], &row[1]);
+        assert_eq!(
+            &row[8],
+            (start - Duration::minutes(15))
+                .format("%I:%M %p")
+                .to_string()
+        );
+        assert_eq!(&row[9], class.class_type);
+        assert_eq!(&row[12], "2");
+    }
+
+    #[test]
+    fn test_markdown() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let table = markdown(&schedule);
+        let rows = table
+            .lines()
+            .filter(|line| line.starts_with("| ") && !line.starts_with("| Day"))
+            .collect::<Vec<_>>();
+
+        assert!(table.starts_with(&format!("## {}", schedule.term)));
+        assert_eq!(
+            rows.len() - 1,
+            schedule
+                .classes
+                .iter()
+                .map(|c| c.date_ranges.len())
+                .sum::<usize>()
+        );
+        assert!(rows[1].starts_with("| Mon |"));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_pdf() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let pdf = pdf(&schedule, &Default::default()).unwrap();
+
+        assert!(pdf.starts_with(b"%PDF"));
+    }
+}
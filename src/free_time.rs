@@ -0,0 +1,285 @@
+//! Finding the free time between classes, eg. for scheduling work shifts or study groups.
+
+use std::{collections::HashSet, fmt, fmt::Write};
+
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use indoc::indoc;
+
+use crate::parser::{finish_calendar, Class, GenerateOptions, Schedule};
+
+/// A free period on one weekday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FreeBlock {
+    pub weekday: Weekday,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl FreeBlock {
+    pub fn minutes(&self) -> i64 {
+        (self.end - self.start).num_minutes()
+    }
+}
+
+impl fmt::Display for FreeBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}–{} ({} h {:02} min)",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M"),
+            self.minutes() / 60,
+            self.minutes() % 60,
+        )
+    }
+}
+
+/// The free blocks between `day_start` and `day_end` in a typical week, treating every meeting
+/// as if it happened every week. Covers Monday to Friday, plus any other days with classes.
+pub fn weekly_free_blocks<'a>(
+    classes: impl IntoIterator<Item = &'a Class>,
+    day_start: NaiveTime,
+    day_end: NaiveTime,
+) -> Vec<FreeBlock> {
+    let classes = classes.into_iter().collect::<Vec<_>>();
+    weekdays(&classes)
+        .into_iter()
+        .flat_map(|weekday| {
+            let busy = classes
+                .iter()
+                .flat_map(|class| &class.date_ranges)
+                .filter(|d| d.weekday == weekday)
+                .map(|d| (d.start_time, d.end_time))
+                .collect();
+            gaps(busy, day_start, day_end)
+                .into_iter()
+                .map(move |(start, end)| FreeBlock {
+                    weekday,
+                    start,
+                    end,
+                })
+        })
+        .collect()
+}
+
+/// The free blocks that two schedules have in common, eg. for finding a time to study with a
+/// friend.
+pub fn common_free_blocks<'a>(
+    mine: impl IntoIterator<Item = &'a Class>,
+    theirs: impl IntoIterator<Item = &'a Class>,
+    day_start: NaiveTime,
+    day_end: NaiveTime,
+) -> Vec<FreeBlock> {
+    weekly_free_blocks(mine.into_iter().chain(theirs), day_start, day_end)
+}
+
+/// A calendar with a `VFREEBUSY` component listing the free time on each day of the term, with
+/// the classes on `exdate` left out. Only the time between
+/// [`free_time_start`](GenerateOptions::free_time_start) and
+/// [`free_time_end`](GenerateOptions::free_time_end) counts.
+///
+/// `FREEBUSY` periods have to be in UTC, so they're converted from this computer's timezone.
+pub fn freebusy_calendar(
+    schedule: &Schedule,
+    exdate: &HashSet<NaiveDate>,
+    options: &GenerateOptions,
+) -> String {
+    let (day_start, day_end) = (options.free_time_start, options.free_time_end);
+    let classes = schedule.classes.iter().collect::<Vec<_>>();
+    let weekdays = weekdays(&classes);
+    let date_ranges = classes
+        .iter()
+        .flat_map(|class| &class.date_ranges)
+        .collect::<Vec<_>>();
+    let first = date_ranges.iter().map(|d| d.start_date).min();
+    let last = date_ranges.iter().map(|d| d.end_date).max();
+
+    let mut calendar = String::from(indoc! {"
+        BEGIN:VCALENDAR
+        VERSION:2.0
+        PRODID:MYCAMPUS-CALENDAR-RS
+        METHOD:PUBLISH
+    "});
+    if let (Some(first), Some(last)) = (first, last) {
+        write!(
+            calendar,
+            indoc! {"
+                BEGIN:VFREEBUSY
+                UID:free-time-{first}-{last}@mycampus-calendar-rs
+                DTSTAMP:{dtstamp}
+                DTSTART:{dtstart}
+                DTEND:{dtend}
+            "},
+            first = first.format("%Y%m%d"),
+            last = last.format("%Y%m%d"),
+            dtstamp = Utc::now().format("%Y%m%dT%H%M%SZ"),
+            dtstart = utc(first.and_time(day_start)),
+            dtend = utc(last.and_time(day_end)),
+        )
+        .ok();
+        for date in first.iter_days().take_while(|date| *date <= last) {
+            if !weekdays.contains(&date.weekday()) {
+                continue;
+            }
+            let busy = date_ranges
+                .iter()
+                .filter(|_| !exdate.contains(&date))
+                .filter(|d| d.weekday == date.weekday() && d.dates().any(|other| other == date))
+                .map(|d| (d.start_time, d.end_time))
+                .collect();
+            let periods = gaps(busy, day_start, day_end)
+                .into_iter()
+                .map(|(start, end)| {
+                    format!("{}/{}", utc(date.and_time(start)), utc(date.and_time(end)))
+                })
+                .collect::<Vec<_>>();
+            if !periods.is_empty() {
+                writeln!(calendar, "FREEBUSY;FBTYPE=FREE:{}", periods.join(",")).ok();
+            }
+        }
+        calendar.push_str("END:VFREEBUSY\n");
+    }
+    finish_calendar(&mut calendar);
+    calendar
+}
+
+/// Monday to Friday, plus any other days with classes.
+fn weekdays(classes: &[&Class]) -> Vec<Weekday> {
+    let mut weekdays = vec![
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+    ];
+    for date_range in classes.iter().flat_map(|class| &class.date_ranges) {
+        if !weekdays.contains(&date_range.weekday) {
+            weekdays.push(date_range.weekday);
+        }
+    }
+    weekdays.sort_by_key(|weekday| weekday.num_days_from_monday());
+    weekdays
+}
+
+/// The periods between `day_start` and `day_end` that aren't in any of the `busy` ones.
+fn gaps(
+    mut busy: Vec<(NaiveTime, NaiveTime)>,
+    day_start: NaiveTime,
+    day_end: NaiveTime,
+) -> Vec<(NaiveTime, NaiveTime)> {
+    busy.sort();
+    let mut free = Vec::new();
+    let mut start = day_start;
+    for (busy_start, busy_end) in busy {
+        if busy_start > start {
+            free.push((start, busy_start.min(day_end)));
+        }
+        start = start.max(busy_end);
+        if start >= day_end {
+            break;
+        }
+    }
+    if start < day_end {
+        free.push((start, day_end));
+    }
+    free.retain(|(start, end)| start < end);
+    free
+}
+
+fn utc(datetime: NaiveDateTime) -> String {
+    Local
+        .from_local_datetime(&datetime)
+        .earliest()
+        .map_or(datetime, |local| local.naive_utc())
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_weekly_free_blocks() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let blocks = weekly_free_blocks(&schedule.classes, time(8, 0), time(18, 0));
+
+        let monday = blocks
+            .iter()
+            .filter(|block| block.weekday == Weekday::Mon)
+            .map(|block| block.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            monday,
+            ["08:00–11:10 (3 h 10 min)", "12:30–18:00 (5 h 30 min)"]
+        );
+        let tuesday = blocks
+            .iter()
+            .filter(|block| block.weekday == Weekday::Tue)
+            .collect::<Vec<_>>();
+        assert_eq!(tuesday.len(), 1);
+        assert_eq!(tuesday[0].minutes(), 10 * 60);
+    }
+
+    #[test]
+    fn test_common_free_blocks() {
+        let data = include_str!("../tests/data/mobile.txt");
+        let mine = Parser::new().parse_data(data).unwrap();
+        let theirs = Parser::new()
+            .parse_data(&data.replace("11:10 AM - 12:30 PM", "09:40 AM - 11:30 AM"))
+            .unwrap();
+        let blocks = common_free_blocks(&mine.classes, &theirs.classes, time(8, 0), time(22, 0));
+
+        let wednesday = blocks
+            .iter()
+            .filter(|block| block.weekday == Weekday::Wed)
+            .map(|block| (block.start, block.end))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            wednesday,
+            [(time(8, 0), time(9, 40)), (time(12, 30), time(22, 0))]
+        );
+    }
+
+    #[test]
+    fn test_gaps() {
+        assert_eq!(
+            gaps(
+                vec![
+                    (time(13, 0), time(14, 0)),
+                    (time(7, 0), time(9, 0)),
+                    (time(13, 30), time(15, 0)),
+                    (time(17, 0), time(20, 0)),
+                ],
+                time(8, 0),
+                time(18, 0)
+            ),
+            [(time(9, 0), time(13, 0)), (time(15, 0), time(17, 0))]
+        );
+    }
+
+    #[test]
+    fn test_freebusy_calendar() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let options = GenerateOptions {
+            free_time_end: time(18, 0),
+            ..Default::default()
+        };
+        let calendar = freebusy_calendar(&schedule, &HashSet::new(), &options);
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(calendar.contains("\r\nBEGIN:VFREEBUSY\r\n"));
+        // one line per weekday from Sep 4 to Dec 3
+        assert_eq!(calendar.matches("\r\nFREEBUSY;FBTYPE=FREE:").count(), 65);
+        assert!(calendar.ends_with("END:VFREEBUSY\r\nEND:VCALENDAR"));
+    }
+}
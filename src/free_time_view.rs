@@ -0,0 +1,128 @@
+use chrono::{NaiveTime, Timelike};
+use eframe::egui::{self, DragValue, Grid, TextEdit};
+use mycampus_calendar_rs::{
+    free_time::{self, FreeBlock},
+    parser::{Class, DateOrder, ParseError, Schedule},
+    GenerateOptions, InstitutionProfile, Parser,
+};
+
+use crate::i18n::{tr, weekday_name};
+
+/// A friend's schedule pasted into the free time view, for finding the free time in common.
+#[derive(Default)]
+pub struct FriendSchedule {
+    data: String,
+    schedule: Option<Result<Schedule, ParseError>>,
+}
+
+impl FriendSchedule {
+    fn parse(&mut self, profile: &InstitutionProfile, date_order: DateOrder) {
+        self.schedule = (!self.data.trim().is_empty()).then(|| {
+            Parser::from_profile(profile)
+                .map(|parser| parser.with_date_order(date_order))
+                .map_err(ParseError::from)
+                .and_then(|parser| parser.parse_data(&self.data))
+        });
+    }
+
+    /// The friend's classes in `term`, or all of them if none are.
+    fn classes(&self, term: &str) -> Vec<&Class> {
+        let Some(Ok(schedule)) = &self.schedule else {
+            return Vec::new();
+        };
+        let in_term = schedule
+            .classes
+            .iter()
+            .filter(|class| schedule.class_term(class) == term)
+            .collect::<Vec<_>>();
+        if in_term.is_empty() {
+            schedule.classes.iter().collect()
+        } else {
+            in_term
+        }
+    }
+}
+
+/// The free blocks between the selected classes on each weekday, with the part of the day that
+/// counts. If a friend's schedule in `term` is pasted, only the free time both of you have is
+/// shown.
+pub fn free_time_view(
+    ui: &mut egui::Ui,
+    classes: &[&Class],
+    term: &str,
+    options: &mut GenerateOptions,
+    friend: &mut FriendSchedule,
+    profile: &InstitutionProfile,
+    date_order: DateOrder,
+) {
+    ui.horizontal(|ui| {
+        ui.label(tr("free-time-from"));
+        hour_edit(ui, &mut options.free_time_start);
+        ui.label(tr("free-time-to"));
+        hour_edit(ui, &mut options.free_time_end);
+    });
+    ui.label(tr("free-time-export"));
+    ui.add_space(4.0);
+
+    ui.collapsing(tr("free-time-compare"), |ui| {
+        ui.label(tr("free-time-compare-description"));
+        if ui
+            .add(
+                TextEdit::multiline(&mut friend.data)
+                    .hint_text(tr("free-time-friend-hint"))
+                    .desired_rows(4),
+            )
+            .changed()
+        {
+            friend.parse(profile, date_order);
+        }
+        match &friend.schedule {
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+            }
+            Some(Ok(schedule)) if schedule.classes.is_empty() => {
+                ui.label(tr("free-time-friend-no-classes"));
+            }
+            _ => {}
+        }
+    });
+    ui.add_space(4.0);
+
+    let friend_classes = friend.classes(term);
+    if !friend_classes.is_empty() {
+        ui.strong(tr("free-time-both"));
+    }
+    let blocks = free_time::common_free_blocks(
+        classes.iter().copied(),
+        friend_classes,
+        options.free_time_start,
+        options.free_time_end,
+    );
+    free_blocks_grid(ui, &blocks);
+}
+
+fn free_blocks_grid(ui: &mut egui::Ui, blocks: &[FreeBlock]) {
+    let mut weekdays = blocks.iter().map(|block| block.weekday).collect::<Vec<_>>();
+    weekdays.dedup();
+    Grid::new("free_time").striped(true).show(ui, |ui| {
+        for weekday in weekdays {
+            ui.strong(weekday_name(weekday));
+            ui.vertical(|ui| {
+                for block in blocks.iter().filter(|block| block.weekday == weekday) {
+                    ui.label(block.to_string());
+                }
+            });
+            ui.end_row();
+        }
+    });
+}
+
+fn hour_edit(ui: &mut egui::Ui, time: &mut NaiveTime) {
+    let mut hour = time.hour();
+    if ui
+        .add(DragValue::new(&mut hour).range(0..=23).suffix(":00"))
+        .changed()
+    {
+        *time = NaiveTime::from_hms_opt(hour, 0, 0).unwrap_or(*time);
+    }
+}
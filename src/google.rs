@@ -0,0 +1,465 @@
+//! Pushing the events straight to a Google Calendar through the Calendar API, instead of
+//! importing `.ics` files by hand.
+//!
+//! Uses the OAuth flow for installed apps with the user's own OAuth client, and keeps the refresh
+//! token in the system keyring. Events are imported by their stable UID, so pushing again updates
+//! them instead of making duplicates.
+
+use std::{
+    collections::HashSet,
+    io::{self, BufRead, BufReader, Write},
+    net::TcpListener,
+    thread,
+    time::{Duration, Instant},
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{NaiveDate, TimeZone};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::{
+    http::{percent_decode, percent_encode, KEYRING_SERVICE},
+    parser::{default_sequence, meetings, with_swap_days, GenerateOptions, Meeting, Schedule},
+    InstitutionProfile,
+};
+
+const AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const API_URL: &str = "https://www.googleapis.com/calendar/v3";
+const SCOPE: &str = "https://www.googleapis.com/auth/calendar";
+/// How long to wait for the browser to redirect back after signing in.
+const SIGN_IN_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Error)]
+pub enum GoogleError {
+    #[error("Request to Google failed: {0}")]
+    Http(#[from] ureq::Error),
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("Unexpected response from Google: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to access the system keyring: {0}")]
+    Keyring(#[from] keyring::Error),
+    #[error("Sign in failed: {0}")]
+    SignIn(String),
+}
+
+/// An OAuth client ("Desktop app" type) from the Google Cloud console.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GoogleClient {
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+/// A signed in Google account.
+#[derive(Debug, Clone)]
+pub struct GoogleAccount {
+    access_token: String,
+}
+
+/// A calendar that the account can add events to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoogleCalendar {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+}
+
+impl GoogleClient {
+    fn keyring_entry(&self) -> Result<keyring::Entry, GoogleError> {
+        Ok(keyring::Entry::new(
+            KEYRING_SERVICE,
+            &format!("google:{}", self.client_id),
+        )?)
+    }
+
+    /// Whether a refresh token from a previous sign in is saved.
+    pub fn is_signed_in(&self) -> bool {
+        self.keyring_entry()
+            .and_then(|entry| Ok(entry.get_password()?))
+            .is_ok()
+    }
+
+    /// Signs in with the saved refresh token, or by opening the consent page in the browser and
+    /// waiting for it to redirect back. Blocks until then.
+    pub fn sign_in(&self) -> Result<GoogleAccount, GoogleError> {
+        let entry = self.keyring_entry()?;
+        if let Ok(refresh_token) = entry.get_password() {
+            let token = self.token(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", &refresh_token),
+            ]);
+            match token {
+                Ok(token) => {
+                    return Ok(GoogleAccount {
+                        access_token: token.access_token,
+                    })
+                }
+                // probably revoked, so sign in again
+                Err(GoogleError::Http(_)) => entry.delete_credential()?,
+                Err(e) => return Err(e),
+            }
+        }
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let redirect_uri = format!("http://127.0.0.1:{}", listener.local_addr()?.port());
+        let verifier = random_string()?;
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(&verifier));
+        let url = format!(
+            "{AUTH_URL}?{}",
+            query(&[
+                ("client_id", &self.client_id),
+                ("redirect_uri", &redirect_uri),
+                ("response_type", "code"),
+                ("scope", SCOPE),
+                ("code_challenge", &challenge),
+                ("code_challenge_method", "S256"),
+                ("access_type", "offline"),
+                ("prompt", "consent"),
+            ])
+        );
+        open::that(&url)?;
+
+        let code = wait_for_code(&listener)?;
+        let token = self.token(&[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("code_verifier", &verifier),
+            ("redirect_uri", &redirect_uri),
+        ])?;
+        if let Some(refresh_token) = &token.refresh_token {
+            entry.set_password(refresh_token)?;
+        }
+        Ok(GoogleAccount {
+            access_token: token.access_token,
+        })
+    }
+
+    /// Forgets the saved refresh token.
+    pub fn sign_out(&self) -> Result<(), GoogleError> {
+        match self.keyring_entry()?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn token(&self, params: &[(&str, &str)]) -> Result<TokenResponse, GoogleError> {
+        let body = ureq::post(TOKEN_URL)
+            .send_form(
+                [
+                    ("client_id", self.client_id.as_str()),
+                    ("client_secret", self.client_secret.as_str()),
+                ]
+                .into_iter()
+                .chain(params.iter().copied()),
+            )?
+            .body_mut()
+            .read_to_string()?;
+        Ok(serde_json::from_str(&body)?)
+    }
+}
+
+impl GoogleAccount {
+    /// The calendars that events can be added to, with the primary calendar first.
+    pub fn calendars(&self) -> Result<Vec<GoogleCalendar>, GoogleError> {
+        let body = ureq::get(format!("{API_URL}/users/me/calendarList"))
+            .query("minAccessRole", "writer")
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .call()?
+            .body_mut()
+            .read_to_string()?;
+        let list: Value = serde_json::from_str(&body)?;
+
+        let mut calendars = list["items"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|item| {
+                let primary = item["primary"].as_bool().unwrap_or_default();
+                let calendar = GoogleCalendar {
+                    id: item["id"].as_str().unwrap_or_default().to_owned(),
+                    name: item["summary"].as_str().unwrap_or_default().to_owned(),
+                };
+                (!primary, calendar)
+            })
+            .collect::<Vec<_>>();
+        calendars.sort_by_key(|(secondary, _)| *secondary);
+        Ok(calendars
+            .into_iter()
+            .map(|(_, calendar)| calendar)
+            .collect())
+    }
+
+    /// Creates or updates every event of `schedule` in `calendar_id`, and returns how many there
+    /// were. The arguments are used the same way as by [`write_calendars`](crate::write_calendars).
+    pub fn push(
+        &self,
+        calendar_id: &str,
+        schedule: &Schedule,
+        exdate: &HashSet<NaiveDate>,
+        profile: &InstitutionProfile,
+        options: &GenerateOptions,
+    ) -> Result<usize, GoogleError> {
+        let meetings = meetings(schedule, profile, options);
+        let sequence = default_sequence();
+        for meeting in &meetings {
+            let event = event_resource(meeting, exdate, profile, options, sequence);
+            ureq::post(format!(
+                "{API_URL}/calendars/{}/events/import",
+                percent_encode(calendar_id)
+            ))
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .header("Content-Type", "application/json")
+            .send(event.to_string())?;
+        }
+        Ok(meetings.len())
+    }
+}
+
+/// The Calendar API event for `meeting`. Importing it replaces any event with the same UID.
+fn event_resource(
+    meeting: &Meeting,
+    exdate: &HashSet<NaiveDate>,
+    profile: &InstitutionProfile,
+    options: &GenerateOptions,
+    sequence: i64,
+) -> Value {
+    let timezone = &profile.timezone;
+    let mut recurrence = Vec::new();
+    let (start, end) = match meeting.date_range {
+        Some(date_range) => {
+            let (start_time, end_time) = options.event_times(date_range);
+            let date_time = |date: NaiveDate| {
+                date.and_time(start_time)
+                    .format("%Y%m%dT%H%M%S")
+                    .to_string()
+            };
+            let date_times = |dates: Vec<NaiveDate>| {
+                dates
+                    .into_iter()
+                    .map(date_time)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            };
+            // one-off events like exams don't repeat
+            if !meeting.event.rrule.is_empty() {
+                recurrence.push(format!(
+                    "RRULE:{}",
+                    google_rrule(&meeting.event.rrule, meeting.until, timezone)
+                ));
+                let swap_dates = meeting.swap_dates(&options.swap_days, exdate);
+                if !swap_dates.is_empty() {
+                    recurrence.push(format!("RDATE;TZID={timezone}:{}", date_times(swap_dates)));
+                }
+                let excluded_dates =
+                    meeting.excluded_dates(&with_swap_days(exdate, &options.swap_days));
+                if !excluded_dates.is_empty() {
+                    recurrence.push(format!(
+                        "EXDATE;TZID={timezone}:{}",
+                        date_times(excluded_dates)
+                    ));
+                }
+            }
+
+            let date_time = |time| {
+                json!({
+                    "dateTime": meeting.first_date.and_time(time).format("%Y-%m-%dT%H:%M:%S").to_string(),
+                    "timeZone": timezone,
+                })
+            };
+            (date_time(start_time), date_time(end_time))
+        }
+        None => {
+            if !meeting.event.rrule.is_empty() {
+                recurrence.push(format!("RRULE:{}", meeting.event.rrule));
+            }
+            let date = |date: NaiveDate| json!({ "date": date.format("%Y-%m-%d").to_string() });
+            (
+                date(meeting.first_date),
+                date(meeting.first_date.succ_opt().unwrap()),
+            )
+        }
+    };
+
+    let reminders = match options.reminders.get(&meeting.class.class_type) {
+        Some(minutes) => json!({
+            "useDefault": false,
+            "overrides": [{ "method": "popup", "minutes": minutes }],
+        }),
+        None => json!({ "useDefault": false }),
+    };
+
+    json!({
+        "iCalUID": meeting.uid,
+        "sequence": sequence,
+        "summary": meeting.event.summary,
+        "description": meeting.event.description,
+        "location": meeting.event.location,
+        "start": start,
+        "end": end,
+        "recurrence": recurrence,
+        "reminders": reminders,
+        "transparency": if meeting.is_busy(options) { "opaque" } else { "transparent" },
+        "visibility": if options.private_events { "private" } else { "default" },
+    })
+}
+
+/// Google rejects the `TZID` part and a local `UNTIL`, so end the rule at the end of `until` in
+/// `timezone`, converted to UTC. An unknown time zone is taken as UTC.
+fn google_rrule(rrule: &str, until: NaiveDate, timezone: &str) -> String {
+    let end = until.and_hms_opt(23, 59, 59).unwrap();
+    let end = timezone
+        .parse::<Tz>()
+        .ok()
+        .and_then(|tz| tz.from_local_datetime(&end).latest())
+        .map_or(end, |end| end.naive_utc());
+    rrule
+        .split(';')
+        .filter(|part| !part.starts_with("TZID="))
+        .map(|part| {
+            if part.starts_with("UNTIL=") {
+                format!("UNTIL={}", end.format("%Y%m%dT%H%M%SZ"))
+            } else {
+                part.to_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Accepts connections until the browser is redirected back with the authorization code.
+fn wait_for_code(listener: &TcpListener) -> Result<String, GoogleError> {
+    listener.set_nonblocking(true)?;
+    let started = Instant::now();
+    loop {
+        let mut stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                if started.elapsed() > SIGN_IN_TIMEOUT {
+                    return Err(GoogleError::SignIn("timed out".to_owned()));
+                }
+                thread::sleep(Duration::from_millis(100));
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        stream.set_nonblocking(false)?;
+
+        // eg. "GET /?code=...&scope=... HTTP/1.1"
+        let mut request_line = String::new();
+        BufReader::new(&stream).read_line(&mut request_line)?;
+        let params = request_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|path| path.split_once('?'))
+            .map(|(_, query)| query)
+            .unwrap_or_default()
+            .split('&')
+            .filter_map(|param| param.split_once('='))
+            .map(|(key, value)| (key.to_owned(), percent_decode(value)))
+            .collect::<Vec<_>>();
+        let param = |name| params.iter().find(|(key, _)| key == name).map(|(_, v)| v);
+
+        let result = match (param("code"), param("error")) {
+            (Some(code), _) => Ok(code.clone()),
+            (_, Some(error)) => Err(GoogleError::SignIn(error.clone())),
+            // eg. the browser asking for a favicon
+            _ => {
+                write!(
+                    stream,
+                    "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n"
+                )?;
+                continue;
+            }
+        };
+        let message = match &result {
+            Ok(_) => "Signed in. You can close this tab and go back to mycampus-calendar-rs.",
+            Err(_) => "Sign in failed. You can close this tab and try again.",
+        };
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\n\r\n{message}",
+            message.len()
+        )?;
+        return result;
+    }
+}
+
+/// A random PKCE code verifier.
+fn random_string() -> Result<String, GoogleError> {
+    let mut bytes = [0; 48];
+    getrandom::getrandom(&mut bytes).map_err(|e| GoogleError::SignIn(e.to_string()))?;
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+fn query(params: &[(&str, &str)]) -> String {
+    params
+        .iter()
+        .map(|(key, value)| format!("{key}={}", percent_encode(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::SwapDay, Parser};
+    use chrono::Weekday;
+
+    #[test]
+    fn test_event_resource() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let profile = InstitutionProfile::default();
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let options = GenerateOptions {
+            reminders: [("Lecture".to_owned(), 10)].into(),
+            swap_days: vec![SwapDay {
+                date: date(12, 3),
+                weekday: Weekday::Mon,
+            }],
+            ..Default::default()
+        };
+        let meetings = meetings(&schedule, &profile, &options);
+        let event = event_resource(
+            &meetings[0],
+            &HashSet::from([date(10, 14)]),
+            &profile,
+            &options,
+            1,
+        );
+
+        assert_eq!(event["iCalUID"], meetings[0].uid);
+        assert_eq!(event["start"]["timeZone"], profile.timezone);
+        assert_eq!(event["reminders"]["overrides"][0]["minutes"], 10);
+        let recurrence = event["recurrence"].as_array().unwrap();
+        assert_eq!(recurrence.len(), 3);
+        // the last class is on December 3, at the end of which it's 4:59:59 in UTC
+        assert_eq!(recurrence[0], "RRULE:FREQ=WEEKLY;UNTIL=20241204T045959Z");
+        assert!(recurrence[1].as_str().unwrap().starts_with("RDATE;TZID="));
+        assert!(recurrence[2]
+            .as_str()
+            .unwrap()
+            .ends_with(":20241014T111000"));
+
+        // an hour less during daylight saving time
+        assert_eq!(
+            google_rrule(
+                "FREQ=WEEKLY;UNTIL=20240621T235959;BYDAY=FR",
+                date(6, 21),
+                "America/Toronto"
+            ),
+            "FREQ=WEEKLY;UNTIL=20240622T035959Z;BYDAY=FR"
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::{
+    collections::HashSet,
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use chrono::NaiveDate;
+use eframe::egui::{self, Button, ComboBox, Grid, TextEdit};
+use mycampus_calendar_rs::{
+    google::{GoogleAccount, GoogleCalendar, GoogleClient},
+    parser::Schedule,
+    GenerateOptions, InstitutionProfile,
+};
+
+use crate::{
+    crash,
+    i18n::{tr, tr_args},
+};
+
+enum Message {
+    SignedIn(Result<(GoogleAccount, Vec<GoogleCalendar>), String>),
+    Pushed(Result<usize, String>),
+}
+
+/// Signing in to Google and pushing the events to one of the account's calendars. The network
+/// requests run on a background thread, since signing in waits for the browser.
+#[derive(Default)]
+pub struct GooglePush {
+    pub client: GoogleClient,
+    account: Option<GoogleAccount>,
+    calendars: Vec<GoogleCalendar>,
+    calendar: usize,
+    status: Option<String>,
+    pending: Option<Receiver<Message>>,
+}
+
+impl GooglePush {
+    pub fn new(client: GoogleClient) -> Self {
+        Self {
+            client,
+            ..Default::default()
+        }
+    }
+
+    /// Returns true if the events should be pushed with [`GooglePush::push`].
+    pub fn ui(&mut self, ui: &mut egui::Ui, can_push: bool) -> bool {
+        if let Some(message) = crash::try_recv(&mut self.pending, &mut self.status) {
+            self.pending = None;
+            self.status = match message {
+                Message::SignedIn(Ok((account, calendars))) => {
+                    self.account = Some(account);
+                    self.calendars = calendars;
+                    self.calendar = 0;
+                    None
+                }
+                Message::SignedIn(Err(e)) => Some(format!("⚠ {e}")),
+                Message::Pushed(Ok(n)) => Some(tr_args("pushed-events", &[("events", n.into())])),
+                Message::Pushed(Err(e)) => Some(format!("⚠ {e}")),
+            };
+        }
+        let busy = self.pending.is_some();
+        let mut push = false;
+
+        match &self.account {
+            None => {
+                ui.label(tr("google-description"));
+                Grid::new("google_client").num_columns(2).show(ui, |ui| {
+                    ui.label(tr("client-id"));
+                    ui.add(TextEdit::singleline(&mut self.client.client_id).desired_width(300.0));
+                    ui.end_row();
+                    ui.label(tr("client-secret"));
+                    ui.add(
+                        TextEdit::singleline(&mut self.client.client_secret)
+                            .password(true)
+                            .desired_width(300.0),
+                    );
+                    ui.end_row();
+                });
+                if ui
+                    .add_enabled(
+                        !busy && !self.client.client_id.is_empty(),
+                        Button::new(tr("google-sign-in")),
+                    )
+                    .clicked()
+                {
+                    self.sign_in(ui.ctx().clone());
+                }
+            }
+            Some(_) => {
+                ui.horizontal(|ui| {
+                    ui.label(tr("calendar"));
+                    ComboBox::from_id_source("google_calendar")
+                        .selected_text(
+                            self.calendars
+                                .get(self.calendar)
+                                .map_or("", |calendar| calendar.name.as_str()),
+                        )
+                        .show_ui(ui, |ui| {
+                            for (i, calendar) in self.calendars.iter().enumerate() {
+                                ui.selectable_value(&mut self.calendar, i, &calendar.name);
+                            }
+                        });
+                });
+                ui.horizontal(|ui| {
+                    push = ui
+                        .add_enabled(
+                            !busy && can_push && self.calendar < self.calendars.len(),
+                            Button::new(tr("push-events")),
+                        )
+                        .on_hover_text(tr("push-events-hover"))
+                        .clicked();
+                    if ui.add_enabled(!busy, Button::new(tr("sign-out"))).clicked() {
+                        self.status = self.client.sign_out().err().map(|e| format!("⚠ {e}"));
+                        self.account = None;
+                        self.calendars.clear();
+                    }
+                });
+            }
+        }
+
+        ui.horizontal(|ui| {
+            if busy {
+                ui.spinner();
+            }
+            if let Some(status) = &self.status {
+                ui.label(status);
+            }
+        });
+        push
+    }
+
+    fn sign_in(&mut self, ctx: egui::Context) {
+        let (tx, rx) = mpsc::channel();
+        let client = self.client.clone();
+        thread::spawn(move || {
+            let result = client
+                .sign_in()
+                .and_then(|account| Ok((account.clone(), account.calendars()?)))
+                .map_err(|e| e.to_string());
+            tx.send(Message::SignedIn(result)).ok();
+            ctx.request_repaint();
+        });
+        self.status = Some(tr("waiting-for-browser"));
+        self.pending = Some(rx);
+    }
+
+    pub fn push(
+        &mut self,
+        ctx: egui::Context,
+        schedule: Schedule,
+        exdate: HashSet<NaiveDate>,
+        profile: InstitutionProfile,
+        options: GenerateOptions,
+    ) {
+        let (Some(account), Some(calendar)) = (&self.account, self.calendars.get(self.calendar))
+        else {
+            return;
+        };
+        let (tx, rx) = mpsc::channel();
+        let (account, calendar_id) = (account.clone(), calendar.id.clone());
+        thread::spawn(move || {
+            let result = account
+                .push(&calendar_id, &schedule, &exdate, &profile, &options)
+                .map_err(|e| e.to_string());
+            tx.send(Message::Pushed(result)).ok();
+            ctx.request_repaint();
+        });
+        self.status = Some(tr("pushing-events"));
+        self.pending = Some(rx);
+    }
+}
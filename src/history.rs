@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use web_time::Instant;
+
+/// Changes closer together than this are undone together, eg. typing a word.
+const MERGE_WINDOW: Duration = Duration::from_secs(1);
+const MAX_UNDO: usize = 100;
+
+/// Undo and redo for a snapshot of the app's state that's recorded every frame.
+#[derive(Default)]
+pub struct History<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+    current: Option<T>,
+    last_change: Option<Instant>,
+}
+
+impl<T: Clone + PartialEq> History<T> {
+    /// Records `state`. If it changed since the last call, the previous state can be undone to.
+    pub fn update(&mut self, state: &T) {
+        match &self.current {
+            Some(current) if current == state => return,
+            Some(current) => {
+                if self
+                    .last_change
+                    .is_none_or(|last_change| last_change.elapsed() >= MERGE_WINDOW)
+                {
+                    self.undo.push(current.clone());
+                    if self.undo.len() > MAX_UNDO {
+                        self.undo.remove(0);
+                    }
+                }
+                self.redo.clear();
+                self.last_change = Some(Instant::now());
+            }
+            None => (),
+        }
+        self.current = Some(state.clone());
+    }
+
+    /// Records `state` without making the change undoable, eg. for the result of parsing.
+    pub fn replace(&mut self, state: T) {
+        self.current = Some(state);
+    }
+
+    /// The state to go back to, if there is one.
+    pub fn undo(&mut self) -> Option<T> {
+        let previous = self.undo.pop()?;
+        self.redo.extend(self.current.replace(previous.clone()));
+        self.last_change = None;
+        Some(previous)
+    }
+
+    /// The state that was last undone, if nothing's changed since.
+    pub fn redo(&mut self) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.extend(self.current.replace(next.clone()));
+        self.last_change = None;
+        Some(next)
+    }
+}
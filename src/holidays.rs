@@ -0,0 +1,100 @@
+//! Importing excluded dates from a holidays calendar, eg. a public holiday feed.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::{fs, io};
+
+use chrono::{Duration, NaiveDate};
+#[cfg(not(target_arch = "wasm32"))]
+use thiserror::Error;
+
+use crate::update::parse_events;
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Error)]
+pub enum HolidaysError {
+    #[error("Failed to read holidays calendar: {0}")]
+    Io(#[from] io::Error),
+    #[error("Failed to download holidays calendar: {0}")]
+    Http(#[from] ureq::Error),
+}
+
+/// Reads a holidays calendar from a file path or an `http(s)://` URL.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load(source: &str) -> Result<String, HolidaysError> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        Ok(ureq::get(source).call()?.body_mut().read_to_string()?)
+    } else {
+        Ok(fs::read_to_string(source)?)
+    }
+}
+
+/// The date range of each event in `calendar`, sorted and without duplicates.
+///
+/// Only the first occurrence of recurring events is used.
+pub fn excluded_dates(calendar: &str) -> Vec<(NaiveDate, NaiveDate)> {
+    let mut dates = parse_events(calendar)
+        .into_values()
+        .filter_map(|event| {
+            let (start, _) = parse_date(&event.start)?;
+            let end = match parse_date(&event.end) {
+                // all-day events end at the start of the next day
+                Some((end, true)) => end - Duration::days(1),
+                Some((end, false)) => end,
+                None => start,
+            };
+            Some((start, end.max(start)))
+        })
+        .collect::<Vec<_>>();
+    dates.sort();
+    dates.dedup();
+    dates
+}
+
+/// The date of a `DTSTART`/`DTEND` value, and whether it's a date without a time.
+fn parse_date(value: &str) -> Option<(NaiveDate, bool)> {
+    let value = value.rsplit(':').next()?;
+    let date = NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()?;
+    Some((date, value.len() == 8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_excluded_dates() {
+        let calendar = indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            UID:thanksgiving
+            DTSTART;VALUE=DATE:20241014
+            DTEND;VALUE=DATE:20241015
+            SUMMARY:Thanksgiving
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:study-week
+            DTSTART;VALUE=DATE:20241014
+            DTEND;VALUE=DATE:20241019
+            SUMMARY:Study week
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:closure
+            DTSTART;TZID=America/Toronto:20241203T120000
+            DTEND;TZID=America/Toronto:20241203T170000
+            SUMMARY:Early closure
+            END:VEVENT
+            END:VCALENDAR
+        "};
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+
+        assert_eq!(
+            excluded_dates(calendar),
+            [
+                (date(10, 14), date(10, 14)),
+                (date(10, 14), date(10, 18)),
+                (date(12, 3), date(12, 3)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Loading the schedule page saved as HTML, by converting it to text laid out the way browsers
+//! copy it, so the regular [`Parser`](crate::Parser) can read it. Saving the page keeps the
+//! structure that copying sometimes mangles, eg. table cells running into each other.
+
+/// Elements whose contents aren't shown on the page.
+const HIDDEN: [&str; 5] = ["head", "script", "style", "template", "noscript"];
+
+/// Elements that start on a new line.
+const BLOCKS: [&str; 27] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "p",
+    "section",
+    "tr",
+];
+
+/// Elements that are separated by tabs, like copied table rows.
+const CELLS: [&str; 2] = ["td", "th"];
+
+/// Converts `html` to text: block elements start new lines, table cells are separated by tabs,
+/// whitespace is collapsed like a browser would, and hidden elements are left out.
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut hidden: Option<String> = None;
+    // whether the next table cell is the first in its row
+    let mut first_cell = true;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            if hidden.is_none() {
+                push_text(&mut text, rest);
+            }
+            break;
+        };
+        if hidden.is_none() {
+            push_text(&mut text, &rest[..start]);
+        }
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+        let tag = &rest[1..end.saturating_sub(1).max(1)];
+        rest = &rest[end..];
+
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if let Some(hidden_name) = &hidden {
+            if closing && name == *hidden_name {
+                hidden = None;
+            }
+            continue;
+        }
+        if !closing && HIDDEN.contains(&name.as_str()) && !tag.ends_with('/') {
+            hidden = Some(name);
+        } else if BLOCKS.contains(&name.as_str()) {
+            new_line(&mut text);
+            if name == "tr" {
+                first_cell = true;
+            }
+        } else if !closing && CELLS.contains(&name.as_str()) {
+            if first_cell {
+                new_line(&mut text);
+            } else {
+                trim_end_spaces(&mut text);
+                text.push('\t');
+            }
+            first_cell = false;
+        }
+    }
+
+    trim_end_spaces(&mut text);
+    text.replace('\u{a0}', " ").trim_matches('\n').to_owned()
+}
+
+/// Appends text content, collapsing whitespace and decoding character references.
+fn push_text(text: &mut String, content: &str) {
+    for c in decode_entities(content).chars() {
+        if c.is_ascii_whitespace() {
+            if !text.is_empty() && !text.ends_with([' ', '\n', '\t']) {
+                text.push(' ');
+            }
+        } else {
+            text.push(c);
+        }
+    }
+}
+
+fn new_line(text: &mut String) {
+    trim_end_spaces(text);
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+}
+
+fn trim_end_spaces(text: &mut String) {
+    text.truncate(text.trim_end_matches(' ').len());
+}
+
+pub(crate) fn decode_entities(content: &str) -> String {
+    let mut decoded = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| Some((decode_entity(&rest[1..end])?, end)));
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some('\u{a0}'),
+        "ndash" => Some('–'),
+        "mdash" => Some('—'),
+        _ => {
+            let code = match name.strip_prefix("#x").or(name.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => name.strip_prefix('#')?.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    #[test]
+    fn test_html_to_text() {
+        assert_eq!(
+            html_to_text(
+                "<html><head><title>x</title></head><body>\n  <div>a &amp;\n b</div>\
+                <script>var s = '<div>';</script><!-- <p>hidden</p> -->\
+                <table><tr><th>Title</th> <th>CRN</th></tr>\
+                <tr><td>Intro</td><td>40001</td></tr></table>\
+                <p>1&nbsp;&nbsp;2&#x21;</p></body></html>"
+            ),
+            "a & b\nTitle\tCRN\nIntro\t40001\n1  2!"
+        );
+    }
+
+    #[test]
+    fn test_parse_saved_page() {
+        let text = html_to_text(include_str!("../tests/data/schedule.html"));
+        let schedule = Parser::new().parse_data(&text).unwrap();
+        let want = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+
+        assert!(schedule.failures.is_empty());
+        assert_eq!(schedule.term, want.term);
+        assert_eq!(
+            serde_json::to_value(&schedule.classes).unwrap(),
+            serde_json::to_value(&want.classes).unwrap()
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! Helpers shared by the clients of the web services the calendars can be pushed to.
+
+/// Service name the sign-in tokens and passwords are saved under in the system keyring.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) const KEYRING_SERVICE: &str = "mycampus-calendar-rs";
+
+/// Encodes `value` for a URL path segment or query parameter.
+pub(crate) fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// Decodes a query parameter, eg. from a redirect back to the app.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn percent_decode(value: &str) -> String {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, after)) = rest.split_first() {
+        let hex = after
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (byte, hex) {
+            (b'%', Some(decoded)) => {
+                bytes.push(decoded);
+                rest = &after[2..];
+            }
+            (b'+', _) => {
+                bytes.push(b' ');
+                rest = after;
+            }
+            _ => {
+                bytes.push(byte);
+                rest = after;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_encoding() {
+        let value = "4/0Ab_c d+e?f=g&h";
+        assert_eq!(percent_encode(value), "4%2F0Ab_c%20d%2Be%3Ff%3Dg%26h");
+        assert_eq!(percent_decode(&percent_encode(value)), value);
+        assert_eq!(percent_decode("a+b%2"), "a b%2");
+    }
+}
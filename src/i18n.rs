@@ -0,0 +1,164 @@
+//! Translations of the interface, from the Fluent files in `locales`.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use chrono::Weekday;
+use fluent::{concurrent::FluentBundle, FluentArgs, FluentResource, FluentValue};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+const ENGLISH_FTL: &str = include_str!("../locales/en.ftl");
+const FRENCH_FTL: &str = include_str!("../locales/fr-CA.ftl");
+
+static ENGLISH: Lazy<FluentBundle<FluentResource>> = Lazy::new(|| bundle("en", ENGLISH_FTL));
+static FRENCH: Lazy<FluentBundle<FluentResource>> = Lazy::new(|| bundle("fr-CA", FRENCH_FTL));
+
+/// The resolved [`Language`] that's shown.
+static CURRENT: AtomicU8 = AtomicU8::new(Language::English as u8);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    /// The operating system's language, if it's translated.
+    #[default]
+    System,
+    English,
+    French,
+}
+
+impl Language {
+    pub const ALL: [Self; 3] = [Self::System, Self::English, Self::French];
+
+    /// Its name in itself, for picking it.
+    pub fn name(self) -> String {
+        match self {
+            Self::System => tr("language-system"),
+            Self::English => "English".to_owned(),
+            Self::French => "Français".to_owned(),
+        }
+    }
+
+    fn resolve(self) -> Self {
+        match self {
+            Self::System => match sys_locale::get_locale() {
+                Some(locale) if locale.starts_with("fr") => Self::French,
+                _ => Self::English,
+            },
+            language => language,
+        }
+    }
+}
+
+pub fn set_language(language: Language) {
+    CURRENT.store(language.resolve() as u8, Ordering::Relaxed);
+}
+
+fn bundle(locale: &str, ftl: &str) -> FluentBundle<FluentResource> {
+    let resource = FluentResource::try_new(ftl.to_owned()).unwrap_or_else(|(resource, errors)| {
+        tracing::warn!("Errors in the {locale} translation: {errors:?}");
+        resource
+    });
+    let mut bundle = FluentBundle::new_concurrent(vec![locale.parse().unwrap_or_default()]);
+    // egui shows the bidi isolation marks around arguments as boxes
+    bundle.set_use_isolating(false);
+    if let Err(errors) = bundle.add_resource(resource) {
+        tracing::warn!("Errors in the {locale} translation: {errors:?}");
+    }
+    bundle
+}
+
+fn format(
+    bundle: &FluentBundle<FluentResource>,
+    key: &str,
+    args: Option<&FluentArgs>,
+) -> Option<String> {
+    let pattern = bundle.get_message(key)?.value()?;
+    let mut errors = Vec::new();
+    let message = bundle.format_pattern(pattern, args, &mut errors);
+    if !errors.is_empty() {
+        tracing::warn!("Errors formatting {key}: {errors:?}");
+    }
+    Some(message.into_owned())
+}
+
+fn translate(key: &str, args: Option<&FluentArgs>) -> String {
+    let bundle: &FluentBundle<FluentResource> = match CURRENT.load(Ordering::Relaxed) {
+        language if language == Language::French as u8 => &FRENCH,
+        _ => &ENGLISH,
+    };
+    format(bundle, key, args)
+        .or_else(|| format(&ENGLISH, key, args))
+        .unwrap_or_else(|| key.to_owned())
+}
+
+/// The message for `key` in the current language, falling back to English, then to the key.
+pub fn tr(key: &str) -> String {
+    translate(key, None)
+}
+
+/// Like [`tr`], with the `{ $name }` placeables filled in from `args`.
+pub fn tr_args(key: &str, args: &[(&str, FluentValue)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+    translate(key, Some(&fluent_args))
+}
+
+/// The short name of `weekday`, eg. "Mon".
+pub fn weekday_name(weekday: Weekday) -> String {
+    tr(match weekday {
+        Weekday::Mon => "weekday-mon",
+        Weekday::Tue => "weekday-tue",
+        Weekday::Wed => "weekday-wed",
+        Weekday::Thu => "weekday-thu",
+        Weekday::Fri => "weekday-fri",
+        Weekday::Sat => "weekday-sat",
+        Weekday::Sun => "weekday-sun",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    /// The message keys in `ftl`, checking that it parses and that every message has a value.
+    fn keys(locale: &str, ftl: &str) -> BTreeSet<String> {
+        assert!(
+            FluentResource::try_new(ftl.to_owned()).is_ok(),
+            "{locale} translation should parse"
+        );
+        let bundle = bundle(locale, ftl);
+        ftl.lines()
+            .filter_map(|line| line.split_once(" ="))
+            .map(|(key, _)| key)
+            .filter(|key| key.starts_with(|c: char| c.is_ascii_lowercase()))
+            .map(|key| {
+                let message = bundle.get_message(key).expect("message should exist");
+                assert!(message.value().is_some(), "{key} has no value");
+                key.to_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_translations_match() {
+        assert_eq!(keys("en", ENGLISH_FTL), keys("fr-CA", FRENCH_FTL));
+
+        set_language(Language::French);
+        assert_eq!(
+            tr_args("update-available", &[("version", "v0.3.0".into())]),
+            "La version v0.3.0 est disponible."
+        );
+        set_language(Language::English);
+        assert_eq!(
+            tr_args(
+                "status-detected",
+                &[("meetings", 1.into()), ("classes", 2.into())]
+            ),
+            "✔ 1 meeting across 2 classes detected"
+        );
+        assert_eq!(tr("missing-key"), "missing-key");
+    }
+}
@@ -0,0 +1,352 @@
+//! Reading the calendars this tool wrote back into a [`Schedule`], so a previous export can be
+//! edited in the preview and written again.
+
+use std::collections::HashMap;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Weekday};
+use thiserror::Error;
+
+use crate::{
+    parser::{Class, DateRange, DateRangeKind, Schedule},
+    travel,
+};
+
+/// Property with a class as JSON, so it can be read back exactly. It's only on the first event of
+/// each class, since the class has all of its meetings.
+pub const CLASS_PROPERTY: &str = "X-MYCAMPUS-CLASS";
+
+#[derive(Debug, Clone, Error)]
+pub enum ImportError {
+    #[error("This calendar wasn't made by mycampus-calendar-rs")]
+    NotGenerated,
+    #[error("No classes were found in the calendar")]
+    NoClasses,
+}
+
+/// Whether `calendar` was written by this tool.
+pub fn is_generated(calendar: &str) -> bool {
+    calendar
+        .lines()
+        .any(|line| line.trim_end() == "PRODID:MYCAMPUS-CALENDAR-RS")
+}
+
+/// Reads the classes back from calendars written by this tool. Events from older versions, which
+/// don't have [`CLASS_PROPERTY`], are pieced back together from their times, title, location and
+/// description, so details that aren't in those are lost.
+pub fn read_calendars<'a>(
+    calendars: impl IntoIterator<Item = &'a str>,
+) -> Result<Schedule, ImportError> {
+    let mut schedule = Schedule::default();
+    for calendar in calendars {
+        if !is_generated(calendar) {
+            return Err(ImportError::NotGenerated);
+        }
+        read_calendar(calendar, &mut schedule);
+    }
+    if schedule.classes.is_empty() {
+        return Err(ImportError::NoClasses);
+    }
+    Ok(schedule)
+}
+
+fn read_calendar(calendar: &str, schedule: &mut Schedule) {
+    let unfolded = calendar.replace("\r\n", "\n").replace("\n ", "");
+    // the other events of each class are already in its property
+    let has_class_property = unfolded
+        .lines()
+        .any(|line| line.starts_with(CLASS_PROPERTY));
+
+    let mut event: Option<HashMap<&str, &str>> = None;
+    for line in unfolded.lines() {
+        match line {
+            "BEGIN:VEVENT" => event = Some(HashMap::new()),
+            "END:VEVENT" => {
+                if let Some(class) = event
+                    .take()
+                    .filter(|event| !has_class_property || event.contains_key(CLASS_PROPERTY))
+                    .and_then(|event| event_class(&event))
+                {
+                    add_class(schedule, class);
+                }
+            }
+            _ => {
+                let Some(split) = line.find([';', ':']) else {
+                    continue;
+                };
+                let (name, value) = (&line[..split], &line[split + 1..]);
+                match &mut event {
+                    // only the first of each property, so VALARM's DESCRIPTION is left out
+                    Some(event) => {
+                        event.entry(name).or_insert(value);
+                    }
+                    None if name == "X-WR-CALNAME" && schedule.term.is_empty() => {
+                        // eg. "Fall 2024 – Lecture"
+                        if let Some((term, _)) = value.split_once(" – ") {
+                            schedule.term = term.to_owned();
+                        }
+                    }
+                    None => (),
+                }
+            }
+        }
+    }
+}
+
+/// Adds `class` to `schedule`, or its meetings to the same class if it's already there, since
+/// each meeting is a separate event.
+fn add_class(schedule: &mut Schedule, class: Class) {
+    let same_class = schedule.classes.iter_mut().find(|other| {
+        other.name == class.name
+            && other.code == class.code
+            && other.crn == class.crn
+            && other.class_type == class.class_type
+    });
+    match same_class {
+        Some(other) => {
+            for date_range in class.date_ranges {
+                if !other.date_ranges.contains(&date_range) {
+                    other.date_ranges.push(date_range);
+                }
+            }
+        }
+        None => schedule.classes.push(class),
+    }
+}
+
+/// The class of the event with `properties`, or `None` for travel events and all-day events
+/// without [`CLASS_PROPERTY`], like the milestones.
+fn event_class(properties: &HashMap<&str, &str>) -> Option<Class> {
+    if properties
+        .get("UID")
+        .is_some_and(|uid| uid.starts_with(travel::UID_PREFIX))
+    {
+        return None;
+    }
+    if let Some(class) = properties
+        .get(CLASS_PROPERTY)
+        .and_then(|class| serde_json::from_str(&unescape_text(class)).ok())
+    {
+        return Some(class);
+    }
+
+    let start = date_time(properties.get("DTSTART")?)?;
+    let end = date_time(properties.get("DTEND")?)?;
+    let rrule = properties
+        .get("RRULE")
+        .map(|rrule| {
+            rrule
+                .split(';')
+                .filter_map(|part| part.split_once('='))
+                .collect::<HashMap<_, _>>()
+        })
+        .unwrap_or_default();
+    let end_date = rrule
+        .get("UNTIL")
+        .and_then(|until| NaiveDate::parse_from_str(until.get(..8)?, "%Y%m%d").ok())
+        .unwrap_or(start.date());
+    let weekdays = match rrule.get("BYDAY") {
+        Some(by_day) => by_day.split(',').filter_map(weekday).collect(),
+        None => vec![start.weekday()],
+    };
+
+    let description = properties
+        .get("DESCRIPTION")
+        .map(|description| unescape_text(description))
+        .unwrap_or_default();
+    let line = |label: &str| {
+        description
+            .lines()
+            .find(|line| line.starts_with(label))
+            .filter(|line| line.len() > label.len())
+            .map(str::to_owned)
+    };
+    let location = properties
+        .get("LOCATION")
+        .map(|location| unescape_text(location))
+        .filter(|location| !location.is_empty());
+
+    Some(Class {
+        name: unescape_text(properties.get("SUMMARY")?),
+        code: line("Code: ")
+            .map(|code| code["Code: ".len()..].to_owned())
+            .unwrap_or_default(),
+        date_ranges: weekdays
+            .into_iter()
+            .map(|weekday| DateRange {
+                start_date: start.date(),
+                end_date,
+                start_time: start.time(),
+                end_time: end.time(),
+                weekday,
+                location: None,
+                building: None,
+                room: location.clone(),
+                biweekly: rrule.get("INTERVAL") == Some(&"2"),
+                kind: if properties.contains_key("RRULE") {
+                    DateRangeKind::Weekly
+                } else {
+                    DateRangeKind::Single
+                },
+            })
+            .collect(),
+        instructor: line("Instructor: "),
+        crn: line("CRN: ").unwrap_or_default(),
+        class_type: properties
+            .get("CATEGORIES")
+            .map(|categories| unescape_text(categories))
+            .unwrap_or_default(),
+        credit_hours: None,
+        asynchronous: Vec::new(),
+        term: String::new(),
+        meeting_url: properties.get("URL").map(|url| url.to_string()),
+    })
+}
+
+/// The local date and time of a `DTSTART` or `DTEND` value, eg.
+/// `TZID=America/Toronto:20240904T111000`. `None` for dates without a time.
+fn date_time(value: &str) -> Option<NaiveDateTime> {
+    let value = value.rsplit(':').next()?;
+    NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y%m%dT%H%M%S").ok()
+}
+
+fn weekday(by_day: &str) -> Option<Weekday> {
+    Some(match by_day {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+/// Reverses the escaping of `TEXT` values.
+pub(crate) fn unescape_text(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, fs};
+
+    use super::*;
+    use crate::{
+        parser::{escape_text, AsyncEvents, GenerateOptions, Grouping},
+        write_calendars, InstitutionProfile, Parser,
+    };
+
+    fn written_calendars(name: &str, options: &GenerateOptions) -> (Schedule, Vec<String>) {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let folder = crate::test_folder(name);
+        let report = write_calendars(
+            &folder,
+            &schedule,
+            HashSet::new(),
+            &InstitutionProfile::default(),
+            options,
+        );
+        let calendars = report
+            .files
+            .iter()
+            .map(|path| fs::read_to_string(path).unwrap())
+            .collect();
+        (schedule, calendars)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let options = GenerateOptions {
+            merge_weekdays: true,
+            async_events: AsyncEvents::FirstDay,
+            expand_occurrences: true,
+            ..Default::default()
+        };
+        let (schedule, calendars) = written_calendars("import-round-trip", &options);
+        let class_properties = calendars
+            .iter()
+            .map(|calendar| calendar.matches(CLASS_PROPERTY).count())
+            .sum::<usize>();
+        assert_eq!(class_properties, schedule.classes.len());
+        let imported = read_calendars(calendars.iter().map(String::as_str)).unwrap();
+
+        assert_eq!(imported.term, "Fall 2024");
+        let mut classes = imported.classes;
+        classes.sort_by(|a, b| a.crn.cmp(&b.crn));
+        assert_eq!(
+            serde_json::to_value(&classes).unwrap(),
+            serde_json::to_value(&schedule.classes).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_read_without_class_property() {
+        let options = GenerateOptions {
+            grouping: Grouping::Combined,
+            summary_template: "{name}".to_owned(),
+            merge_weekdays: true,
+            ..Default::default()
+        };
+        let (schedule, calendars) = written_calendars("import-legacy", &options);
+        let calendar = calendars[0]
+            .replace("\r\n ", "")
+            .split("\r\n")
+            .filter(|line| !line.starts_with(CLASS_PROPERTY))
+            .collect::<Vec<_>>()
+            .join("\r\n");
+        let imported = read_calendars([calendar.as_str()]).unwrap();
+
+        // the asynchronous seminar only has all-day events, if any
+        assert_eq!(imported.classes.len(), 2);
+        let lecture = &imported.classes[0];
+        assert_eq!(lecture.name, schedule.classes[0].name);
+        assert_eq!(lecture.code, "CSCI 1200U");
+        assert_eq!(lecture.crn, "CRN: 40001");
+        assert_eq!(lecture.instructor, schedule.classes[0].instructor);
+        assert_eq!(lecture.class_type, "Lecture");
+        let weekdays = lecture
+            .date_ranges
+            .iter()
+            .map(|d| d.weekday)
+            .collect::<Vec<_>>();
+        assert_eq!(weekdays, [Weekday::Mon, Weekday::Wed]);
+        assert_eq!(
+            lecture.date_ranges[0].start_time,
+            schedule.classes[0].date_ranges[0].start_time
+        );
+        assert_eq!(
+            lecture.date_ranges[0].end_date,
+            schedule.classes[0].date_ranges[0].end_date
+        );
+    }
+
+    #[test]
+    fn test_not_generated() {
+        assert!(matches!(
+            read_calendars(["BEGIN:VCALENDAR\r\nPRODID:-//Other//EN\r\nEND:VCALENDAR"]),
+            Err(ImportError::NotGenerated)
+        ));
+    }
+
+    #[test]
+    fn test_unescape_text() {
+        let text = "Campus: North\nCRN: 1; 2, 3 \\ done";
+        assert_eq!(unescape_text(&escape_text(text)), text);
+    }
+}
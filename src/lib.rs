@@ -0,0 +1,63 @@
+//! Parses student schedules from MyOntarioTech (formerly MyCampus) into `.ics` calendar files.
+//!
+//! ```no_run
+//! use std::collections::HashSet;
+//!
+//! let data = std::fs::read_to_string("schedule.txt").unwrap();
+//! let report = mycampus_calendar_rs::generate("calendars", &data, HashSet::new()).unwrap();
+//! println!("Wrote {} calendar(s)", report.calendars);
+//! ```
+
+pub mod archive;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod banner;
+pub mod batch;
+pub mod bug_report;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod caldav;
+pub mod catalog;
+pub mod conflicts;
+pub mod deadlines;
+pub mod exams;
+pub mod export;
+pub mod free_time;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod google;
+pub mod holidays;
+pub mod html;
+mod http;
+pub mod import;
+pub mod merge;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod ocr;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod outlook;
+pub mod parser;
+pub mod pdf;
+pub mod profile;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod release_check;
+pub mod sanitize;
+pub mod stats;
+pub mod template;
+pub mod term;
+pub mod travel;
+pub mod update;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod webdriver;
+
+pub use parser::{
+    generate, generate_calendars, write_calendars, write_calendars_with_progress, AsyncEvents,
+    Class, CustomEvent, DateOrder, DateRange, DateRangeKind, FormatVersion, GenerateOptions,
+    Grouping, Instructor, ParseError, Parser, Progress, Report, Schedule, SwapDay,
+};
+pub use profile::InstitutionProfile;
+
+/// An empty folder in the temp folder for a test, removing whatever a failed run left there.
+#[cfg(test)]
+pub(crate) fn test_folder(name: &str) -> std::path::PathBuf {
+    let folder = std::env::temp_dir().join(format!("mycampus-calendar-rs-test-{name}"));
+    std::fs::remove_dir_all(&folder).ok();
+    std::fs::create_dir_all(&folder).unwrap();
+    folder
+}
@@ -0,0 +1,213 @@
+//! Logging to a file in the platform's data folder and to the log panel, so problems can be
+//! looked into without running the program from a console.
+
+use std::{
+    collections::VecDeque,
+    fmt::{self, Write as _},
+    fs::{self, File, OpenOptions},
+    io::Write as _,
+    path::{Path, PathBuf},
+    sync::{Mutex, RwLock},
+};
+
+use chrono::Local;
+#[cfg(not(target_arch = "wasm32"))]
+use directories::ProjectDirs;
+use eframe::egui::{self, RichText, ScrollArea};
+use mycampus_calendar_rs::sanitize::Sanitizer;
+use once_cell::sync::Lazy;
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Level, Metadata, Subscriber,
+};
+
+use crate::i18n::tr;
+
+const LOG_FILE_NAME: &str = "mycampus-calendar-rs.log";
+/// Size a log file can grow to before it's rotated.
+const MAX_LOG_SIZE: u64 = 1024 * 1024;
+/// How many old log files to keep, as eg. "mycampus-calendar-rs.log.1".
+const OLD_LOG_FILES: usize = 2;
+/// How many lines to keep for the log panel.
+const MAX_LINES: usize = 500;
+
+static LINES: Lazy<Mutex<VecDeque<String>>> = Lazy::new(Default::default);
+/// Applied to every line before it's written anywhere.
+static SANITIZER: Lazy<RwLock<Sanitizer>> = Lazy::new(Default::default);
+
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl LogFile {
+    fn open(path: PathBuf) -> Option<Self> {
+        if fs::metadata(&path).is_ok_and(|metadata| metadata.len() > MAX_LOG_SIZE) {
+            rotate(&path);
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .ok()?;
+        let size = file.metadata().map_or(0, |metadata| metadata.len());
+        Some(Self { path, file, size })
+    }
+
+    fn write_line(&mut self, line: &str) {
+        if self.size > MAX_LOG_SIZE {
+            rotate(&self.path);
+            match OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+            {
+                Ok(file) => {
+                    self.file = file;
+                    self.size = 0;
+                }
+                Err(_) => return,
+            }
+        }
+        if writeln!(self.file, "{line}").is_ok() {
+            self.size += line.len() as u64 + 1;
+        }
+    }
+}
+
+/// Shifts `path` to `path.1`, `path.1` to `path.2`, and so on, dropping the oldest.
+fn rotate(path: &Path) {
+    let numbered = |n: usize| PathBuf::from(format!("{}.{n}", path.display()));
+    for n in (1..OLD_LOG_FILES).rev() {
+        fs::rename(numbered(n), numbered(n + 1)).ok();
+    }
+    fs::rename(path, numbered(1)).ok();
+}
+
+/// Writes this crate's events to the log file and the log panel.
+struct Logger {
+    file: Mutex<Option<LogFile>>,
+}
+
+impl Subscriber for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target().starts_with("mycampus_calendar_rs") && *metadata.level() <= Level::DEBUG
+    }
+
+    fn new_span(&self, _: &span::Attributes) -> span::Id {
+        // spans aren't used, so they all get the same ID
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let line = SANITIZER.read().unwrap().sanitize(&format!(
+            "{} {:>5} {}{}",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            event.metadata().level(),
+            fields.message,
+            fields.rest
+        ));
+
+        if let Some(file) = self.file.lock().unwrap().as_mut() {
+            file.write_line(&line);
+        }
+        let mut lines = LINES.lock().unwrap();
+        if lines.len() == MAX_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+#[derive(Default)]
+struct Fields {
+    message: String,
+    rest: String,
+}
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            write!(self.message, "{value:?}").ok();
+        } else {
+            write!(self.rest, " {}={value:?}", field.name()).ok();
+        }
+    }
+}
+
+/// Folder the log files are written to, eg. `~/.local/share/mycampus-calendar-rs` on Linux.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn log_folder() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "mycampus-calendar-rs").map(|dirs| dirs.data_dir().to_owned())
+}
+
+/// The web build can't write files, so there it only logs to the log panel.
+#[cfg(target_arch = "wasm32")]
+pub fn log_folder() -> Option<PathBuf> {
+    None
+}
+
+/// Starts logging. Events are still shown in the log panel if the log file can't be opened.
+pub fn init() {
+    let file = log_folder()
+        .filter(|folder| fs::create_dir_all(folder).is_ok())
+        .and_then(|folder| LogFile::open(folder.join(LOG_FILE_NAME)));
+    tracing::subscriber::set_global_default(Logger {
+        file: Mutex::new(file),
+    })
+    .ok();
+}
+
+/// Sets what's removed from the log, eg. with the student's name from newly pasted data.
+pub fn set_sanitizer(sanitizer: Sanitizer) {
+    *SANITIZER.write().unwrap() = sanitizer;
+}
+
+/// `text` with what's removed from the log taken out, eg. for a crash report.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn sanitize(text: &str) -> String {
+    SANITIZER.read().unwrap().sanitize(text)
+}
+
+/// The recent log lines, with buttons to copy them and to open the folder with the log files.
+pub fn log_panel(ui: &mut egui::Ui) {
+    let lines = LINES.lock().unwrap().iter().cloned().collect::<Vec<_>>();
+    ui.horizontal(|ui| {
+        if ui.button(tr("copy")).clicked() {
+            ui.output_mut(|output| output.copied_text = lines.join("\n"));
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(folder) = log_folder() {
+            if ui
+                .button(tr("log-open-folder"))
+                .on_hover_text(folder.display().to_string())
+                .clicked()
+            {
+                open::that(folder).ok();
+            }
+        }
+    });
+    ScrollArea::vertical()
+        .id_source("log")
+        .max_height(150.0)
+        .stick_to_bottom(true)
+        .show(ui, |ui| {
+            if lines.is_empty() {
+                ui.weak(tr("log-empty"));
+            }
+            for line in &lines {
+                ui.label(RichText::new(line).monospace());
+            }
+        });
+}
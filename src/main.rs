@@ -1,186 +1,2495 @@
-mod parser;
+mod background;
+#[cfg(not(target_arch = "wasm32"))]
+mod banner_fetch;
+#[cfg(not(target_arch = "wasm32"))]
+mod browser_fetch;
+mod building_editor;
+#[cfg(not(target_arch = "wasm32"))]
+mod caldav_upload;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
+mod configs;
+#[cfg(not(target_arch = "wasm32"))]
+mod crash;
+mod custom_events;
+mod diagnostics;
+mod exam_schedule;
+mod free_time_view;
+#[cfg(not(target_arch = "wasm32"))]
+mod google_push;
+mod history;
+mod i18n;
+mod logging;
+#[cfg(not(target_arch = "wasm32"))]
+mod outlook_push;
+mod preview;
+mod regex_editor;
+#[cfg(not(target_arch = "wasm32"))]
+mod session;
+mod settings;
+mod subject_editor;
+mod tabs;
+#[cfg(target_arch = "wasm32")]
+mod web;
+mod week_grid;
 
-use std::{collections::HashSet, panic, path::PathBuf};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{collections::BTreeSet, fs, path::PathBuf, process::Command};
+use std::{
+    collections::HashSet,
+    iter,
+    path::Path,
+    sync::{
+        mpsc::{self, Receiver, TryRecvError},
+        Arc,
+    },
+    time::Duration,
+};
 
-use chrono::{Local, NaiveDate};
-use eframe::egui::{self, Button, CentralPanel, ScrollArea, TextEdit, Widget};
+use chrono::{Datelike, Local, NaiveDate, Weekday};
+#[cfg(not(target_arch = "wasm32"))]
+use eframe::egui::CollapsingHeader;
+use eframe::egui::{
+    self, Button, CentralPanel, ComboBox, Key, KeyboardShortcut, Modifiers, ProgressBar,
+    ScrollArea, Slider, TextEdit, Widget,
+};
 use egui_extras::DatePickerButton;
+use i18n::{tr, tr_args, weekday_name, Language};
+use mycampus_calendar_rs::{
+    archive, bug_report, conflicts,
+    export::{self, ExportFormat},
+    html, import,
+    parser::{self, Class, GenerateOptions, Grouping, Progress, Schedule, SwapDay},
+    pdf,
+    sanitize::Sanitizer,
+    update::{self, EventChange, EventTime, MeetingChange},
+    InstitutionProfile,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use mycampus_calendar_rs::{
+    catalog, deadlines, holidays, merge, ocr,
+    release_check::{self, Release, UpdateError},
+    stats::Statistics,
+};
 use once_cell::sync::Lazy;
+#[cfg(not(target_arch = "wasm32"))]
 use rfd::FileDialog;
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
 
 const VERSION: &str = concat!("v", env!("CARGO_PKG_VERSION"));
 
+#[cfg(not(target_arch = "wasm32"))]
 const OUTPUT_FOLDER_KEY: &str = "output_folder";
+#[cfg(not(target_arch = "wasm32"))]
+const RECENT_OUTPUT_FOLDERS_KEY: &str = "recent_output_folders";
+/// How many output folders to offer in the dropdown.
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_RECENT_OUTPUT_FOLDERS: usize = 8;
+const PROFILE_KEY: &str = "institution_profile";
+const CUSTOM_SUBJECTS_KEY: &str = "custom_subjects";
+const SETTINGS_KEY: &str = "settings";
+const CONFIGS_KEY: &str = "configs";
+const TABS_KEY: &str = "tabs";
+/// Where the settings were stored before [`SETTINGS_KEY`], read if that isn't set yet.
+const GENERATE_OPTIONS_KEY: &str = "generate_options";
+const EXCLUDED_DATES_KEY: &str = "excluded_dates";
+const REMEMBER_DATA_KEY: &str = "remember_data";
+#[cfg(not(target_arch = "wasm32"))]
+const CHECK_FOR_UPDATES_KEY: &str = "check_for_updates";
+const LANGUAGE_KEY: &str = "language";
+const UI_SCALE_KEY: &str = "ui_scale";
+const THEME_KEY: &str = "theme";
+const DATA_KEY: &str = "data";
+const SUMMARY_DATA_KEY: &str = "summary_data";
+#[cfg(not(target_arch = "wasm32"))]
+const GOOGLE_CLIENT_KEY: &str = "google_client";
+#[cfg(not(target_arch = "wasm32"))]
+const OUTLOOK_CLIENT_KEY: &str = "outlook_client";
+#[cfg(not(target_arch = "wasm32"))]
+const CALDAV_SERVER_KEY: &str = "caldav_server";
+#[cfg(not(target_arch = "wasm32"))]
+const WEBDRIVER_URL_KEY: &str = "webdriver_url";
+#[cfg(not(target_arch = "wasm32"))]
+const BANNER_TERM_KEY: &str = "banner_term";
+const DATE_ORDER_KEY: &str = "date_order";
+const CUSTOM_EVENTS_KEY: &str = "custom_events";
+#[cfg(not(target_arch = "wasm32"))]
+const DEADLINES_SOURCE_KEY: &str = "deadlines_source";
+/// How often to check on background work while it's running.
+const REPAINT_INTERVAL: Duration = Duration::from_millis(50);
+const SLOW_PARSE: Duration = Duration::from_millis(300);
+/// How long to wait after the last keystroke before parsing the pasted data.
+const PARSE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+const WEEKDAYS: [Weekday; 5] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+];
 
 static DEFAULT_DATE: Lazy<NaiveDate> = Lazy::new(|| Local::now().date_naive());
 
+#[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result {
+    // older versions of macOS add a process serial number when opened from Finder
+    let args = std::env::args_os()
+        .skip(1)
+        .filter(|arg| !arg.to_string_lossy().starts_with("-psn_"))
+        .collect::<Vec<_>>();
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
+
     let app_name = "mycampus-calendar-rs";
-    println!("{app_name} {VERSION}");
+    logging::init();
+    crash::install(VERSION);
+    tracing::info!("{app_name} {VERSION}");
     eframe::run_native(
         app_name,
         eframe::NativeOptions::default(),
-        Box::new(|cc| {
-            let app = match cc.storage {
-                Some(storage) => App {
-                    output_folder: storage
-                        .get_string(OUTPUT_FOLDER_KEY)
-                        .map(|s| s.into())
-                        .take_if(|p: &mut PathBuf| p.is_dir()),
-                    ..Default::default()
-                },
-                None => App::default(),
-            };
-            Ok(Box::<App>::new(app))
-        }),
+        Box::new(|cc| Ok(Box::new(App::new(cc)))),
     )
 }
 
+/// Starts the web build on the canvas in `index.html`.
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    logging::init();
+    tracing::info!("mycampus-calendar-rs {VERSION}");
+    wasm_bindgen_futures::spawn_local(async {
+        let started = eframe::WebRunner::new()
+            .start(
+                "mycampus_calendar",
+                eframe::WebOptions::default(),
+                Box::new(|cc| Ok(Box::new(App::new(cc)))),
+            )
+            .await;
+        if let Err(e) = started {
+            tracing::error!("Failed to start: {e:?}");
+        }
+    });
+}
+
 #[derive(Default)]
 struct App {
     data: String,
+    summary_data: String,
+    remember_data: bool,
+    profile: InstitutionProfile,
+    profile_error: Option<String>,
+    show_regex_editor: bool,
+    regex_test_line: String,
+    custom_subjects: Vec<(String, String)>,
+    configs: configs::Configs,
+    show_subject_editor: bool,
+    show_building_editor: bool,
+    show_settings: bool,
+    settings_status: Option<String>,
+    /// How the dates in the data are read, for browsers that don't use MM/DD/YYYY.
+    date_order: parser::DateOrder,
     excluded_dates: Vec<ExcludedDate>,
+    history: history::History<Snapshot>,
+    #[cfg(not(target_arch = "wasm32"))]
+    autosave: session::Autosave<Session>,
+    /// Left behind by a run that crashed or was closed with different data, until it's restored
+    /// or discarded. Autosaving waits until then, so it isn't overwritten.
+    #[cfg(not(target_arch = "wasm32"))]
+    previous_session: Option<Session>,
+    /// The other schedules that are open, each with its own data and settings.
+    tabs: tabs::Tabs<TabState>,
+    /// Whether [`Self::previous_session`] has been compared to the state that was loaded.
+    #[cfg(not(target_arch = "wasm32"))]
+    previous_session_checked: bool,
+    /// Classes from a restored session, with its edits from the preview, to use once its data
+    /// has been parsed.
+    restored_classes: Option<Vec<Class>>,
+    /// Weekly events to add to the calendars along with the classes.
+    custom_events: Vec<parser::CustomEvent>,
+    exam_schedule: exam_schedule::ExamSchedule,
+    #[cfg(not(target_arch = "wasm32"))]
+    deadlines_source: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    deadlines_status: Option<String>,
+    #[cfg(not(target_arch = "wasm32"))]
+    holidays_source: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    holidays_status: Option<String>,
+    week_grid_status: Option<String>,
+    #[cfg(not(target_arch = "wasm32"))]
+    catalog_error: Option<String>,
+    friend_schedule: free_time_view::FriendSchedule,
+    #[cfg(not(target_arch = "wasm32"))]
+    google: google_push::GooglePush,
+    #[cfg(not(target_arch = "wasm32"))]
+    outlook: outlook_push::OutlookPush,
+    #[cfg(not(target_arch = "wasm32"))]
+    caldav: caldav_upload::CalDavUpload,
+    #[cfg(not(target_arch = "wasm32"))]
+    browser_fetch: browser_fetch::BrowserFetch,
+    #[cfg(not(target_arch = "wasm32"))]
+    banner_fetch: banner_fetch::BannerFetch,
+    schedule: Option<Result<parser::Schedule, parser::ParseError>>,
+    /// Line of the schedule data to scroll to on the next frame, eg. one that failed to parse.
+    scroll_to_line: Option<usize>,
+    selected_classes: Vec<bool>,
+    /// Term whose classes are selected, when the data has several. `None` selects all of them.
+    selected_term: Option<String>,
+    #[cfg(not(target_arch = "wasm32"))]
     output_folder: Option<PathBuf>,
+    /// Most recently used first.
+    #[cfg(not(target_arch = "wasm32"))]
+    recent_output_folders: Vec<PathBuf>,
+    options: GenerateOptions,
+    changes: Option<Result<Vec<MeetingChange>, String>>,
     result_text: Option<String>,
+    #[cfg(not(target_arch = "wasm32"))]
+    report: Option<parser::Report>,
+    /// Calendars that would be replaced, while asking whether to.
+    #[cfg(not(target_arch = "wasm32"))]
+    confirm_overwrite: Vec<PathBuf>,
+    /// When the pasted data was last edited, if it hasn't been parsed since.
+    parse_requested: Option<Instant>,
+    parsing: Option<(
+        Instant,
+        Receiver<Result<parser::Schedule, parser::ParseError>>,
+    )>,
+    generating: Option<(Arc<Progress>, Receiver<parser::Report>)>,
+    data_error: Option<String>,
+    /// Text being recognized from a screenshot.
+    reading_image: Option<Receiver<Result<String, String>>>,
+    reading_pdf: Option<Receiver<Result<parser::Schedule, String>>>,
+    /// Whether to ask GitHub for a newer release on startup. Off unless the user opts in.
+    #[cfg(not(target_arch = "wasm32"))]
+    check_for_updates: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    update_check: Option<Receiver<Result<Option<Release>, UpdateError>>>,
+    /// A newer release that hasn't been dismissed.
+    #[cfg(not(target_arch = "wasm32"))]
+    update: Option<Release>,
+    language: Language,
+    theme: settings::Theme,
+    /// Zoom on top of the OS's scaling. Also changed with Ctrl +/-.
+    ui_scale: f32,
+}
+
+impl App {
+    /// The app with the settings and data saved by the last run.
+    fn new(cc: &eframe::CreationContext) -> Self {
+        let mut app = match cc.storage {
+            Some(storage) => {
+                let settings = eframe::get_value(storage, SETTINGS_KEY).unwrap_or_else(|| {
+                    settings::Settings {
+                        options: eframe::get_value(storage, GENERATE_OPTIONS_KEY)
+                            .unwrap_or_default(),
+                        date_order: eframe::get_value(storage, DATE_ORDER_KEY).unwrap_or_default(),
+                        timezone: None,
+                    }
+                });
+                let mut app = App {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    output_folder: storage
+                        .get_string(OUTPUT_FOLDER_KEY)
+                        .map(|s| s.into())
+                        .take_if(|p: &mut PathBuf| p.is_dir()),
+                    profile: storage
+                        .get_string(PROFILE_KEY)
+                        .and_then(|s| InstitutionProfile::from_toml(&s).ok())
+                        .unwrap_or_default(),
+                    custom_subjects: eframe::get_value(storage, CUSTOM_SUBJECTS_KEY)
+                        .unwrap_or_default(),
+                    configs: eframe::get_value(storage, CONFIGS_KEY).unwrap_or_default(),
+                    tabs: eframe::get_value(storage, TABS_KEY).unwrap_or_default(),
+                    #[cfg(not(target_arch = "wasm32"))]
+                    recent_output_folders: eframe::get_value(storage, RECENT_OUTPUT_FOLDERS_KEY)
+                        .unwrap_or_default(),
+                    options: settings.options,
+                    excluded_dates: eframe::get_value(storage, EXCLUDED_DATES_KEY)
+                        .unwrap_or_default(),
+                    custom_events: eframe::get_value(storage, CUSTOM_EVENTS_KEY)
+                        .unwrap_or_default(),
+                    #[cfg(not(target_arch = "wasm32"))]
+                    deadlines_source: storage.get_string(DEADLINES_SOURCE_KEY).unwrap_or_default(),
+                    #[cfg(not(target_arch = "wasm32"))]
+                    check_for_updates: eframe::get_value(storage, CHECK_FOR_UPDATES_KEY)
+                        .unwrap_or_default(),
+                    language: eframe::get_value(storage, LANGUAGE_KEY).unwrap_or_default(),
+                    theme: eframe::get_value(storage, THEME_KEY).unwrap_or_default(),
+                    remember_data: eframe::get_value(storage, REMEMBER_DATA_KEY).unwrap_or(false),
+                    date_order: settings.date_order,
+                    data: storage.get_string(DATA_KEY).unwrap_or_default(),
+                    summary_data: storage.get_string(SUMMARY_DATA_KEY).unwrap_or_default(),
+                    #[cfg(not(target_arch = "wasm32"))]
+                    google: google_push::GooglePush::new(
+                        eframe::get_value(storage, GOOGLE_CLIENT_KEY).unwrap_or_default(),
+                    ),
+                    #[cfg(not(target_arch = "wasm32"))]
+                    outlook: outlook_push::OutlookPush::new(
+                        eframe::get_value(storage, OUTLOOK_CLIENT_KEY).unwrap_or_default(),
+                    ),
+                    #[cfg(not(target_arch = "wasm32"))]
+                    caldav: caldav_upload::CalDavUpload::new(
+                        eframe::get_value(storage, CALDAV_SERVER_KEY).unwrap_or_default(),
+                    ),
+                    #[cfg(not(target_arch = "wasm32"))]
+                    browser_fetch: browser_fetch::BrowserFetch::new(
+                        storage.get_string(WEBDRIVER_URL_KEY),
+                    ),
+                    #[cfg(not(target_arch = "wasm32"))]
+                    banner_fetch: banner_fetch::BannerFetch::new(
+                        storage.get_string(BANNER_TERM_KEY).unwrap_or_default(),
+                    ),
+                    ..Default::default()
+                };
+                if let Some(timezone) = settings.timezone {
+                    app.profile.timezone = timezone;
+                }
+                // from before recent folders were remembered
+                #[cfg(not(target_arch = "wasm32"))]
+                if let Some(path) = app.output_folder.clone() {
+                    app.set_output_folder(path);
+                }
+                app
+            }
+            None => App::default(),
+        };
+        i18n::set_language(app.language);
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            app.previous_session =
+                session::Autosave::<Session>::load().filter(|session| !session.data.is_empty());
+        }
+        app.ui_scale = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, UI_SCALE_KEY))
+            .unwrap_or(1.0);
+        cc.egui_ctx.set_zoom_factor(app.ui_scale);
+        app.parse_data();
+        #[cfg(not(target_arch = "wasm32"))]
+        if app.check_for_updates {
+            app.check_for_update();
+        }
+        app
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn can_generate_calendars(&self) -> bool {
+        self.schedule.is_some() && self.output_folder.is_some() && self.generating.is_none()
+    }
+
+    /// The web build downloads the calendars, so it doesn't need an output folder.
+    #[cfg(target_arch = "wasm32")]
+    fn can_generate_calendars(&self) -> bool {
+        self.schedule.is_some()
+    }
+
+    /// Parses the data on a background thread, since a huge paste can take a while. Replaces any
+    /// parse that's still running.
+    fn parse_data(&mut self) {
+        self.parse_requested = None;
+        logging::set_sanitizer(Sanitizer::new(&self.data));
+        #[cfg(not(target_arch = "wasm32"))]
+        crash::set_input_len(self.data.len());
+        if self.data.is_empty() {
+            self.parsing = None;
+            self.set_schedule(None);
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let profile = self.profile.clone();
+        let subjects = self
+            .custom_subjects
+            .iter()
+            .filter(|(name, code)| !name.is_empty() && !code.is_empty())
+            .cloned()
+            .collect::<Vec<_>>();
+        let (data, summary_data) = (self.data.clone(), self.summary_data.clone());
+        let date_order = self.date_order;
+        background::spawn(move || {
+            let schedule = parser::Parser::from_profile(&profile)
+                .map(|parser| parser.with_subjects(subjects).with_date_order(date_order))
+                .map_err(parser::ParseError::from)
+                .and_then(|parser| parser.parse_data_with_summary(&data, &summary_data));
+            tx.send(schedule).ok();
+        });
+        self.parsing = Some((Instant::now(), rx));
+    }
+
+    /// Summary of the parsed data for above the Generate button, and whether it can be generated
+    /// as is. `None` if nothing's been pasted. The previous result is kept while the data is
+    /// being parsed again.
+    fn validation_status(&self) -> Option<(bool, String)> {
+        match self.schedule.as_ref()? {
+            Ok(schedule) if schedule.classes.is_empty() => Some((false, tr("status-no-classes"))),
+            Ok(schedule) => {
+                let classes = selected_classes(schedule, &self.selected_classes);
+                let meetings = classes
+                    .iter()
+                    .map(|class| class.date_ranges.len())
+                    .sum::<usize>();
+                let mut status = tr_args(
+                    "status-detected",
+                    &[
+                        ("meetings", meetings.into()),
+                        ("classes", classes.len().into()),
+                    ],
+                );
+                if !schedule.failures.is_empty() {
+                    status += &tr_args(
+                        "status-failures",
+                        &[("failures", schedule.failures.len().into())],
+                    );
+                }
+                Some((schedule.failures.is_empty(), status))
+            }
+            Err(e) => Some((false, format!("✖ {e}"))),
+        }
+    }
+
+    /// What went wrong parsing the pasted data, for a bug report.
+    fn problem(&self) -> String {
+        match &self.schedule {
+            Some(Ok(schedule)) if !schedule.failures.is_empty() => schedule
+                .failures
+                .iter()
+                .map(|failure| {
+                    let location = failure
+                        .line
+                        .map(|line| format!("Line {line}: "))
+                        .unwrap_or_default();
+                    format!("- {location}{}: {}", failure.heading, failure.error)
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Some(Ok(_)) => "No classes were found in the schedule data.".to_owned(),
+            Some(Err(e)) => e.to_string(),
+            None => String::new(),
+        }
+    }
+
+    fn set_schedule(&mut self, schedule: Option<Result<parser::Schedule, parser::ParseError>>) {
+        let first_failure = |schedule: &Option<Result<parser::Schedule, _>>| match schedule {
+            Some(Ok(schedule)) => schedule.failures.iter().find_map(|failure| failure.line),
+            _ => None,
+        };
+        // only when it moves, so it doesn't jump back there on every keystroke
+        let previous = first_failure(&self.schedule);
+        self.schedule = schedule;
+        if first_failure(&self.schedule) != previous {
+            self.scroll_to_line = first_failure(&self.schedule);
+        }
+        self.selected_term = match &self.schedule {
+            Some(Ok(schedule)) if schedule.terms().len() > 1 => {
+                schedule.terms().first().map(|term| term.to_string())
+            }
+            _ => None,
+        };
+        self.selected_classes = match &self.schedule {
+            Some(Ok(schedule)) => term_selection(schedule, self.selected_term.as_deref()),
+            _ => Vec::new(),
+        };
+        self.changes = None;
+    }
+
+    /// The parsed schedule, without the classes that were unchecked in the preview, and with the
+    /// custom events and exams.
+    fn selected_schedule(&self) -> Option<Result<parser::Schedule, parser::ParseError>> {
+        self.schedule.as_ref().map(|schedule| {
+            schedule.clone().map(|mut selected| {
+                let mut is_selected = self.selected_classes.iter();
+                selected
+                    .classes
+                    .retain(|_| is_selected.next().copied().unwrap_or(true));
+                if let Some(term) = &self.selected_term {
+                    selected.term = term.clone();
+                }
+                selected.add_custom_events(&self.custom_events);
+                selected.add_exams(self.exam_schedule.exams());
+                selected
+            })
+        })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn compare_with_previous_export(&mut self) {
+        if let (Some(output_folder), Some(Ok(schedule))) =
+            (&self.output_folder, self.selected_schedule())
+        {
+            self.changes = Some(
+                update::diff_previous_export(
+                    output_folder,
+                    &schedule,
+                    &self.profile,
+                    &self.options,
+                )
+                .map_err(|e| e.to_string()),
+            );
+        }
+    }
+
+    /// Adds the dates from a holidays calendar to the excluded dates. If a schedule was parsed,
+    /// dates outside of it are skipped, since holiday feeds usually cover several years.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn import_holidays(&mut self, source: &str) {
+        let calendar = match holidays::load(source) {
+            Ok(calendar) => calendar,
+            Err(e) => {
+                self.holidays_status = Some(format!("⚠ {e}"));
+                return;
+            }
+        };
+
+        let term = match &self.schedule {
+            Some(Ok(schedule)) => {
+                let date_ranges = schedule.classes.iter().flat_map(|c| &c.date_ranges);
+                date_ranges
+                    .clone()
+                    .map(|d| d.start_date)
+                    .min()
+                    .zip(date_ranges.map(|d| d.end_date).max())
+            }
+            _ => None,
+        };
+
+        let mut imported = 0;
+        let mut skipped = 0;
+        for (start, end) in holidays::excluded_dates(&calendar) {
+            if term.is_some_and(|(term_start, term_end)| end < term_start || start > term_end) {
+                skipped += 1;
+                continue;
+            }
+            let excluded_date = ExcludedDate::fixed(start, end);
+            if !self.excluded_dates.contains(&excluded_date) {
+                self.excluded_dates.push(excluded_date);
+                imported += 1;
+            }
+        }
+
+        self.holidays_status = Some(if skipped > 0 {
+            tr_args(
+                "holidays-imported-skipped",
+                &[("imported", imported.into()), ("skipped", skipped.into())],
+            )
+        } else {
+            tr_args("holidays-imported", &[("imported", imported.into())])
+        });
+    }
+
+    fn settings(&self) -> settings::Settings {
+        settings::Settings {
+            options: self.options.clone(),
+            date_order: self.date_order,
+            timezone: Some(self.profile.timezone.clone()),
+        }
+    }
+
+    fn apply_settings(&mut self, settings: settings::Settings) {
+        self.options = settings.options;
+        if let Some(timezone) = settings.timezone {
+            self.profile.timezone = timezone;
+        }
+        if self.date_order != settings.date_order {
+            self.date_order = settings.date_order;
+            self.parse_data();
+        }
+        self.changes = None;
+    }
+
+    /// Sets the output folder and moves it to the top of the recent ones.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn set_output_folder(&mut self, path: PathBuf) {
+        self.recent_output_folders.retain(|recent| *recent != path);
+        self.recent_output_folders.insert(0, path.clone());
+        self.recent_output_folders
+            .truncate(MAX_RECENT_OUTPUT_FOLDERS);
+        self.output_folder = Some(path);
+    }
+
+    fn config(&self) -> configs::Config {
+        configs::Config {
+            settings: self.settings(),
+            #[cfg(not(target_arch = "wasm32"))]
+            output_folder: self.output_folder.clone(),
+            #[cfg(target_arch = "wasm32")]
+            output_folder: None,
+            custom_subjects: self.custom_subjects.clone(),
+        }
+    }
+
+    fn apply_config(&mut self, config: configs::Config) {
+        #[cfg(not(target_arch = "wasm32"))]
+        match config.output_folder.filter(|path| path.is_dir()) {
+            Some(path) => self.set_output_folder(path),
+            None => self.output_folder = None,
+        }
+        let subjects_changed = self.custom_subjects != config.custom_subjects;
+        self.custom_subjects = config.custom_subjects;
+        let date_order = self.date_order;
+        self.apply_settings(config.settings);
+        // otherwise it was already parsed again for the new date order
+        if subjects_changed && self.date_order == date_order {
+            self.parse_data();
+        }
+    }
+
+    fn tab_state(&self) -> TabState {
+        TabState {
+            session: self.session(),
+            config: self.config(),
+            config_name: self.configs.active.clone(),
+        }
+    }
+
+    /// Replaces the app's state with the tab at `index`, after saving the active tab's.
+    fn switch_tab(&mut self, index: usize) {
+        let state = self.tab_state();
+        if let Some(tab) = self.tabs.tabs.get_mut(self.tabs.active) {
+            tab.state = state;
+        }
+        self.load_tab(index);
+    }
+
+    fn load_tab(&mut self, index: usize) {
+        let Some(tab) = self.tabs.tabs.get(index) else {
+            return;
+        };
+        let TabState {
+            session,
+            config,
+            config_name,
+        } = tab.state.clone();
+        self.tabs.active = index;
+        self.data = session.data;
+        self.summary_data = session.summary_data;
+        self.excluded_dates = session.excluded_dates;
+        self.restored_classes = Some(session.classes).filter(|classes| !classes.is_empty());
+        self.configs.active = config_name;
+        self.apply_config(config);
+        self.selected_classes.clear();
+        // undoing shouldn't bring back another tab's data
+        self.history = Default::default();
+        self.parse_data();
+    }
+
+    fn handle_tab_action(&mut self, action: tabs::TabAction) {
+        match action {
+            tabs::TabAction::Switch(index) => self.switch_tab(index),
+            tabs::TabAction::New => {
+                // the same settings, but not the same output folder, so it doesn't overwrite the
+                // other tab's calendars
+                let config = configs::Config {
+                    output_folder: None,
+                    ..self.config()
+                };
+                let index = self.tabs.push(TabState {
+                    session: Session::default(),
+                    config,
+                    config_name: String::new(),
+                });
+                self.switch_tab(index);
+            }
+            tabs::TabAction::Close => {
+                if self.tabs.tabs.len() > 1 {
+                    self.tabs.tabs.remove(self.tabs.active);
+                    self.load_tab(self.tabs.active.min(self.tabs.tabs.len() - 1));
+                }
+            }
+        }
+    }
+
+    fn handle_config_action(&mut self, action: configs::ConfigAction) {
+        match action {
+            configs::ConfigAction::Switch(name) => {
+                let Some(config) = self.configs.saved.get(&name).cloned() else {
+                    return;
+                };
+                if !self.configs.active.is_empty() {
+                    let current = self.config();
+                    self.configs
+                        .saved
+                        .insert(self.configs.active.clone(), current);
+                }
+                self.configs.active = name;
+                self.apply_config(config);
+            }
+            configs::ConfigAction::SaveAs(name) => {
+                self.configs.saved.insert(name.clone(), self.config());
+                self.configs.active = name;
+            }
+            configs::ConfigAction::Delete => {
+                self.configs.saved.remove(&self.configs.active);
+                self.configs.active.clear();
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_settings(&mut self) {
+        let Some(path) = FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_file_name("mycampus-calendar-settings.json")
+            .save_file()
+        else {
+            return;
+        };
+        let json = serde_json::to_string_pretty(&self.settings()).map_err(|e| e.to_string());
+        self.settings_status = Some(
+            match json.and_then(|json| fs::write(&path, json).map_err(|e| e.to_string())) {
+                Ok(()) => tr_args(
+                    "settings-exported",
+                    &[("path", path.display().to_string().into())],
+                ),
+                Err(e) => tr_args("settings-export-failed", &[("error", e.into())]),
+            },
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn import_settings(&mut self) {
+        let Some(path) = FileDialog::new().add_filter("JSON", &["json"]).pick_file() else {
+            return;
+        };
+        let settings = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()));
+        match settings {
+            Ok(settings) => {
+                self.apply_settings(settings);
+                self.settings_status = Some(tr_args(
+                    "settings-imported",
+                    &[("path", path.display().to_string().into())],
+                ));
+            }
+            Err(e) => {
+                self.settings_status =
+                    Some(tr_args("settings-import-failed", &[("error", e.into())]));
+            }
+        }
+    }
+
+    /// Replaces the deadlines with the ones for the parsed schedule's courses in a Canvas or
+    /// Brightspace calendar feed.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn import_deadlines(&mut self, source: &str) {
+        let Some(Ok(schedule)) = &self.schedule else {
+            self.deadlines_status = Some(tr("deadlines-no-schedule"));
+            return;
+        };
+        let calendar = match deadlines::load(source) {
+            Ok(calendar) => calendar,
+            Err(e) => {
+                self.deadlines_status = Some(format!("⚠ {e}"));
+                return;
+            }
+        };
+
+        self.options.deadlines = deadlines::parse_deadlines(
+            &calendar,
+            schedule.classes.iter().map(|class| class.code.as_str()),
+        );
+        let courses = self
+            .options
+            .deadlines
+            .iter()
+            .map(|deadline| &deadline.course)
+            .collect::<BTreeSet<_>>();
+        self.deadlines_status = Some(tr_args(
+            "deadlines-imported",
+            &[
+                ("deadlines", self.options.deadlines.len().into()),
+                ("courses", courses.len().into()),
+            ],
+        ));
+        self.changes = None;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_profile(&mut self, path: PathBuf) {
+        self.set_profile(InstitutionProfile::load(path).map_err(|e| e.to_string()));
+    }
+
+    /// Switches to `profile` if it was read and its regexes are valid, or else shows why not.
+    fn set_profile(&mut self, profile: Result<InstitutionProfile, String>) {
+        match profile.and_then(|p| match parser::Parser::from_profile(&p) {
+            Ok(_) => Ok(p),
+            Err(e) => Err(tr_args(
+                "profile-invalid-regex",
+                &[("error", e.to_string().into())],
+            )),
+        }) {
+            Ok(profile) => {
+                self.profile = profile;
+                self.profile_error = None;
+                self.parse_data();
+            }
+            Err(e) => self.profile_error = Some(e),
+        }
+    }
+
+    /// Loads a schedule saved as a text file, or an institution profile, dropped onto the window.
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| !i.raw.hovered_files.is_empty()) {
+            let painter = ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                egui::Id::new("file_drop_target"),
+            ));
+            let screen_rect = ctx.screen_rect();
+            painter.rect_filled(screen_rect, 0.0, egui::Color32::from_black_alpha(192));
+            painter.text(
+                screen_rect.center(),
+                egui::Align2::CENTER_CENTER,
+                tr("drop-file"),
+                egui::FontId::proportional(20.0),
+                egui::Color32::WHITE,
+            );
+        }
+
+        let Some(file) = ctx.input(|i| i.raw.dropped_files.first().cloned()) else {
+            return;
+        };
+        let name = file
+            .path
+            .as_deref()
+            .and_then(Path::file_name)
+            .map_or(file.name.clone(), |name| {
+                name.to_string_lossy().into_owned()
+            });
+        // the web build gets the contents, and the others the path
+        let contents = match (file.bytes, &file.path) {
+            (Some(bytes), _) => Ok(bytes.to_vec()),
+            #[cfg(not(target_arch = "wasm32"))]
+            (None, Some(path)) => fs::read(path).map_err(|e| e.to_string()),
+            (None, _) => Err(tr("dropped-file-unavailable")),
+        };
+        self.load_file(ctx, name, contents);
+    }
+
+    /// Loads a dropped or picked file named `name`: schedule text or a saved page, a screenshot,
+    /// a PDF schedule, or an institution profile.
+    fn load_file(&mut self, ctx: &egui::Context, name: String, contents: Result<Vec<u8>, String>) {
+        let contents = match contents {
+            Ok(contents) => contents,
+            Err(e) => {
+                self.data_error = Some(load_error("load-failed", &name, e));
+                return;
+            }
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        if ocr::is_image(&name) {
+            self.read_image(ctx.clone(), name, move || ocr::image_to_text(&contents));
+            return;
+        }
+        if name.to_lowercase().ends_with(".toml") {
+            self.set_profile(
+                InstitutionProfile::from_toml(&String::from_utf8_lossy(&contents))
+                    .map_err(|e| e.to_string()),
+            );
+        } else if name.to_lowercase().ends_with(".pdf") {
+            self.read_pdf(ctx.clone(), name, contents);
+        } else if name.to_lowercase().ends_with(".ics") {
+            // a previous export replaces the parsed schedule, like a PDF
+            match import::read_calendars([String::from_utf8_lossy(&contents).as_ref()]) {
+                Ok(schedule) => {
+                    self.parsing = None;
+                    self.data_error = None;
+                    self.set_schedule(Some(Ok(schedule)));
+                }
+                Err(e) => self.data_error = Some(load_error("load-failed", &name, e)),
+            }
+        } else {
+            self.load_data(
+                schedule_text(&name, contents).map_err(|e| load_error("load-failed", &name, e)),
+            );
+        }
+    }
+
+    /// Reads the classes from a PDF schedule on a background thread. They replace the parsed
+    /// schedule, like when fetching from Banner.
+    fn read_pdf(&mut self, ctx: egui::Context, name: String, pdf: Vec<u8>) {
+        let (tx, rx) = mpsc::channel();
+        let profile = self.profile.clone();
+        background::spawn(move || {
+            tx.send(
+                pdf::parse_pdf(&pdf, &profile).map_err(|e| load_error("read-failed", &name, e)),
+            )
+            .ok();
+            ctx.request_repaint();
+        });
+        self.data_error = None;
+        self.reading_pdf = Some(rx);
+    }
+
+    /// Recognizes the text of a screenshot named `name` on a background thread, then loads it.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read_image(
+        &mut self,
+        ctx: egui::Context,
+        name: String,
+        read: impl FnOnce() -> Result<String, ocr::OcrError> + Send + 'static,
+    ) {
+        let (tx, rx) = mpsc::channel();
+        background::spawn(move || {
+            tx.send(read().map_err(|e| load_error("read-failed", &name, e)))
+                .ok();
+            ctx.request_repaint();
+        });
+        self.data_error = None;
+        self.reading_image = Some(rx);
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            data: self.data.clone(),
+            excluded_dates: self.excluded_dates.clone(),
+            schedule: match &self.schedule {
+                Some(Ok(schedule)) => Some(schedule.clone()),
+                _ => None,
+            },
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.data = snapshot.data;
+        self.excluded_dates = snapshot.excluded_dates;
+        match snapshot.schedule {
+            // keep the edits from the preview instead of parsing again
+            Some(schedule) => {
+                self.parse_requested = None;
+                self.parsing = None;
+                self.set_schedule(Some(Ok(schedule)));
+            }
+            None => self.parse_data(),
+        }
+    }
+
+    fn session(&self) -> Session {
+        Session {
+            data: self.data.clone(),
+            summary_data: self.summary_data.clone(),
+            excluded_dates: self.excluded_dates.clone(),
+            classes: match &self.schedule {
+                Some(Ok(schedule)) => schedule.classes.clone(),
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn restore_session(&mut self, session: Session) {
+        self.data = session.data;
+        self.summary_data = session.summary_data;
+        self.excluded_dates = session.excluded_dates;
+        self.restored_classes = Some(session.classes).filter(|classes| !classes.is_empty());
+        self.parse_data();
+    }
+
+    /// Ctrl+Z and Ctrl+Y (or Ctrl+Shift+Z), unless a text field is focused, since those have their
+    /// own undo.
+    fn handle_undo_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.memory(|memory| memory.focused().is_some()) {
+            return;
+        }
+        let redo = ctx.input_mut(|input| {
+            input.consume_shortcut(&KeyboardShortcut::new(
+                Modifiers::COMMAND | Modifiers::SHIFT,
+                Key::Z,
+            )) || input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::Y))
+        });
+        let undo = !redo
+            && ctx.input_mut(|input| {
+                input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::Z))
+            });
+        let snapshot = if undo {
+            self.history.undo()
+        } else if redo {
+            self.history.redo()
+        } else {
+            None
+        };
+        if let Some(snapshot) = snapshot {
+            self.restore(snapshot);
+        }
+    }
+
+    /// Replaces the schedule data, or shows why it couldn't be loaded.
+    fn load_data(&mut self, data: Result<String, String>) {
+        match data {
+            Ok(data) => {
+                self.data = data;
+                self.data_error = None;
+                self.parse_data();
+            }
+            Err(e) => self.data_error = Some(e),
+        }
+    }
+
+    fn exdate(&self) -> HashSet<NaiveDate> {
+        self.excluded_dates
+            .iter()
+            .flat_map(|d| d.iter_days())
+            .collect()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export(&mut self, format: ExportFormat) {
+        if let (Some(output_folder), Some(schedule)) =
+            (&self.output_folder, self.selected_schedule())
+        {
+            self.result_text = Some(
+                match schedule.map_err(|e| e.to_string()).and_then(|schedule| {
+                    export::export(
+                        output_folder,
+                        &schedule,
+                        &self.exdate(),
+                        &self.profile,
+                        &self.options,
+                        format,
+                    )
+                    .map_err(|e| e.to_string())
+                }) {
+                    Ok(path) if self.options.zip => match archive::zip_outputs(output_folder) {
+                        Ok(zip_path) => tr_args(
+                            "wrote-and-zipped",
+                            &[
+                                ("path", path.display().to_string().into()),
+                                ("zip", zip_path.display().to_string().into()),
+                            ],
+                        ),
+                        Err(e) => tr_args(
+                            "wrote-zip-failed",
+                            &[
+                                ("path", path.display().to_string().into()),
+                                ("zip", archive::ZIP_FILE_NAME.into()),
+                                ("error", e.to_string().into()),
+                            ],
+                        ),
+                    },
+                    Ok(path) => tr_args("wrote", &[("path", path.display().to_string().into())]),
+                    Err(e) => tr_args(
+                        "export-failed",
+                        &[("format", format.to_string().into()), ("error", e.into())],
+                    ),
+                },
+            );
+        }
+    }
+
+    /// Downloads the selected classes as `format`.
+    #[cfg(target_arch = "wasm32")]
+    fn export(&mut self, format: ExportFormat) {
+        let Some(schedule) = self.selected_schedule() else {
+            return;
+        };
+        let name = format.file_name();
+        let downloaded = schedule
+            .map_err(|e| e.to_string())
+            .and_then(|schedule| {
+                export::export_contents(
+                    &schedule,
+                    &self.exdate(),
+                    &self.profile,
+                    &self.options,
+                    format,
+                )
+                .map_err(|e| e.to_string())
+            })
+            .and_then(|contents| web::download(name, &contents));
+        self.result_text = Some(match downloaded {
+            Ok(()) => tr_args("downloaded", &[("file", name.into())]),
+            Err(e) => tr_args(
+                "export-failed",
+                &[("format", format.to_string().into()), ("error", e.into())],
+            ),
+        });
+    }
+
+    /// The calendars that generating would replace without asking first. Updating the previous
+    /// export replaces them on purpose.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn existing_calendars(&self) -> Vec<PathBuf> {
+        match (&self.output_folder, self.selected_schedule()) {
+            (Some(output_folder), Some(Ok(schedule)))
+                if !self.options.update_previous && !self.options.rename_existing =>
+            {
+                parser::existing_calendars(output_folder, &schedule, &self.profile, &self.options)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Writes the calendars on a background thread, so the window stays responsive.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn generate_calendars(&mut self) {
+        if let (Some(output_folder), Some(schedule)) =
+            (&self.output_folder, self.selected_schedule())
+        {
+            self.changes = None;
+            let schedule = match schedule {
+                Ok(schedule) => schedule,
+                Err(e) => {
+                    self.result_text = Some(tr_args(
+                        "generate-failed",
+                        &[("error", e.to_string().into())],
+                    ));
+                    self.report = None;
+                    return;
+                }
+            };
+
+            let progress = Arc::new(Progress::default());
+            let (tx, rx) = mpsc::channel();
+            let (output_folder, exdate, profile, options) = (
+                output_folder.clone(),
+                self.exdate(),
+                self.profile.clone(),
+                self.options.clone(),
+            );
+            let thread_progress = progress.clone();
+            background::spawn(move || {
+                let report = parser::write_calendars_with_progress(
+                    output_folder,
+                    &schedule,
+                    exdate,
+                    &profile,
+                    &options,
+                    &thread_progress,
+                );
+                tx.send(report).ok();
+            });
+            self.generating = Some((progress, rx));
+            self.result_text = None;
+        }
+    }
+
+    /// Downloads the calendars, or a zip of them if they should be zipped, since the web build
+    /// can't write them to a folder.
+    #[cfg(target_arch = "wasm32")]
+    fn generate_calendars(&mut self) {
+        let calendars = match self.selected_schedule() {
+            Some(Ok(schedule)) => {
+                parser::generate_calendars(&schedule, &self.exdate(), &self.profile, &self.options)
+            }
+            Some(Err(e)) => {
+                self.result_text = Some(tr_args(
+                    "generate-failed",
+                    &[("error", e.to_string().into())],
+                ));
+                return;
+            }
+            None => return,
+        };
+        if calendars.is_empty() {
+            self.result_text = Some(tr("generate-none"));
+            return;
+        }
+
+        let downloaded = if self.options.zip {
+            archive::zip(
+                calendars
+                    .iter()
+                    .map(|(name, calendar)| (name.as_str(), calendar.as_bytes())),
+            )
+            .map_err(|e| e.to_string())
+            .and_then(|zip| web::download(archive::ZIP_FILE_NAME, &zip))
+        } else {
+            calendars
+                .iter()
+                .try_for_each(|(name, calendar)| web::download(name, calendar.as_bytes()))
+        };
+        self.result_text = Some(match downloaded {
+            Ok(()) => tr_args("generated", &[("calendars", calendars.len().into())]),
+            Err(e) => tr_args("generate-failed", &[("error", e.into())]),
+        });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn check_for_update(&mut self) {
+        let (tx, rx) = mpsc::channel();
+        background::spawn(move || {
+            tx.send(release_check::check_for_update(VERSION)).ok();
+        });
+        self.update_check = Some(rx);
+    }
+
+    /// Picks up the results of [`Self::parse_data`] and [`Self::generate_calendars`] once they're
+    /// done.
+    fn poll_background(&mut self, ctx: &egui::Context) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(rx) = &self.update_check {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.update_check = None;
+                    match result {
+                        Ok(update) => self.update = update,
+                        Err(e) => tracing::warn!("Failed to check for updates: {e}"),
+                    }
+                }
+                Err(TryRecvError::Empty) => ctx.request_repaint_after(REPAINT_INTERVAL),
+                Err(TryRecvError::Disconnected) => {
+                    self.update_check = None;
+                    tracing::warn!("The update check stopped without a result");
+                }
+            }
+        }
+
+        if let Some(rx) = &self.reading_image {
+            match rx.try_recv() {
+                Ok(data) => {
+                    self.reading_image = None;
+                    self.load_data(data);
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => {
+                    self.reading_image = None;
+                    self.data_error = Some(tr("worker-crashed"));
+                }
+            }
+        }
+
+        if let Some(rx) = &self.reading_pdf {
+            match rx.try_recv() {
+                Ok(schedule) => {
+                    self.reading_pdf = None;
+                    match schedule {
+                        Ok(schedule) => {
+                            self.parsing = None;
+                            self.set_schedule(Some(Ok(schedule)));
+                        }
+                        Err(e) => self.data_error = Some(e),
+                    }
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => {
+                    self.reading_pdf = None;
+                    self.data_error = Some(tr("worker-crashed"));
+                }
+            }
+        }
+
+        if let Some(requested) = self.parse_requested {
+            match PARSE_DEBOUNCE.checked_sub(requested.elapsed()) {
+                Some(remaining) if !remaining.is_zero() => ctx.request_repaint_after(remaining),
+                _ => self.parse_data(),
+            }
+        }
+
+        if let Some((_, rx)) = &self.parsing {
+            match rx.try_recv() {
+                Ok(mut schedule) => {
+                    self.parsing = None;
+                    if let (Ok(schedule), Some(classes)) =
+                        (&mut schedule, self.restored_classes.take())
+                    {
+                        schedule.classes = classes;
+                    }
+                    self.set_schedule(Some(schedule));
+                    // so undoing an edit to the data goes back to what was parsed before it
+                    self.history.replace(self.snapshot());
+                }
+                Err(TryRecvError::Empty) => ctx.request_repaint_after(REPAINT_INTERVAL),
+                Err(TryRecvError::Disconnected) => {
+                    self.parsing = None;
+                    self.data_error = Some(tr("worker-crashed"));
+                }
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some((_, rx)) = &self.generating {
+            match rx.try_recv() {
+                Ok(report) => {
+                    self.generating = None;
+                    self.result_text = Some(match &report {
+                        report if report.cancelled => tr_args(
+                            "generate-cancelled",
+                            &[("files", report.files.len().into())],
+                        ),
+                        report if report.calendars == 0 => tr("generate-none"),
+                        report if !report.write_failures.is_empty() => tr_args(
+                            "generate-write-failures",
+                            &[
+                                ("calendars", report.calendars.into()),
+                                ("files", report.write_failures.len().into()),
+                            ],
+                        ),
+                        report if !report.failed.is_empty() => tr_args(
+                            "generate-parse-failures",
+                            &[
+                                ("calendars", report.calendars.into()),
+                                ("classes", report.failed.len().into()),
+                            ],
+                        ),
+                        report => tr_args("generated", &[("calendars", report.calendars.into())]),
+                    });
+                    self.report = Some(report);
+                }
+                Err(TryRecvError::Empty) => ctx.request_repaint_after(REPAINT_INTERVAL),
+                Err(TryRecvError::Disconnected) => {
+                    self.generating = None;
+                    self.result_text = Some(tr("worker-crashed"));
+                }
+            }
+        }
+    }
+
+    /// What happened writing the calendars, with buttons to open them or merge them into another
+    /// calendar.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn show_report(&mut self, ui: &mut egui::Ui) {
+        let mut open_error = None;
+        let mut merge_result = None;
+        if let Some(report) = &mut self.report {
+            ui.add_space(6.0);
+
+            if !report.write_failures.is_empty() {
+                for failure in &report.write_failures {
+                    ui.label(format!(
+                        "❌ {}\n    {}",
+                        tr_args(
+                            "write-failed",
+                            &[("path", failure.path.display().to_string().into())],
+                        ),
+                        failure.error
+                    ));
+                }
+                if ui.button(tr("retry")).clicked() {
+                    report.retry_writes();
+                }
+            }
+
+            for failure in &report.failed {
+                ui.label(format!("❌ {}\n    {}", failure.heading, failure.error));
+            }
+
+            for warning in &report.warnings {
+                ui.label(format!("⚠ {warning}"));
+            }
+
+            show_changes(ui, &report.changes);
+
+            if report.statistics.weekly_hours > 0.0 {
+                ui.collapsing(
+                    tr_args(
+                        "weekly-hours",
+                        &[(
+                            "hours",
+                            format!("{:.1}", report.statistics.weekly_hours).into(),
+                        )],
+                    ),
+                    |ui| show_statistics(ui, &report.statistics),
+                );
+            }
+
+            if !report.succeeded.is_empty() {
+                ui.collapsing(
+                    tr_args(
+                        "classes-generated",
+                        &[("classes", report.succeeded.len().into())],
+                    ),
+                    |ui| {
+                        for class in &report.succeeded {
+                            ui.label(format!("☑ {class}"));
+                        }
+                    },
+                );
+            }
+
+            if !report.files.is_empty() {
+                CollapsingHeader::new(tr_args(
+                    "files-written",
+                    &[("files", report.files.len().into())],
+                ))
+                .default_open(true)
+                .show(ui, |ui| open_error = show_files(ui, &report.files));
+
+                if ui
+                    .button(tr("merge-into-calendar"))
+                    .on_hover_text(tr("merge-into-calendar-hover"))
+                    .clicked()
+                {
+                    if let Some(path) = FileDialog::new()
+                        .add_filter(tr("filter-calendar"), &["ics"])
+                        .pick_file()
+                    {
+                        merge_result = Some(merge::merge_into_file(path, &report.files).map(
+                            |(path, merged)| {
+                                tr_args(
+                                    "merged",
+                                    &[
+                                        ("path", path.display().to_string().into()),
+                                        ("added", merged.added.into()),
+                                        ("replaced", merged.replaced.into()),
+                                        ("kept", merged.kept.into()),
+                                    ],
+                                )
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+        if let Some(e) = open_error {
+            self.result_text = Some(format!("⚠ {e}"));
+        }
+        match merge_result {
+            Some(Ok(result)) => self.result_text = Some(result),
+            Some(Err(e)) => {
+                self.result_text =
+                    Some(tr_args("merge-failed", &[("error", e.to_string().into())]));
+            }
+            None => {}
+        }
+    }
 }
 
-impl App {
-    fn can_generate_calendars(&self) -> bool {
-        !self.data.is_empty() && self.output_folder.is_some()
-    }
+impl eframe::App for App {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        // eframe switches the visuals when the OS theme changes, so this overrides it every frame
+        let dark = self.theme.is_dark(frame.info().system_theme);
+        if ctx.style().visuals.dark_mode != dark {
+            ctx.set_visuals(if dark {
+                egui::Visuals::dark()
+            } else {
+                egui::Visuals::light()
+            });
+        }
+        self.poll_background(ctx);
+        self.handle_dropped_files(ctx);
+        self.handle_undo_shortcuts(ctx);
+
+        let mut show_regex_editor = self.show_regex_editor;
+        egui::Window::new(tr("regex-window"))
+            .id(egui::Id::new("regexes"))
+            .open(&mut show_regex_editor)
+            .default_width(500.0)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                if regex_editor::regex_editor(
+                    ui,
+                    &mut self.profile.regexes,
+                    &self.data,
+                    &mut self.regex_test_line,
+                ) {
+                    self.parse_data();
+                }
+            });
+        self.show_regex_editor = show_regex_editor;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if !self.confirm_overwrite.is_empty() {
+            egui::Window::new(tr("replace-title"))
+                .id(egui::Id::new("confirm_overwrite"))
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(tr("replace-description"));
+                    for path in &self.confirm_overwrite {
+                        ui.label(format!("• {}", path.display()));
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button(tr("replace")).clicked() {
+                            self.confirm_overwrite.clear();
+                            self.generate_calendars();
+                        }
+                        if ui
+                            .button(tr("keep-both"))
+                            .on_hover_text(tr("keep-both-hover"))
+                            .clicked()
+                        {
+                            self.confirm_overwrite.clear();
+                            self.options.rename_existing = true;
+                            self.generate_calendars();
+                            self.options.rename_existing = false;
+                        }
+                        if ui.button(tr("cancel")).clicked() {
+                            self.confirm_overwrite.clear();
+                        }
+                    });
+                });
+        }
+
+        let mut show_subject_editor = self.show_subject_editor;
+        egui::Window::new(tr("subjects-window"))
+            .id(egui::Id::new("subject_codes"))
+            .open(&mut show_subject_editor)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                if subject_editor::subject_editor(
+                    ui,
+                    &mut self.custom_subjects,
+                    self.profile.subjects.clone(),
+                ) {
+                    self.parse_data();
+                }
+            });
+        self.show_subject_editor = show_subject_editor;
+
+        let mut show_building_editor = self.show_building_editor;
+        egui::Window::new(tr("buildings-window"))
+            .id(egui::Id::new("building_codes"))
+            .open(&mut show_building_editor)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                if building_editor::building_editor(
+                    ui,
+                    &mut self.profile.buildings,
+                    &mut self.profile.campus_aliases,
+                ) {
+                    self.changes = None;
+                }
+            });
+        self.show_building_editor = show_building_editor;
 
-    fn generate_calendars(&mut self) {
-        if let Some(output_folder) = &self.output_folder {
-            let exdate = self
-                .excluded_dates
-                .iter()
-                .flat_map(|d| d.iter_days())
-                .collect::<HashSet<_>>();
+        let mut show_settings = self.show_settings;
+        egui::Window::new(tr("settings-window"))
+            .id(egui::Id::new("settings"))
+            .open(&mut show_settings)
+            .default_width(400.0)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(tr("export-settings"))
+                        .on_hover_text(tr("export-settings-hover"))
+                        .clicked()
+                    {
+                        self.export_settings();
+                    }
+                    if ui.button(tr("import-settings")).clicked() {
+                        self.import_settings();
+                    }
+                });
+                if let Some(status) = &self.settings_status {
+                    ui.label(status);
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if ui
+                    .checkbox(&mut self.check_for_updates, tr("check-for-updates"))
+                    .on_hover_text(tr("check-for-updates-hover"))
+                    .changed()
+                    && self.check_for_updates
+                {
+                    self.check_for_update();
+                }
+                ui.horizontal(|ui| {
+                    ui.strong(tr("language"));
+                    let before = self.language;
+                    ComboBox::from_id_source("language")
+                        .selected_text(self.language.name())
+                        .show_ui(ui, |ui| {
+                            for language in Language::ALL {
+                                ui.selectable_value(&mut self.language, language, language.name());
+                            }
+                        });
+                    if self.language != before {
+                        i18n::set_language(self.language);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.strong(tr("theme"));
+                    ComboBox::from_id_source("theme")
+                        .selected_text(self.theme.name())
+                        .show_ui(ui, |ui| {
+                            for theme in settings::Theme::ALL {
+                                ui.selectable_value(&mut self.theme, theme, theme.name());
+                            }
+                        });
+                });
+                ui.horizontal(|ui| {
+                    ui.strong(tr("interface-scale"));
+                    let response = ui.add(
+                        Slider::new(&mut self.ui_scale, 0.5..=3.0)
+                            .step_by(0.05)
+                            .fixed_decimals(2)
+                            .suffix("×"),
+                    );
+                    // rescaling while dragging would move the slider out from under the pointer
+                    if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                        ctx.set_zoom_factor(self.ui_scale);
+                    }
+                    if ui.button(tr("reset")).clicked() {
+                        self.ui_scale = 1.0;
+                        ctx.set_zoom_factor(1.0);
+                    }
+                });
+                ui.separator();
 
-            // FIXME: this should really return a result instead of catching errors.
-            let result =
-                panic::catch_unwind(|| parser::generate(output_folder, &self.data, exdate));
+                ui.horizontal(|ui| {
+                    ui.strong(tr("timezone"));
+                    if ui
+                        .add(
+                            TextEdit::singleline(&mut self.profile.timezone)
+                                .hint_text("America/Toronto")
+                                .desired_width(160.0),
+                        )
+                        .on_hover_text(tr("timezone-hover"))
+                        .changed()
+                    {
+                        self.changes = None;
+                    }
+                });
+                let date_order = self.date_order;
+                ui.horizontal(|ui| {
+                    ui.strong(tr("date-order"));
+                    ComboBox::from_id_source("date_order")
+                        .selected_text(settings::date_order_name(self.date_order))
+                        .show_ui(ui, |ui| {
+                            for date_order in parser::DateOrder::ALL {
+                                ui.selectable_value(
+                                    &mut self.date_order,
+                                    date_order,
+                                    settings::date_order_name(date_order),
+                                );
+                            }
+                        })
+                        .response
+                        .on_hover_text(tr("date-order-hover"));
+                });
+                if self.date_order != date_order {
+                    self.parse_data();
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    ui.strong(tr("course-catalog"));
+                    if ui
+                        .button(tr("load"))
+                        .on_hover_text(tr("course-catalog-hover"))
+                        .clicked()
+                    {
+                        if let Some(path) = FileDialog::new()
+                            .add_filter(tr("course-catalog-filter"), &["csv", "json"])
+                            .pick_file()
+                        {
+                            match catalog::load(path) {
+                                Ok(titles) => {
+                                    self.options.course_titles = titles;
+                                    self.catalog_error = None;
+                                    self.changes = None;
+                                }
+                                Err(e) => self.catalog_error = Some(e.to_string()),
+                            }
+                        }
+                    }
+                    if !self.options.course_titles.is_empty() {
+                        ui.label(tr_args(
+                            "course-catalog-titles",
+                            &[("titles", self.options.course_titles.len().into())],
+                        ));
+                        if ui.button(tr("clear")).clicked() {
+                            self.options.course_titles.clear();
+                            self.changes = None;
+                        }
+                    }
+                    if let Some(error) = &self.catalog_error {
+                        ui.label(format!("⚠ {error}"));
+                    }
+                });
+                ui.add_space(6.0);
 
-            self.result_text = Some(match result {
-                Ok(n) if n > 0 => format!("☑ Generated {n} calendar(s)."),
-                Ok(_) => "⚠ No calendars were generated.".to_owned(),
-                Err(_) => {
-                    "⚠ An error occurred while generating calendars. See console for more details."
-                        .to_owned()
+                let schedule = match &self.schedule {
+                    Some(Ok(schedule)) => Some(schedule),
+                    _ => None,
+                };
+                if settings::settings(ui, &mut self.options, schedule) {
+                    self.changes = None;
                 }
             });
-        }
-    }
-}
+        self.show_settings = show_settings;
 
-impl eframe::App for App {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         CentralPanel::default().show(ctx, |ui| {
-            ui.vertical_centered_justified(|ui| {
-                ui.heading("mycampus-calendar-rs");
-                ui.label(VERSION);
-            });
-            ui.separator();
-            ui.hyperlink_to(
-                "Usage instructions",
-                format!("https://github.com/object-Object/mycampus-calendar-rs/tree/{VERSION}"),
-            );
+            ScrollArea::vertical().animated(false).show(ui, |ui| {
+                ui.vertical_centered_justified(|ui| {
+                    ui.heading("mycampus-calendar-rs");
+                    ui.label(VERSION);
+                });
+                #[cfg(not(target_arch = "wasm32"))]
+                if let Some(update) = &self.update {
+                    let mut dismissed = false;
+                    ui.horizontal(|ui| {
+                        ui.label(tr_args(
+                            "update-available",
+                            &[("version", update.tag_name.as_str().into())],
+                        ));
+                        ui.hyperlink_to(tr("update-download"), &update.html_url);
+                        dismissed = ui.small_button(tr("dismiss")).clicked();
+                    });
+                    if dismissed {
+                        self.update = None;
+                    }
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if self.previous_session_checked && self.previous_session.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.label(tr("previous-session"));
+                        if ui.small_button(tr("restore-session")).clicked() {
+                            if let Some(session) = self.previous_session.take() {
+                                self.restore_session(session);
+                            }
+                        }
+                        if ui.small_button(tr("discard")).clicked() {
+                            self.previous_session = None;
+                        }
+                    });
+                }
+                ui.separator();
+                if let Some(action) = tabs::tabs_bar(ui, &mut self.tabs, self.generating.is_none())
+                {
+                    self.handle_tab_action(action);
+                }
+                if let Some(action) = configs::configs_bar(ui, &mut self.configs) {
+                    self.handle_config_action(action);
+                }
+                ui.hyperlink_to(
+                    tr("usage-instructions"),
+                    format!("https://github.com/object-Object/mycampus-calendar-rs/tree/{VERSION}"),
+                );
 
-            ui.add_space(12.0);
-            ui.heading("MyOntarioTech Schedule Data");
-
-            ScrollArea::vertical()
-                .max_height(100.0)
-                .animated(false)
-                .show(ui, |ui| {
-                    ui.add_sized(
-                        ui.available_size(),
-                        TextEdit::multiline(&mut self.data)
-                            .hint_text("Paste the copied schedule data here."),
-                    )
+                ui.add_space(12.0);
+                ui.heading(tr_args(
+                    "schedule-data-heading",
+                    &[("profile", self.profile.name.as_str().into())],
+                ));
+
+                ui.horizontal(|ui| {
+                    ui.label(tr("institution-profile"));
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if ui.button(tr("load")).clicked() {
+                        if let Some(path) =
+                            FileDialog::new().add_filter("TOML", &["toml"]).pick_file()
+                        {
+                            self.load_profile(path);
+                        }
+                    }
+                    if ui
+                        .add_enabled(
+                            self.profile != InstitutionProfile::default(),
+                            Button::new(tr("reset-profile")),
+                        )
+                        .clicked()
+                    {
+                        self.profile = InstitutionProfile::default();
+                        self.profile_error = None;
+                        self.parse_data();
+                    }
+                    if ui.button(tr("edit-regexes")).clicked() {
+                        self.show_regex_editor = true;
+                    }
+                    if ui.button(tr("edit-subject-codes")).clicked() {
+                        self.show_subject_editor = true;
+                    }
+                    if ui.button(tr("edit-building-codes")).clicked() {
+                        self.show_building_editor = true;
+                    }
+                    if let Some(error) = &self.profile_error {
+                        ui.label(format!("⚠ {error}"));
+                    }
+                });
+
+                // the web build can't pick files or read the clipboard, but files can still be
+                // dropped onto the page and text pasted into the box
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    if ui.button(tr("load-from-file")).clicked() {
+                        if let Some(path) = FileDialog::new()
+                            .add_filter(tr("filter-schedule"), &["txt", "html", "htm"])
+                            .add_filter(tr("filter-screenshot"), &ocr::IMAGE_EXTENSIONS)
+                            .add_filter(tr("filter-pdf-schedule"), &["pdf"])
+                            .add_filter(tr("filter-previous-export"), &["ics"])
+                            .pick_file()
+                        {
+                            let contents = fs::read(&path).map_err(|e| e.to_string());
+                            self.load_file(ui.ctx(), path.display().to_string(), contents);
+                        }
+                    }
+                    if ui
+                        .button(tr("paste-from-clipboard"))
+                        .on_hover_text(tr("paste-from-clipboard-hover"))
+                        .clicked()
+                    {
+                        // the text, or else a screenshot
+                        let clipboard = arboard::Clipboard::new().and_then(|mut clipboard| {
+                            clipboard.get_text().map(Ok).or_else(|e| match e {
+                                arboard::Error::ContentNotAvailable => {
+                                    clipboard.get_image().map(Err)
+                                }
+                                e => Err(e),
+                            })
+                        });
+                        match clipboard {
+                            Ok(Ok(text)) => self.load_data(Ok(text)),
+                            Ok(Err(image)) => {
+                                self.read_image(ui.ctx().clone(), tr("the-screenshot"), move || {
+                                    ocr::pixels_to_text(image.width, image.height, &image.bytes)
+                                })
+                            }
+                            Err(e) => self.load_data(Err(tr_args(
+                                "clipboard-failed",
+                                &[("error", e.to_string().into())],
+                            ))),
+                        }
+                    }
+                });
+
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.collapsing(tr("fetch-with-browser"), |ui| {
+                    if let Some(data) = self.browser_fetch.ui(ui, &self.profile) {
+                        self.load_data(Ok(data));
+                    }
                 });
 
-            ui.add_space(12.0);
-            ui.heading("Excluded Dates");
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.collapsing(tr("fetch-from-banner"), |ui| {
+                    if let Some(schedule) = self.banner_fetch.ui(ui, &self.profile) {
+                        self.parsing = None;
+                        self.data_error = None;
+                        self.set_schedule(Some(Ok(schedule)));
+                    }
+                });
+
+                let failed_lines = match &self.schedule {
+                    Some(Ok(schedule)) => schedule
+                        .failures
+                        .iter()
+                        .filter_map(|failure| failure.line)
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+                    diagnostics::highlight_lines(ui, text, wrap_width, &failed_lines)
+                };
+                let data_response = ScrollArea::vertical()
+                    .max_height(100.0)
+                    .animated(false)
+                    .show(ui, |ui| {
+                        let output = TextEdit::multiline(&mut self.data)
+                            .hint_text(tr("schedule-data-hint"))
+                            .layouter(&mut layouter)
+                            .desired_width(f32::INFINITY)
+                            .min_size(ui.available_size())
+                            .show(ui);
+                        if let Some(line) = self.scroll_to_line.take() {
+                            diagnostics::scroll_to_line(ui, &output, line);
+                        }
+                        output.response
+                    })
+                    .inner;
+
+                if self.reading_image.is_some() || self.reading_pdf.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(tr(if self.reading_pdf.is_some() {
+                            "reading-pdf"
+                        } else {
+                            "reading-screenshot"
+                        }));
+                    });
+                } else if let Some(error) = &self.data_error {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {error}"));
+                }
+
+                ui.checkbox(&mut self.remember_data, tr("remember-data"))
+                    .on_hover_text(tr("remember-data-hover"));
+
+                ui.collapsing(tr("summary-table"), |ui| {
+                    ui.label(tr("summary-table-description"));
+                    ScrollArea::vertical()
+                        .id_source("summary_data")
+                        .max_height(60.0)
+                        .animated(false)
+                        .show(ui, |ui| {
+                            if ui
+                                .add_sized(
+                                    ui.available_size(),
+                                    TextEdit::multiline(&mut self.summary_data)
+                                        .hint_text(tr("summary-table-hint")),
+                                )
+                                .changed()
+                            {
+                                self.parse_requested = Some(Instant::now());
+                            }
+                        });
+                });
 
-            ui.horizontal(|ui| {
-                if ui.button("➕ Single").clicked() {
-                    self.excluded_dates.push(ExcludedDate::single());
+                if data_response.changed() {
+                    self.parse_requested = Some(Instant::now());
                 }
 
-                if ui.button("➕ Range").clicked() {
-                    self.excluded_dates.push(ExcludedDate::range());
+                // only once it's slow, so the spinner doesn't flash while typing
+                if self
+                    .parsing
+                    .as_ref()
+                    .is_some_and(|(started, _)| started.elapsed() > SLOW_PARSE)
+                {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(tr("parsing"));
+                    });
+                } else if let Some(Err(e)) = &self.schedule {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {e}"));
                 }
-            });
 
-            if !self.excluded_dates.is_empty() {
-                ui.add_space(6.0);
-            }
+                if let Some(Ok(schedule)) = &mut self.schedule {
+                    ui.add_space(12.0);
+                    ui.heading(tr("preview-heading"));
+                    if let Some(format) = &schedule.format {
+                        ui.label(tr_args(
+                            "detected-format",
+                            &[("format", format.name.into())],
+                        ));
+                    }
+                    let terms = schedule.terms();
+                    if terms.len() > 1 {
+                        let previous = self.selected_term.clone();
+                        ui.horizontal(|ui| {
+                            ui.label(tr("term"));
+                            ComboBox::from_id_source("term")
+                                .selected_text(
+                                    self.selected_term
+                                        .clone()
+                                        .unwrap_or_else(|| tr("all-terms")),
+                                )
+                                .show_ui(ui, |ui| {
+                                    for term in terms {
+                                        ui.selectable_value(
+                                            &mut self.selected_term,
+                                            Some(term.to_owned()),
+                                            term,
+                                        );
+                                    }
+                                    ui.selectable_value(
+                                        &mut self.selected_term,
+                                        None,
+                                        tr("all-terms"),
+                                    );
+                                })
+                                .response
+                                .on_hover_text(tr("term-hover"));
+                        });
+                        if self.selected_term != previous {
+                            self.selected_classes =
+                                term_selection(schedule, self.selected_term.as_deref());
+                            self.changes = None;
+                        }
+                    }
+                    if let Some(line) = diagnostics::failures(ui, &schedule.failures) {
+                        self.scroll_to_line = Some(line);
+                    }
+                    for warning in &schedule.warnings {
+                        ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {warning}"));
+                    }
+                    let selected = selected_classes(schedule, &self.selected_classes);
+                    for conflict in conflicts::find_conflicts(selected.iter().copied()) {
+                        ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {conflict}"));
+                    }
+                    for change in
+                        conflicts::find_campus_changes(selected.iter().copied(), &self.profile)
+                    {
+                        ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {change}"));
+                    }
+
+                    if schedule.classes.is_empty() {
+                        ui.label(tr("no-classes"));
+                    } else {
+                        preview::preview_table(ui, schedule, &mut self.selected_classes);
+                        ui.collapsing(tr("week-view"), |ui| {
+                            week_grid::week_grid(
+                                ui,
+                                schedule,
+                                &self.selected_classes,
+                                &self.options.colors,
+                                &mut self.week_grid_status,
+                            );
+                        });
+                        ui.collapsing(tr("free-time"), |ui| {
+                            free_time_view::free_time_view(
+                                ui,
+                                &selected_classes(schedule, &self.selected_classes),
+                                self.selected_term.as_deref().unwrap_or(&schedule.term),
+                                &mut self.options,
+                                &mut self.friend_schedule,
+                                &self.profile,
+                                self.date_order,
+                            );
+                        });
+                    }
+                }
+
+                ui.add_space(12.0);
+                ui.heading(tr("excluded-dates-heading"));
 
-            let mut i = 0;
-            self.excluded_dates.retain_mut(|range| {
                 ui.horizontal(|ui| {
-                    let should_delete = ui.button("❌").clicked();
+                    if ui.button(tr("add-single-date")).clicked() {
+                        self.excluded_dates.push(ExcludedDate::single());
+                    }
+
+                    if ui.button(tr("add-date-range")).clicked() {
+                        self.excluded_dates.push(ExcludedDate::range());
+                    }
+
+                    if !self.profile.date_presets.is_empty() {
+                        ui.menu_button(tr("add-preset"), |ui| {
+                            for preset in &self.profile.date_presets {
+                                let excluded_date = ExcludedDate::fixed(preset.start, preset.end);
+                                if ui
+                                    .add_enabled(
+                                        !self.excluded_dates.contains(&excluded_date),
+                                        Button::new(&preset.name),
+                                    )
+                                    .clicked()
+                                {
+                                    self.excluded_dates.push(excluded_date);
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    }
+                });
 
-                    if date_picker(ui, &mut range.start, &format!("{i}_start")).changed()
-                        && !range.was_changed
-                        && range.end.is_some()
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    ui.label(tr("import-holidays"));
+                    ui.add(
+                        TextEdit::singleline(&mut self.holidays_source)
+                            .hint_text("https://example.com/holidays.ics"),
+                    );
+                    if ui
+                        .add_enabled(!self.holidays_source.is_empty(), Button::new(tr("import")))
+                        .clicked()
                     {
-                        range.was_changed = true;
-                        range.end = Some(range.start);
-                    };
+                        self.import_holidays(&self.holidays_source.clone());
+                    }
+                    if ui.button(tr("from-file")).clicked() {
+                        if let Some(path) = FileDialog::new()
+                            .add_filter("iCalendar", &["ics"])
+                            .pick_file()
+                        {
+                            self.import_holidays(&path.to_string_lossy());
+                        }
+                    }
+                    if let Some(status) = &self.holidays_status {
+                        ui.label(status);
+                    }
+                });
 
-                    if let Some(end) = &mut range.end {
-                        ui.label("-");
-                        if date_picker(ui, end, &format!("{i}_end")).changed() && !range.was_changed
+                if !self.excluded_dates.is_empty() {
+                    ui.add_space(6.0);
+                }
+
+                let mut i = 0;
+                self.excluded_dates.retain_mut(|range| {
+                    ui.horizontal(|ui| {
+                        let should_delete = ui.button("❌").clicked();
+
+                        if date_picker(ui, &mut range.start, &format!("{i}_start")).changed()
+                            && !range.was_changed
+                            && range.end.is_some()
                         {
                             range.was_changed = true;
-                            range.start = *end;
+                            range.end = Some(range.start);
                         };
+
+                        if let Some(end) = &mut range.end {
+                            ui.label("-");
+                            if date_picker(ui, end, &format!("{i}_end")).changed()
+                                && !range.was_changed
+                            {
+                                range.was_changed = true;
+                                range.start = *end;
+                            };
+                        }
+
+                        i += 1;
+                        !should_delete
+                    })
+                    .inner
+                });
+
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(tr("add-swap-day"))
+                        .on_hover_text(tr("add-swap-day-hover"))
+                        .clicked()
+                    {
+                        self.options.swap_days.push(SwapDay {
+                            date: *DEFAULT_DATE,
+                            weekday: Weekday::Mon,
+                        });
                     }
+                });
 
-                    i += 1;
-                    !should_delete
-                })
-                .inner
-            });
+                let mut i = 0;
+                self.options.swap_days.retain_mut(|swap_day| {
+                    ui.horizontal(|ui| {
+                        let should_delete = ui.button("❌").clicked();
+                        date_picker(ui, &mut swap_day.date, &format!("{i}_swap"));
+                        ui.label(tr("swap-day-follows"));
+                        ComboBox::from_id_source(("swap_weekday", i))
+                            .selected_text(weekday_name(swap_day.weekday))
+                            .show_ui(ui, |ui| {
+                                for weekday in WEEKDAYS {
+                                    ui.selectable_value(
+                                        &mut swap_day.weekday,
+                                        weekday,
+                                        weekday_name(weekday),
+                                    );
+                                }
+                            });
+                        ui.label(tr("swap-day-schedule"));
 
-            ui.add_space(12.0);
-            ui.heading("Output");
+                        i += 1;
+                        !should_delete
+                    })
+                    .inner
+                });
 
-            ui.horizontal(|ui| {
-                if ui.button("Select output folder...").clicked() {
-                    if let Some(path) = FileDialog::new().pick_folder() {
-                        self.output_folder = Some(path);
+                ui.add_space(12.0);
+                ui.heading(tr("custom-events-heading"));
+                let term_dates = match &self.schedule {
+                    Some(Ok(schedule)) => {
+                        let date_ranges = selected_classes(schedule, &self.selected_classes)
+                            .into_iter()
+                            .flat_map(|class| &class.date_ranges);
+                        date_ranges
+                            .clone()
+                            .map(|d| d.start_date)
+                            .min()
+                            .zip(date_ranges.map(|d| d.end_date).max())
                     }
+                    _ => None,
+                };
+                if custom_events::custom_events(ui, &mut self.custom_events, term_dates) {
+                    self.changes = None;
                 }
 
-                if let Some(path) = &self.output_folder {
-                    ui.label(path.display().to_string());
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.add_space(6.0);
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    ui.label(tr("deadlines"))
+                        .on_hover_text(tr("deadlines-hover"));
+                    ui.add(
+                        TextEdit::singleline(&mut self.deadlines_source)
+                            .hint_text("webcal://example.instructure.com/feeds/calendars/...ics"),
+                    );
+                    if ui
+                        .add_enabled(!self.deadlines_source.is_empty(), Button::new(tr("import")))
+                        .clicked()
+                    {
+                        self.import_deadlines(&self.deadlines_source.clone());
+                    }
+                    if ui.button(tr("from-file")).clicked() {
+                        if let Some(path) = FileDialog::new()
+                            .add_filter("iCalendar", &["ics"])
+                            .pick_file()
+                        {
+                            self.import_deadlines(&path.to_string_lossy());
+                        }
+                    }
+                    if !self.options.deadlines.is_empty() && ui.button(tr("clear")).clicked() {
+                        self.options.deadlines.clear();
+                        self.deadlines_status = None;
+                        self.changes = None;
+                    }
+                    if let Some(status) = &self.deadlines_status {
+                        ui.label(status);
+                    }
+                });
+
+                ui.add_space(12.0);
+                ui.heading(tr("exam-schedule-heading"));
+                if exam_schedule::exam_schedule(ui, &mut self.exam_schedule, &self.profile) {
+                    self.changes = None;
                 }
-            });
 
-            ui.add_space(8.0);
+                ui.add_space(12.0);
+                ui.heading(tr("output-heading"));
+
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    if ui.button(tr("select-output-folder")).clicked() {
+                        if let Some(path) = FileDialog::new().pick_folder() {
+                            self.set_output_folder(path);
+                        }
+                    }
+
+                    let recent = self
+                        .recent_output_folders
+                        .iter()
+                        .filter(|path| path.is_dir())
+                        .collect::<Vec<_>>();
+                    let mut selected = None;
+                    match &self.output_folder {
+                        Some(path) if recent.len() > 1 => {
+                            ComboBox::from_id_source("output_folder")
+                                .selected_text(path.display().to_string())
+                                .show_ui(ui, |ui| {
+                                    for &recent in &recent {
+                                        if ui
+                                            .selectable_label(
+                                                recent == path,
+                                                recent.display().to_string(),
+                                            )
+                                            .clicked()
+                                        {
+                                            selected = Some(recent.clone());
+                                        }
+                                    }
+                                })
+                                .response
+                                .on_hover_text(tr("recent-output-folders"));
+                        }
+                        Some(path) => {
+                            ui.label(path.display().to_string());
+                        }
+                        None => (),
+                    }
+                    if let Some(path) = selected {
+                        self.set_output_folder(path);
+                    }
+                });
+
+                let grouping = self.options.grouping;
+                ui.horizontal(|ui| {
+                    if ui.button(tr("settings")).clicked() {
+                        self.show_settings = true;
+                    }
+                    ui.label(tr("settings-calendars"));
+                    ComboBox::from_id_source("grouping")
+                        .selected_text(settings::grouping_name(self.options.grouping))
+                        .show_ui(ui, |ui| {
+                            for grouping in Grouping::ALL {
+                                ui.selectable_value(
+                                    &mut self.options.grouping,
+                                    grouping,
+                                    settings::grouping_name(grouping),
+                                );
+                            }
+                        });
+                });
+                if self.options.grouping != grouping {
+                    self.changes = None;
+                }
 
-            ui.horizontal(|ui| {
                 if ui
-                    .add_enabled(
-                        self.can_generate_calendars(),
-                        Button::new("Generate calendar files"),
-                    )
-                    .clicked()
+                    .checkbox(&mut self.options.merge_weekdays, tr("merge-weekdays"))
+                    .on_hover_text(tr("merge-weekdays-hover"))
+                    .changed()
                 {
-                    self.generate_calendars();
+                    self.changes = None;
+                }
+
+                ui.checkbox(
+                    &mut self.options.zip,
+                    tr_args("zip", &[("zip", archive::ZIP_FILE_NAME.into())]),
+                )
+                .on_hover_text(tr("zip-hover"));
+
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.checkbox(&mut self.options.rename_existing, tr("rename-existing"))
+                    .on_hover_text(tr("rename-existing-hover"));
+
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    if ui
+                        .checkbox(&mut self.options.update_previous, tr("update-previous"))
+                        .on_hover_text(tr("update-previous-hover"))
+                        .changed()
+                    {
+                        self.changes = None;
+                    }
+
+                    if self.options.update_previous
+                        && ui
+                            .add_enabled(self.can_generate_calendars(), Button::new(tr("compare")))
+                            .clicked()
+                    {
+                        self.compare_with_previous_export();
+                    }
+                });
+
+                if self.options.update_previous {
+                    match &self.changes {
+                        Some(Ok(changes)) if changes.is_empty() => {
+                            ui.label(tr("no-changes"));
+                        }
+                        Some(Ok(changes)) => show_changes(ui, changes),
+                        Some(Err(e)) => {
+                            ui.colored_label(
+                                ui.visuals().warn_fg_color,
+                                tr_args(
+                                    "previous-export-failed",
+                                    &[("error", e.to_string().into())],
+                                ),
+                            );
+                        }
+                        None => (),
+                    }
                 }
 
-                if let Some(result_text) = &self.result_text {
-                    ui.label(result_text);
+                ui.add_space(8.0);
+
+                if let Some((ok, status)) = self.validation_status() {
+                    let color = if ok {
+                        regex_editor::OK_COLOR
+                    } else {
+                        ui.visuals().error_fg_color
+                    };
+                    ui.horizontal(|ui| {
+                        ui.colored_label(color, status);
+                        if !ok
+                            && ui
+                                .button(tr("copy-diagnostic-report"))
+                                .on_hover_text(tr("copy-diagnostic-report-hover"))
+                                .clicked()
+                        {
+                            let report =
+                                bug_report::bug_report(VERSION, &self.problem(), &self.data);
+                            ui.output_mut(|output| output.copied_text = report);
+                        }
+                    });
                 }
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(self.can_generate_calendars(), Button::new(tr("generate")))
+                        .clicked()
+                    {
+                        #[cfg(not(target_arch = "wasm32"))]
+                        {
+                            self.confirm_overwrite = self.existing_calendars();
+                            if self.confirm_overwrite.is_empty() {
+                                self.generate_calendars();
+                            }
+                        }
+                        #[cfg(target_arch = "wasm32")]
+                        self.generate_calendars();
+                    }
+
+                    ui.add_enabled_ui(self.can_generate_calendars(), |ui| {
+                        ui.menu_button(tr("export-as"), |ui| {
+                            for &format in ExportFormat::ALL {
+                                if ui
+                                    .button(format.to_string())
+                                    .on_hover_text(tr_args(
+                                        "export-format-hover",
+                                        &[("file", format.file_name().to_string().into())],
+                                    ))
+                                    .clicked()
+                                {
+                                    self.export(format);
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    });
+
+                    if let Some((progress, _)) = &self.generating {
+                        ui.add(
+                            ProgressBar::new(progress.fraction())
+                                .desired_width(150.0)
+                                .show_percentage(),
+                        );
+                        if ui
+                            .add_enabled(!progress.is_cancelled(), Button::new(tr("cancel")))
+                            .clicked()
+                        {
+                            progress.cancel();
+                        }
+                    } else if let Some(result_text) = &self.result_text {
+                        ui.label(result_text);
+                    }
+                });
+
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.collapsing("Google Calendar", |ui| {
+                    let can_push = matches!(self.schedule, Some(Ok(_)));
+                    if self.google.ui(ui, can_push) {
+                        if let Some(Ok(schedule)) = self.selected_schedule() {
+                            self.google.push(
+                                ctx.clone(),
+                                schedule,
+                                self.exdate(),
+                                self.profile.clone(),
+                                self.options.clone(),
+                            );
+                        }
+                    }
+                });
+
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.collapsing("Outlook", |ui| {
+                    let can_push = matches!(self.schedule, Some(Ok(_)));
+                    if self.outlook.ui(ui, can_push) {
+                        if let Some(Ok(schedule)) = self.selected_schedule() {
+                            self.outlook.push(
+                                ctx.clone(),
+                                schedule,
+                                self.exdate(),
+                                self.profile.clone(),
+                                self.options.clone(),
+                            );
+                        }
+                    }
+                });
+
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.collapsing("CalDAV", |ui| {
+                    let can_upload = matches!(self.schedule, Some(Ok(_)));
+                    if self.caldav.ui(ui, can_upload) {
+                        if let Some(Ok(schedule)) = self.selected_schedule() {
+                            self.caldav.upload(
+                                ctx.clone(),
+                                schedule,
+                                self.exdate(),
+                                self.profile.clone(),
+                                self.options.clone(),
+                            );
+                        }
+                    }
+                });
+
+                #[cfg(not(target_arch = "wasm32"))]
+                self.show_report(ui);
+
+                ui.add_space(12.0);
+                ui.collapsing(tr("log"), logging::log_panel);
             });
         });
+
+        self.history.update(&self.snapshot());
+        #[cfg(not(target_arch = "wasm32"))]
+        if !self.previous_session_checked && self.parsing.is_none() {
+            self.previous_session_checked = true;
+            let session = self.session();
+            self.previous_session
+                .take_if(|previous| *previous == session);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.previous_session.is_none() {
+            self.autosave.update(ctx, &self.session());
+        }
+        // picks up Ctrl +/-, but not while the slider is still being dragged
+        if !ctx.is_using_pointer() {
+            self.ui_scale = ctx.zoom_factor();
+        }
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        // kept for an accidental close, unless the data shouldn't be remembered
+        #[cfg(not(target_arch = "wasm32"))]
+        if !self.remember_data && self.previous_session.is_none() {
+            self.autosave.clear();
+        }
     }
 
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        #[cfg(not(target_arch = "wasm32"))]
         if let Some(output_folder) = self
             .output_folder
             .as_ref()
@@ -189,6 +2498,233 @@ impl eframe::App for App {
         {
             storage.set_string(OUTPUT_FOLDER_KEY, output_folder.to_owned())
         }
+        storage.set_string(PROFILE_KEY, self.profile.to_toml());
+        eframe::set_value(storage, CUSTOM_SUBJECTS_KEY, &self.custom_subjects);
+        if !self.configs.active.is_empty() {
+            let current = self.config();
+            self.configs
+                .saved
+                .insert(self.configs.active.clone(), current);
+        }
+        eframe::set_value(storage, CONFIGS_KEY, &self.configs);
+        let mut tabs = self.tabs.clone();
+        if !self.remember_data {
+            for tab in &mut tabs.tabs {
+                tab.state.session = Session::default();
+            }
+        }
+        eframe::set_value(storage, TABS_KEY, &tabs);
+        #[cfg(not(target_arch = "wasm32"))]
+        eframe::set_value(
+            storage,
+            RECENT_OUTPUT_FOLDERS_KEY,
+            &self.recent_output_folders,
+        );
+        eframe::set_value(storage, SETTINGS_KEY, &self.settings());
+        eframe::set_value(storage, EXCLUDED_DATES_KEY, &self.excluded_dates);
+        eframe::set_value(storage, CUSTOM_EVENTS_KEY, &self.custom_events);
+        eframe::set_value(storage, REMEMBER_DATA_KEY, &self.remember_data);
+        #[cfg(not(target_arch = "wasm32"))]
+        eframe::set_value(storage, CHECK_FOR_UPDATES_KEY, &self.check_for_updates);
+        eframe::set_value(storage, LANGUAGE_KEY, &self.language);
+        eframe::set_value(storage, UI_SCALE_KEY, &self.ui_scale);
+        eframe::set_value(storage, THEME_KEY, &self.theme);
+        #[cfg(not(target_arch = "wasm32"))]
+        eframe::set_value(storage, GOOGLE_CLIENT_KEY, &self.google.client);
+        #[cfg(not(target_arch = "wasm32"))]
+        eframe::set_value(storage, OUTLOOK_CLIENT_KEY, &self.outlook.client);
+        #[cfg(not(target_arch = "wasm32"))]
+        eframe::set_value(storage, CALDAV_SERVER_KEY, &self.caldav.server);
+        #[cfg(not(target_arch = "wasm32"))]
+        storage.set_string(DEADLINES_SOURCE_KEY, self.deadlines_source.clone());
+        #[cfg(not(target_arch = "wasm32"))]
+        storage.set_string(WEBDRIVER_URL_KEY, self.browser_fetch.webdriver_url.clone());
+        #[cfg(not(target_arch = "wasm32"))]
+        storage.set_string(BANNER_TERM_KEY, self.banner_fetch.term.clone());
+        let (data, summary_data) = if self.remember_data {
+            (self.data.clone(), self.summary_data.clone())
+        } else {
+            Default::default()
+        };
+        storage.set_string(DATA_KEY, data);
+        storage.set_string(SUMMARY_DATA_KEY, summary_data);
+    }
+}
+
+fn show_changes(ui: &mut egui::Ui, changes: &[MeetingChange]) {
+    for change in changes {
+        ui.label(match change {
+            MeetingChange::Added(meeting) => tr_args(
+                "change-added",
+                &[("meeting", meeting_label(meeting).into())],
+            ),
+            MeetingChange::Removed(meeting) => tr_args(
+                "change-removed",
+                &[("meeting", meeting_label(meeting).into())],
+            ),
+            MeetingChange::Changed { meeting, changes } => tr_args(
+                "change-changed",
+                &[
+                    ("meeting", meeting_label(meeting).into()),
+                    (
+                        "changes",
+                        changes
+                            .iter()
+                            .map(describe_change)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                            .into(),
+                    ),
+                ],
+            ),
+        });
+    }
+}
+
+/// Short description of a meeting, eg. "Calculus (Mon 11:10–12:30)".
+fn meeting_label(meeting: &update::Event) -> String {
+    tr_args(
+        "change-meeting",
+        &[
+            ("name", meeting.summary.as_str().into()),
+            ("time", event_time(&meeting.time()).into()),
+        ],
+    )
+}
+
+fn event_time(time: &EventTime) -> String {
+    match time {
+        EventTime::Timed { start, end } => format!(
+            "{} {}–{}",
+            weekday_name(start.weekday()),
+            start.format("%H:%M"),
+            end.format("%H:%M")
+        ),
+        EventTime::AllDay(date) => tr_args(
+            "change-all-day",
+            &[
+                ("weekday", weekday_name(date.weekday()).into()),
+                ("date", date.format("%Y-%m-%d").to_string().into()),
+            ],
+        ),
+        EventTime::Other(start) => start.clone(),
+    }
+}
+
+fn describe_change(change: &EventChange) -> String {
+    match change {
+        EventChange::Name { from, to } => tr_args(
+            "change-name",
+            &[("from", from.as_str().into()), ("to", to.as_str().into())],
+        ),
+        EventChange::Time { from, to } => tr_args(
+            "change-time",
+            &[
+                ("from", event_time(from).into()),
+                ("to", event_time(to).into()),
+            ],
+        ),
+        EventChange::Location { from, to } => tr_args(
+            "change-location",
+            &[("from", from.as_str().into()), ("to", to.as_str().into())],
+        ),
+        EventChange::Dates => tr("change-dates"),
+        EventChange::Description => tr("change-description"),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn show_statistics(ui: &mut egui::Ui, statistics: &Statistics) {
+    for (heading, hours) in [
+        ("statistics-by-course", &statistics.courses),
+        ("statistics-by-type", &statistics.class_types),
+    ] {
+        ui.strong(tr(heading));
+        egui::Grid::new(heading).striped(true).show(ui, |ui| {
+            for (name, hours) in hours {
+                ui.label(name);
+                ui.label(format!("{hours:.1} h"));
+                ui.end_row();
+            }
+        });
+    }
+}
+
+/// Which classes are selected when only the classes of `term` should be, or all if it's `None`.
+fn term_selection(schedule: &parser::Schedule, term: Option<&str>) -> Vec<bool> {
+    schedule
+        .classes
+        .iter()
+        .map(|class| term.is_none_or(|term| schedule.class_term(class) == term))
+        .collect()
+}
+
+/// The message for `key` saying why the file `name` couldn't be loaded or read.
+fn load_error(key: &str, name: &str, error: impl ToString) -> String {
+    tr_args(
+        key,
+        &[("name", name.into()), ("error", error.to_string().into())],
+    )
+}
+
+/// The message for `key` saying why `file` couldn't be opened.
+#[cfg(not(target_arch = "wasm32"))]
+fn file_error(key: &str, file: &Path, error: std::io::Error) -> String {
+    tr_args(
+        key,
+        &[
+            ("path", file.display().to_string().into()),
+            ("error", error.to_string().into()),
+        ],
+    )
+}
+
+/// The schedule data in a loaded file, converting saved web pages to text.
+fn schedule_text(name: &str, contents: Vec<u8>) -> Result<String, String> {
+    let text = String::from_utf8(contents).map_err(|_| tr("not-text-file"))?;
+    let name = name.to_lowercase();
+    Ok(if name.ends_with(".html") || name.ends_with(".htm") {
+        html::html_to_text(&text)
+    } else {
+        text
+    })
+}
+
+/// Lists `files` with buttons to open them. Returns the error if one couldn't be opened.
+#[cfg(not(target_arch = "wasm32"))]
+fn show_files(ui: &mut egui::Ui, files: &[PathBuf]) -> Option<String> {
+    let mut error = None;
+    egui::Grid::new("files").show(ui, |ui| {
+        for file in files {
+            ui.label(file.file_name().unwrap_or_default().to_string_lossy());
+            if ui.small_button(tr("open-file")).clicked() {
+                error = open::that(file)
+                    .err()
+                    .map(|e| file_error("open-file-failed", file, e));
+            }
+            if ui.small_button(tr("reveal-in-folder")).clicked() {
+                error = reveal(file)
+                    .err()
+                    .map(|e| file_error("reveal-in-folder-failed", file, e));
+            }
+            ui.end_row();
+        }
+    });
+    error
+}
+
+/// Opens the folder containing `file`, with it selected where the file manager supports that.
+#[cfg(not(target_arch = "wasm32"))]
+fn reveal(file: &Path) -> std::io::Result<()> {
+    if cfg!(target_os = "windows") {
+        Command::new("explorer")
+            .arg(format!("/select,{}", file.display()))
+            .spawn()
+            .map(drop)
+    } else if cfg!(target_os = "macos") {
+        Command::new("open").arg("-R").arg(file).spawn().map(drop)
+    } else {
+        open::that(file.parent().unwrap_or(file))
     }
 }
 
@@ -200,7 +2736,46 @@ fn date_picker(ui: &mut egui::Ui, selection: &mut NaiveDate, id_source: &str) ->
         .ui(ui)
 }
 
-#[derive(Debug, Clone)]
+/// The edits that can be undone: the pasted data, the excluded dates, and the parsed schedule with
+/// any changes from the preview.
+#[derive(Clone, Default)]
+struct Snapshot {
+    data: String,
+    excluded_dates: Vec<ExcludedDate>,
+    schedule: Option<Schedule>,
+}
+
+impl PartialEq for Snapshot {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+            && self.excluded_dates == other.excluded_dates
+            && self.schedule.as_ref().map(|schedule| &schedule.classes)
+                == other.schedule.as_ref().map(|schedule| &schedule.classes)
+    }
+}
+
+/// What's autosaved to restore after a crash. Like [`Snapshot`], but with only the classes of the
+/// schedule, since the rest is parsed again from the data.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct Session {
+    data: String,
+    summary_data: String,
+    excluded_dates: Vec<ExcludedDate>,
+    classes: Vec<Class>,
+}
+
+/// What's kept for each tab while another one is active.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct TabState {
+    session: Session,
+    config: configs::Config,
+    /// The configuration that was active in the tab, if it had a name.
+    config_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct ExcludedDate {
     start: NaiveDate,
     end: Option<NaiveDate>,
@@ -224,6 +2799,15 @@ impl ExcludedDate {
         Self::new(*DEFAULT_DATE, Some(*DEFAULT_DATE))
     }
 
+    /// An already complete date range, so changing one end doesn't move the other.
+    fn fixed(start: NaiveDate, end: NaiveDate) -> Self {
+        Self {
+            start,
+            end: (end != start).then_some(end),
+            was_changed: true,
+        }
+    }
+
     fn iter_days(&self) -> impl Iterator<Item = NaiveDate> {
         let (start, end) = match self.end {
             Some(end) if end < self.start => (end, self.start),
@@ -237,6 +2821,17 @@ impl ExcludedDate {
     }
 }
 
+/// The classes in `schedule` that are checked in the preview.
+fn selected_classes<'a>(schedule: &'a Schedule, selected: &[bool]) -> Vec<&'a Class> {
+    schedule
+        .classes
+        .iter()
+        .zip(selected.iter().chain(iter::repeat(&true)))
+        .filter(|(_, &selected)| selected)
+        .map(|(class, _)| class)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,261 @@
+//! Merging the generated events into an existing calendar file, eg. a personal calendar that's
+//! kept in one file.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::parser::finish_calendar;
+
+/// The result of [`merge_calendars`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Merged {
+    pub calendar: String,
+    /// Events from the existing calendar that were kept as-is.
+    pub kept: usize,
+    /// Events from the existing calendar that were replaced by generated ones.
+    pub replaced: usize,
+    /// Generated events that weren't in the existing calendar.
+    pub added: usize,
+}
+
+/// A component of a calendar, eg. a `VEVENT`, with its lines unfolded.
+struct Component {
+    lines: Vec<String>,
+}
+
+impl Component {
+    fn kind(&self) -> &str {
+        self.lines[0].strip_prefix("BEGIN:").unwrap_or_default()
+    }
+
+    /// The value of the first `name` property, without its parameters.
+    fn property(&self, name: &str) -> Option<&str> {
+        self.lines.iter().find_map(|line| {
+            let rest = line.strip_prefix(name)?;
+            rest.strip_prefix(':')
+                .or_else(|| Some(rest.strip_prefix(';')?.split_once(':')?.1))
+        })
+    }
+
+    /// The same event is either the same UID, or the same title at the same time.
+    fn is_same_event(&self, other: &Component) -> bool {
+        let uid = self.property("UID");
+        (uid.is_some() && uid == other.property("UID"))
+            || (self.property("SUMMARY") == other.property("SUMMARY")
+                && self.property("DTSTART").is_some()
+                && self.property("DTSTART") == other.property("DTSTART"))
+    }
+}
+
+/// The properties of the `VCALENDAR` itself, and its components.
+fn split_calendar(calendar: &str) -> (Vec<String>, Vec<Component>) {
+    let unfolded = calendar
+        .replace("\r\n", "\n")
+        .replace("\n ", "")
+        .replace("\n\t", "");
+
+    let mut properties = Vec::new();
+    let mut components = Vec::new();
+    let mut current: Option<(String, Vec<String>)> = None;
+    for line in unfolded.lines().filter(|line| !line.is_empty()) {
+        match &mut current {
+            Some((kind, lines)) => {
+                lines.push(line.to_owned());
+                // nested components like VALARM stay part of their event
+                if line.strip_prefix("END:") == Some(kind.as_str()) {
+                    let (_, lines) = current.take().unwrap_or_default();
+                    components.push(Component { lines });
+                }
+            }
+            None => match line.strip_prefix("BEGIN:") {
+                Some("VCALENDAR") => (),
+                Some(kind) => current = Some((kind.to_owned(), vec![line.to_owned()])),
+                None if line == "END:VCALENDAR" => (),
+                None => properties.push(line.to_owned()),
+            },
+        }
+    }
+    (properties, components)
+}
+
+/// Adds the events of the `generated` calendars to `existing`, replacing the events in it that
+/// they duplicate. `existing`'s calendar properties and other components (eg. to-dos) are kept,
+/// and time zones are only added if they aren't already there.
+pub fn merge_calendars<'a>(existing: &str, generated: impl IntoIterator<Item = &'a str>) -> Merged {
+    let (properties, mut components) = split_calendar(existing);
+    let mut events = Vec::new();
+    for calendar in generated {
+        let (_, generated) = split_calendar(calendar);
+        for component in generated {
+            if component.kind() == "VEVENT" {
+                events.push(component);
+            } else if component.kind() == "VTIMEZONE"
+                && !components.iter().any(|existing| {
+                    existing.kind() == "VTIMEZONE"
+                        && existing.property("TZID") == component.property("TZID")
+                })
+            {
+                // time zones have to come before the events using them
+                let index = components
+                    .iter()
+                    .position(|existing| existing.kind() != "VTIMEZONE")
+                    .unwrap_or(components.len());
+                components.insert(index, component);
+            }
+        }
+    }
+
+    let added = events
+        .iter()
+        .filter(|event| {
+            !components
+                .iter()
+                .any(|component| component.kind() == "VEVENT" && event.is_same_event(component))
+        })
+        .count();
+    let (mut kept, mut replaced) = (0, 0);
+    components.retain(|component| {
+        if component.kind() != "VEVENT" {
+            true
+        } else if events.iter().any(|event| event.is_same_event(component)) {
+            replaced += 1;
+            false
+        } else {
+            kept += 1;
+            true
+        }
+    });
+
+    let mut calendar = String::from("BEGIN:VCALENDAR\n");
+    if properties.is_empty() {
+        calendar.push_str("VERSION:2.0\nPRODID:MYCAMPUS-CALENDAR-RS\n");
+    }
+    for line in properties
+        .iter()
+        .chain(components.iter().flat_map(|component| &component.lines))
+        .chain(events.iter().flat_map(|event| &event.lines))
+    {
+        calendar.push_str(line);
+        calendar.push('\n');
+    }
+    finish_calendar(&mut calendar);
+
+    Merged {
+        calendar,
+        kept,
+        replaced,
+        added,
+    }
+}
+
+/// Merges the `.ics` files in `generated` into the calendar at `existing`, and writes the result
+/// next to the generated files as eg. `Personal (merged).ics`. `existing` itself isn't changed.
+pub fn merge_into_file(
+    existing: impl AsRef<Path>,
+    generated: &[PathBuf],
+) -> io::Result<(PathBuf, Merged)> {
+    let existing = existing.as_ref();
+    let calendars = generated
+        .iter()
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("ics"))
+        })
+        .map(fs::read_to_string)
+        .collect::<io::Result<Vec<_>>>()?;
+    let merged = merge_calendars(
+        &fs::read_to_string(existing)?,
+        calendars.iter().map(String::as_str),
+    );
+
+    let folder = generated
+        .first()
+        .and_then(|path| path.parent())
+        .or(existing.parent())
+        .unwrap_or(Path::new("."));
+    let stem = existing
+        .file_stem()
+        .map_or_else(|| "calendar".into(), |stem| stem.to_string_lossy());
+    let path = folder.join(format!("{stem} (merged).ics"));
+    fs::write(&path, &merged.calendar)?;
+    Ok((path, merged))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use indoc::indoc;
+
+    use super::*;
+    use crate::{update::parse_events, write_calendars, InstitutionProfile, Parser};
+
+    #[test]
+    fn test_merge_calendars() {
+        let existing = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Example//Personal//EN
+            X-WR-CALNAME:Personal
+            BEGIN:VEVENT
+            UID:dentist@example.com
+            DTSTART:20240910T090000
+            SUMMARY:Dentist
+            BEGIN:VALARM
+            ACTION:DISPLAY
+            TRIGGER:-PT1H
+            END:VALARM
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:old-copy@example.com
+            DTSTART;TZID=America/Toronto:20240904T111000
+            SUMMARY:Discrete Mathematics for Comp. Sci
+            END:VEVENT
+            BEGIN:VTODO
+            UID:todo@example.com
+            SUMMARY:Buy textbooks
+            END:VTODO
+            END:VCALENDAR
+        "};
+
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let output_folder = crate::test_folder("merge");
+        let report = write_calendars(
+            &output_folder,
+            &schedule,
+            HashSet::new(),
+            &InstitutionProfile::default(),
+            &Default::default(),
+        );
+        let generated = report
+            .files
+            .iter()
+            .map(|path| fs::read_to_string(path).unwrap())
+            .collect::<Vec<_>>();
+        let generated_events = generated
+            .iter()
+            .map(|calendar| parse_events(calendar).len())
+            .sum::<usize>();
+
+        let merged = merge_calendars(existing, generated.iter().map(String::as_str));
+        assert_eq!(merged.kept, 1);
+        assert_eq!(merged.replaced, 1);
+        assert_eq!(merged.added, generated_events - 1);
+
+        let events = parse_events(&merged.calendar);
+        assert_eq!(events.len(), generated_events + 1);
+        assert!(events.contains_key("dentist@example.com"));
+        assert!(!events.contains_key("old-copy@example.com"));
+        assert!(merged
+            .calendar
+            .starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Example//Personal//EN\r\n"));
+        assert!(merged.calendar.contains("SUMMARY:Buy textbooks\r\n"));
+        assert!(merged.calendar.contains("TRIGGER:-PT1H\r\nEND:VALARM\r\n"));
+        assert_eq!(merged.calendar.matches("BEGIN:VTIMEZONE").count(), 1);
+        assert!(merged.calendar.ends_with("END:VCALENDAR"));
+    }
+}
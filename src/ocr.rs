@@ -0,0 +1,129 @@
+//! Reading the schedule from a screenshot, for devices where copying the page mangles its text.
+//!
+//! The text is recognized by the [Tesseract](https://github.com/tesseract-ocr/tesseract) command
+//! line program, which has to be installed separately, and then tidied up into the layout that
+//! the regular [`Parser`](crate::Parser) reads.
+
+use std::{
+    fs, io,
+    path::PathBuf,
+    process::Command,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use thiserror::Error;
+
+/// File extensions of images that can be read.
+pub const IMAGE_EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "bmp", "gif", "tif", "tiff"];
+
+static DATE_DASH_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\d{2}/\d{2}/\d{4})\s*[-–—~]+\s*(\d{2}/\d{2}/\d{4})").unwrap());
+/// The row of day abbreviations beside each meeting, recognized as one or more lines.
+static DAY_LETTERS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^([SMTWF] ?)+$").unwrap());
+
+#[derive(Debug, Error)]
+pub enum OcrError {
+    #[error("Tesseract isn't installed or isn't on the PATH ({0})")]
+    NotInstalled(#[source] io::Error),
+    #[error("Failed to save the image for Tesseract: {0}")]
+    Io(#[from] io::Error),
+    #[error("Tesseract couldn't read the image: {0}")]
+    Failed(String),
+}
+
+/// Whether a file with this name is an image that can be read with [`image_to_text`].
+pub fn is_image(name: &str) -> bool {
+    name.rsplit_once('.')
+        .is_some_and(|(_, extension)| IMAGE_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+/// Recognizes the text in an image file's contents, eg. a PNG screenshot.
+pub fn image_to_text(image: &[u8]) -> Result<String, OcrError> {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let path = std::env::temp_dir().join(format!(
+        "mycampus-calendar-rs-ocr-{}-{}",
+        std::process::id(),
+        COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&path, image)?;
+    let result = tesseract(path.clone());
+    fs::remove_file(&path).ok();
+    result.map(|text| clean_text(&text))
+}
+
+/// Recognizes the text in an image given as RGBA pixels, eg. from the clipboard.
+pub fn pixels_to_text(width: usize, height: usize, rgba: &[u8]) -> Result<String, OcrError> {
+    // a binary PPM, which Tesseract reads without needing an image encoder here
+    let mut ppm = format!("P6\n{width} {height}\n255\n").into_bytes();
+    ppm.extend(rgba.chunks_exact(4).flat_map(|pixel| &pixel[..3]));
+    image_to_text(&ppm)
+}
+
+fn tesseract(path: PathBuf) -> Result<String, OcrError> {
+    // treat the page as one block of text, which keeps each line of the schedule together
+    let output = Command::new("tesseract")
+        .arg(path)
+        .args(["stdout", "--psm", "6"])
+        .output()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => OcrError::NotInstalled(e),
+            _ => OcrError::Io(e),
+        })?;
+    if !output.status.success() {
+        return Err(OcrError::Failed(
+            String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Tidies recognized text into the layout of a copied page: blank lines and the day
+/// abbreviations are dropped, and the dashes between dates are made consistent. The result
+/// matches the "Screenshot" layout in [`crate::parser::FORMAT_VERSIONS`], with the weekday on the
+/// date line.
+fn clean_text(text: &str) -> String {
+    text.lines()
+        .map(|line| line.trim().replace(['‘', '’'], "'"))
+        .filter(|line| !line.is_empty() && !DAY_LETTERS_RE.is_match(line))
+        .map(|line| DATE_DASH_RE.replace(&line, "$1 -- $2").into_owned())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    #[test]
+    fn test_clean_text() {
+        // what Tesseract makes of a screenshot of the page in tests/data/chromium.txt
+        let text = include_str!("../tests/data/chromium.txt")
+            .lines()
+            .map(|line| line.trim().replace(" -- ", " — "))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+            .replace("S\n\nM\n\nT\n\nW\n\nT\n\nF\n\nS", "SMTWTFS");
+
+        let schedule = Parser::new().parse_data(&clean_text(&text)).unwrap();
+        let want = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+
+        assert!(schedule.failures.is_empty());
+        assert_eq!(schedule.format.unwrap().name, "Screenshot");
+        assert_eq!(
+            serde_json::to_value(&schedule.classes).unwrap(),
+            serde_json::to_value(&want.classes).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_is_image() {
+        assert!(is_image("Screenshot 2024-09-04.PNG"));
+        assert!(!is_image("schedule.txt"));
+        assert!(!is_image("png"));
+    }
+}
@@ -0,0 +1,408 @@
+//! Pushing the events to an Outlook or Office 365 calendar through Microsoft Graph.
+//!
+//! Signs in with the device code flow, so it also works for university accounts that only allow
+//! signing in from the browser, and keeps the refresh token in the system keyring. Graph doesn't
+//! let events be looked up by their iCalendar UID, so each event is tagged with the meeting's UID
+//! and date in extended properties instead. Pushing again updates the tagged events, and deletes
+//! the ones that no longer happen, eg. on newly excluded dates.
+
+use std::{collections::HashSet, io, thread, time::Duration};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::{
+    http::{percent_encode, KEYRING_SERVICE},
+    parser::{meetings, GenerateOptions, Meeting, Schedule},
+    InstitutionProfile,
+};
+
+const LOGIN_URL: &str = "https://login.microsoftonline.com";
+const API_URL: &str = "https://graph.microsoft.com/v1.0";
+const SCOPE: &str = "Calendars.ReadWrite offline_access";
+/// Arbitrary, but must never change, or previously pushed events won't be found.
+const PROPERTY_SET: &str = "{9b3c6f1e-5d2a-4c8e-a7f0-3e61d2b8c45a}";
+
+#[derive(Debug, Error)]
+pub enum OutlookError {
+    #[error("Request to Microsoft failed: {0}")]
+    Http(#[from] ureq::Error),
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("Unexpected response from Microsoft: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to access the system keyring: {0}")]
+    Keyring(#[from] keyring::Error),
+    #[error("Sign in failed: {0}")]
+    SignIn(String),
+}
+
+/// An app registration from the Azure portal, with public client flows allowed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutlookClient {
+    pub client_id: String,
+    /// `common`, `organizations`, or a tenant ID or domain, eg. `ontariotechu.net`.
+    pub tenant: String,
+}
+
+impl Default for OutlookClient {
+    fn default() -> Self {
+        Self {
+            client_id: String::new(),
+            tenant: "common".to_owned(),
+        }
+    }
+}
+
+/// A signed in Microsoft account.
+#[derive(Debug, Clone)]
+pub struct OutlookAccount {
+    access_token: String,
+}
+
+/// A calendar that the account can add events to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlookCalendar {
+    pub id: String,
+    pub name: String,
+}
+
+/// A code that the user enters at `verification_uri` to finish signing in.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceCode {
+    pub user_code: String,
+    pub verification_uri: String,
+    device_code: String,
+    interval: u64,
+    expires_in: u64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+impl OutlookClient {
+    fn keyring_entry(&self) -> Result<keyring::Entry, OutlookError> {
+        Ok(keyring::Entry::new(
+            KEYRING_SERVICE,
+            &format!("microsoft:{}", self.client_id),
+        )?)
+    }
+
+    fn url(&self, endpoint: &str) -> String {
+        format!("{LOGIN_URL}/{}/oauth2/v2.0/{endpoint}", self.tenant)
+    }
+
+    /// Signs in with the saved refresh token, if there is one that still works.
+    pub fn sign_in_saved(&self) -> Result<Option<OutlookAccount>, OutlookError> {
+        let entry = self.keyring_entry()?;
+        let Ok(refresh_token) = entry.get_password() else {
+            return Ok(None);
+        };
+        match self.token(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", &refresh_token),
+            ("scope", SCOPE),
+        ])? {
+            Ok(token) => self.signed_in(token).map(Some),
+            // probably revoked, so sign in again
+            Err(_) => {
+                entry.delete_credential()?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Starts signing in with a device code.
+    pub fn device_code(&self) -> Result<DeviceCode, OutlookError> {
+        let body = ureq::post(self.url("devicecode"))
+            .send_form([("client_id", self.client_id.as_str()), ("scope", SCOPE)])?
+            .body_mut()
+            .read_to_string()?;
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// Waits for the user to enter `code`. Blocks until then.
+    pub fn sign_in(&self, code: &DeviceCode) -> Result<OutlookAccount, OutlookError> {
+        let mut interval = code.interval.max(1);
+        let mut waited = 0;
+        loop {
+            thread::sleep(Duration::from_secs(interval));
+            waited += interval;
+            match self.token(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                ("device_code", &code.device_code),
+            ])? {
+                Ok(token) => return self.signed_in(token),
+                Err(e) if e.error == "authorization_pending" && waited < code.expires_in => (),
+                Err(e) if e.error == "slow_down" => interval += 5,
+                Err(e) => return Err(OutlookError::SignIn(e.error_description.unwrap_or(e.error))),
+            }
+        }
+    }
+
+    /// Forgets the saved refresh token.
+    pub fn sign_out(&self) -> Result<(), OutlookError> {
+        match self.keyring_entry()?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn signed_in(&self, token: TokenResponse) -> Result<OutlookAccount, OutlookError> {
+        // refresh tokens are rotated, so always save the latest one
+        if let Some(refresh_token) = &token.refresh_token {
+            self.keyring_entry()?.set_password(refresh_token)?;
+        }
+        Ok(OutlookAccount {
+            access_token: token.access_token,
+        })
+    }
+
+    /// The token, or the OAuth error, which the device code flow uses while waiting.
+    fn token(
+        &self,
+        params: &[(&str, &str)],
+    ) -> Result<Result<TokenResponse, ErrorResponse>, OutlookError> {
+        let mut response = ureq::post(self.url("token"))
+            .config()
+            .http_status_as_error(false)
+            .build()
+            .send_form(
+                [("client_id", self.client_id.as_str())]
+                    .into_iter()
+                    .chain(params.iter().copied()),
+            )?;
+        let body = response.body_mut().read_to_string()?;
+        Ok(if response.status().is_success() {
+            Ok(serde_json::from_str(&body)?)
+        } else {
+            Err(serde_json::from_str(&body)?)
+        })
+    }
+}
+
+impl OutlookAccount {
+    fn get(&self, url: &str) -> Result<Value, OutlookError> {
+        let body = ureq::get(url)
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .call()?
+            .body_mut()
+            .read_to_string()?;
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// Every item of a collection, following the pages.
+    fn get_all(&self, url: String) -> Result<Vec<Value>, OutlookError> {
+        let mut items = Vec::new();
+        let mut next = Some(url);
+        while let Some(url) = next {
+            let mut page = self.get(&url)?;
+            if let Value::Array(values) = page["value"].take() {
+                items.extend(values);
+            }
+            next = page["@odata.nextLink"].as_str().map(str::to_owned);
+        }
+        Ok(items)
+    }
+
+    /// The calendars that events can be added to, with the default calendar first.
+    pub fn calendars(&self) -> Result<Vec<OutlookCalendar>, OutlookError> {
+        let mut calendars = self
+            .get_all(format!(
+                "{API_URL}/me/calendars?$select=id,name,canEdit,isDefaultCalendar"
+            ))?
+            .into_iter()
+            .filter(|item| item["canEdit"].as_bool().unwrap_or_default())
+            .map(|item| {
+                let default = item["isDefaultCalendar"].as_bool().unwrap_or_default();
+                let calendar = OutlookCalendar {
+                    id: item["id"].as_str().unwrap_or_default().to_owned(),
+                    name: item["name"].as_str().unwrap_or_default().to_owned(),
+                };
+                (!default, calendar)
+            })
+            .collect::<Vec<_>>();
+        calendars.sort_by_key(|(secondary, _)| *secondary);
+        Ok(calendars
+            .into_iter()
+            .map(|(_, calendar)| calendar)
+            .collect())
+    }
+
+    /// Creates or updates one event per occurrence of every meeting of `schedule` in
+    /// `calendar_id`, deletes the previously pushed occurrences that no longer happen, and returns
+    /// how many events there are now. The arguments are used the same way as by
+    /// [`write_calendars`](crate::write_calendars).
+    pub fn push(
+        &self,
+        calendar_id: &str,
+        schedule: &Schedule,
+        exdate: &HashSet<NaiveDate>,
+        profile: &InstitutionProfile,
+        options: &GenerateOptions,
+    ) -> Result<usize, OutlookError> {
+        let events_url = format!(
+            "{API_URL}/me/calendars/{}/events",
+            percent_encode(calendar_id)
+        );
+        let mut count = 0;
+        for meeting in meetings(schedule, profile, options) {
+            let mut existing = self.pushed_events(&events_url, &meeting.uid)?;
+            for date in meeting.occurrences(exdate, &options.swap_days) {
+                let event = event_resource(&meeting, date, profile, options).to_string();
+                let request = match existing.iter().position(|(d, _)| *d == Some(date)) {
+                    Some(i) => {
+                        let (_, id) = existing.swap_remove(i);
+                        ureq::patch(format!("{API_URL}/me/events/{}", percent_encode(&id)))
+                    }
+                    None => ureq::post(&events_url),
+                };
+                request
+                    .header("Authorization", format!("Bearer {}", self.access_token))
+                    .header("Content-Type", "application/json")
+                    .send(event)?;
+                count += 1;
+            }
+            for (_, id) in existing {
+                ureq::delete(format!("{API_URL}/me/events/{}", percent_encode(&id)))
+                    .header("Authorization", format!("Bearer {}", self.access_token))
+                    .call()?;
+            }
+        }
+        Ok(count)
+    }
+
+    /// The date and ID of each event previously pushed for the meeting with `uid`.
+    fn pushed_events(
+        &self,
+        events_url: &str,
+        uid: &str,
+    ) -> Result<Vec<(Option<NaiveDate>, String)>, OutlookError> {
+        let filter = format!(
+            "singleValueExtendedProperties/Any(ep: ep/id eq '{}' and ep/value eq '{}')",
+            property_id("Uid"),
+            uid.replace('\'', "''"),
+        );
+        let expand = format!(
+            "singleValueExtendedProperties($filter=id eq '{}')",
+            property_id("Date")
+        );
+        let events = self.get_all(format!(
+            "{events_url}?$select=id&$top=100&$filter={}&$expand={}",
+            percent_encode(&filter),
+            percent_encode(&expand),
+        ))?;
+        Ok(events
+            .iter()
+            .map(|event| {
+                let date = event["singleValueExtendedProperties"][0]["value"]
+                    .as_str()
+                    .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+                (date, event["id"].as_str().unwrap_or_default().to_owned())
+            })
+            .collect())
+    }
+}
+
+fn property_id(name: &str) -> String {
+    format!("String {PROPERTY_SET} Name MyCampus{name}")
+}
+
+/// The Graph event for the occurrence of `meeting` on `date`.
+fn event_resource(
+    meeting: &Meeting,
+    date: NaiveDate,
+    profile: &InstitutionProfile,
+    options: &GenerateOptions,
+) -> Value {
+    let date_time = |date_time: chrono::NaiveDateTime| {
+        json!({
+            "dateTime": date_time.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            "timeZone": profile.timezone,
+        })
+    };
+    let (start, end) = match meeting.date_range {
+        Some(date_range) => {
+            let (start_time, end_time) = options.event_times(date_range);
+            (
+                date_time(date.and_time(start_time)),
+                date_time(date.and_time(end_time)),
+            )
+        }
+        None => (
+            date_time(date.and_time(Default::default())),
+            date_time(date.succ_opt().unwrap().and_time(Default::default())),
+        ),
+    };
+    let reminder = options.reminders.get(&meeting.class.class_type);
+
+    json!({
+        "subject": meeting.event.summary,
+        "body": { "contentType": "text", "content": meeting.event.description },
+        "location": { "displayName": meeting.event.location },
+        "start": start,
+        "end": end,
+        "isAllDay": meeting.date_range.is_none(),
+        "showAs": if meeting.is_busy(options) { "busy" } else { "free" },
+        "sensitivity": if options.private_events { "private" } else { "normal" },
+        "isReminderOn": reminder.is_some(),
+        "reminderMinutesBeforeStart": reminder.copied().unwrap_or_default(),
+        "singleValueExtendedProperties": [
+            { "id": property_id("Uid"), "value": meeting.uid },
+            { "id": property_id("Date"), "value": date.format("%Y-%m-%d").to_string() },
+        ],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::AsyncEvents, Parser};
+
+    #[test]
+    fn test_event_resource() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let profile = InstitutionProfile::default();
+        let options = GenerateOptions {
+            reminders: [("Lecture".to_owned(), 10)].into(),
+            async_events: AsyncEvents::Weekly,
+            ..Default::default()
+        };
+        let meetings = meetings(&schedule, &profile, &options);
+        let date = NaiveDate::from_ymd_opt(2024, 9, 9).unwrap();
+
+        let event = event_resource(&meetings[0], date, &profile, &options);
+        assert_eq!(event["start"]["dateTime"], "2024-09-09T11:10:00");
+        assert_eq!(event["end"]["dateTime"], "2024-09-09T12:30:00");
+        assert_eq!(event["isAllDay"], false);
+        assert_eq!(event["reminderMinutesBeforeStart"], 10);
+        assert_eq!(
+            event["singleValueExtendedProperties"][0]["value"],
+            meetings[0].uid
+        );
+        assert_eq!(
+            event["singleValueExtendedProperties"][1]["value"],
+            "2024-09-09"
+        );
+
+        let all_day = meetings.iter().find(|m| m.date_range.is_none()).unwrap();
+        let event = event_resource(all_day, date, &profile, &options);
+        assert_eq!(event["isAllDay"], true);
+        assert_eq!(event["end"]["dateTime"], "2024-09-10T00:00:00");
+        assert_eq!(event["isReminderOn"], false);
+    }
+}
@@ -1,208 +1,1164 @@
 use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
 use indoc::indoc;
-use phf::phf_map;
-use regex::Regex;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::Write,
     fs::{self},
-    path::Path,
+    iter::Peekable,
+    path::{Path, PathBuf},
+    ptr,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
 };
-use uuid::Uuid;
-
-static SUBJECTS: phf::Map<&'static str, &'static str> = phf_map! {
-    "Academic Learning and Success" => "ALSU",
-    "Biology" => "BIOL",
-    "Business" => "BUSI",
-    "Chemistry" => "CHEM",
-    "Communications" => "COMM",
-    "Computer Science" => "CSCI",
-    "Criminology and Justice" => "CRMN",
-    "Curriculum Studies" => "CURS",
-    "Economics" => "ECON",
-    "Education" => "EDUC",
-    "Educational Studies and Digital Technology" => "AEDT",
-    "Electrical Engineering" => "ELEE",
-    "Energy Systems and Nuclear Science" => "ESNS",
-    "Engineering" => "ENGR",
-    "Environmental Science" => "ENVS",
-    "Forensic Science" => "FSCI",
-    "Health Science" => "HLSC",
-    "Indigenous Studies" => "INDG",
-    "Information Technology" => "INFR",
-    "Integrated Mathematics and Computer Science" => "IMCS",
-    "Kinesiology" => "KINE",
-    "Legal Studies" => "LGLS",
-    "Liberal Studies" => "LBAT",
-    "Manufacturing Engineering" => "MANE",
-    "Mathematics" => "MATH",
-    "Mechanical Engineering" => "MECE",
-    "Mechatronics Engineering" => "METE",
-    "Medical Laboratory Science" => "MLSC",
-    "Neuroscience" => "NSCI",
-    "Nuclear" => "NUCL",
-    "Nursing" => "NURS",
-    "Physics" => "PHY",
-    "Political Science" => "POSC",
-    "Psychology" => "PSYC",
-    "Radiation Science" => "RADI",
-    "Science" => "SCIE",
-    "Science Co-op" => "SCCO",
-    "Science Co-op Work Term" => "SCCO",
-    "Social Science" => "SSCI",
-    "Sociology" => "SOCI",
-    "Software Engineering" => "SOFE",
-    "Statistics" => "STAT",
-    "Sustainable Energy Systems" => "ENSY",
+use thiserror::Error;
+use tracing::{debug, info, warn};
+
+use crate::{
+    archive, catalog,
+    deadlines::{self, Deadline},
+    exams, import,
+    profile::{InstitutionProfile, Markers},
+    stats::{self, Statistics},
+    template, term, travel,
+    update::{self, Event, MeetingChange},
 };
 
-#[derive(Debug)]
-struct DateRange {
-    start_date: NaiveDate,
-    end_date: NaiveDate,
-    start_time: NaiveTime,
-    end_time: NaiveTime,
-    weekday: Weekday,
-    location: String,
-    building: String,
-    room: String,
+/// One weekly meeting of a class, eg. the Monday lecture.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DateRange {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub weekday: Weekday,
+    /// The campus. This and the building and room are `None` until they've been assigned.
+    pub location: Option<String>,
+    pub building: Option<String>,
+    pub room: Option<String>,
+    /// Meets every other week instead of every week, eg. some labs.
+    pub biweekly: bool,
+    #[serde(default)]
+    pub kind: DateRangeKind,
+}
+
+/// Whether a [`DateRange`] repeats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DateRangeKind {
+    /// Every week (or every other week) between the start and end dates.
+    #[default]
+    Weekly,
+    /// Only once, on the start date, eg. an exam.
+    Single,
+}
+
+impl DateRange {
+    /// Every date this meets on, in order.
+    pub fn dates(&self) -> impl Iterator<Item = NaiveDate> {
+        match self.kind {
+            DateRangeKind::Weekly => weekly_dates(
+                first_on_or_after(self.start_date, self.weekday),
+                self.end_date,
+                self.biweekly,
+            ),
+            DateRangeKind::Single => weekly_dates(self.start_date, self.start_date, false),
+        }
+    }
+
+    pub fn is_weekly(&self) -> bool {
+        self.kind == DateRangeKind::Weekly
+    }
+
+    /// Whether this meets online instead of in a room, going by its location.
+    pub fn is_online(&self) -> bool {
+        self.online_place().is_some()
+    }
+
+    fn online_place(&self) -> Option<&String> {
+        [&self.location, &self.building, &self.room]
+            .into_iter()
+            .flatten()
+            .find(|place| {
+                let place = place.to_lowercase();
+                place.contains("online") || place.contains("virtual")
+            })
+    }
+
+    /// The building and room for the event's location, or [`TBA`] if neither is known yet. Online
+    /// meetings just say so, eg. "Online".
+    pub fn place(&self) -> String {
+        if let Some(place) = self.online_place() {
+            return place.clone();
+        }
+        match (&self.building, &self.room) {
+            (Some(building), Some(room)) => format!("{building} - {room}"),
+            (Some(place), None) | (None, Some(place)) => place.clone(),
+            (None, None) => TBA.to_owned(),
+        }
+    }
+}
+
+/// Calendar that exams are written to, see [`Grouping::calendar_name`].
+pub const EXAMS_CALENDAR: &str = "Exams";
+
+/// Shown in place of a location that hasn't been assigned yet.
+pub const TBA: &str = "TBA";
+
+/// A single course section (lecture, lab, tutorial, etc) from the schedule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Class {
+    pub name: String,
+    pub code: String,
+    pub date_ranges: Vec<DateRange>,
+    /// The instructor line, eg. "Instructor: Doe, John (Primary)", if one has been assigned.
+    pub instructor: Option<String>,
+    pub crn: String,
+    pub class_type: String,
+    /// From the registration summary table, if it was available.
+    pub credit_hours: Option<f32>,
+    /// Start and end dates of sections without scheduled meetings, eg. asynchronous online ones.
+    pub asynchronous: Vec<(NaiveDate, NaiveDate)>,
+    /// Term the class is in, eg. "Fall 2024", when the data had more than one.
+    #[serde(default)]
+    pub term: String,
+    /// Link for joining the online meetings, added by the user in the preview.
+    #[serde(default)]
+    pub meeting_url: Option<String>,
+}
+
+impl Class {
+    /// Whether this came from a [`CustomEvent`] instead of the schedule.
+    pub fn is_custom(&self) -> bool {
+        self.class_type == CUSTOM_EVENT_TYPE && self.code.is_empty() && self.crn.is_empty()
+    }
+
+    /// Whether this is an exam from [`exams`](crate::exams), rather than a class.
+    pub fn is_exam(&self) -> bool {
+        self.class_type == exams::EXAM_TYPE && self.crn.is_empty()
+    }
+
+    /// Whether any meetings are online, or there are asynchronous sections.
+    pub fn is_online(&self) -> bool {
+        !self.asynchronous.is_empty() || self.date_ranges.iter().any(DateRange::is_online)
+    }
+
+    /// The instructors on the instructor line, eg. "Doe, John (Primary), Smith, Alice".
+    pub fn instructors(&self) -> Vec<Instructor> {
+        let Some(line) = &self.instructor else {
+            return Vec::new();
+        };
+        let line = line.strip_prefix("Instructor:").unwrap_or(line).trim();
+        let parts = line.split(',').map(str::trim).collect::<Vec<_>>();
+        // names are "Last, First", so anything else is kept as one name
+        let names = if parts.len() % 2 == 0 {
+            parts
+                .chunks(2)
+                .map(|name| format!("{}, {}", name[0], name[1]))
+                .collect()
+        } else {
+            vec![line.to_owned()]
+        };
+        names
+            .into_iter()
+            .filter(|name| !is_tba(name))
+            .map(|name| match name.strip_suffix("(Primary)") {
+                Some(name) => Instructor {
+                    name: name.trim().to_owned(),
+                    primary: true,
+                },
+                None => Instructor {
+                    name,
+                    primary: false,
+                },
+            })
+            .collect()
+    }
+}
+
+/// An instructor of a [`Class`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instructor {
+    /// The name as it's shown in the schedule, eg. "Doe, John".
+    pub name: String,
+    pub primary: bool,
+}
+
+impl Instructor {
+    /// The name in reading order, eg. "John Doe".
+    pub fn display_name(&self) -> String {
+        match self.name.split_once(", ") {
+            Some((last, first)) => format!("{first} {last}"),
+            None => self.name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Error)]
+pub enum ParseError {
+    #[error("Failed to find start of schedule")]
+    MissingScheduleStart,
+    #[error(
+        "It looks like you copied the Registration Information page. Open the Schedule Details \
+        tab instead, expand all of the courses, and copy that page."
+    )]
+    RegistrationPage,
+    #[error("Unexpected end of data")]
+    UnexpectedEnd,
+    #[error("Failed to match course name line: {0}")]
+    CourseNameLine(String),
+    #[error("Failed to parse message line: {0}")]
+    MessageLine(String),
+    #[error("Failed to parse date: {0}")]
+    Date(String, #[source] chrono::ParseError),
+    #[error("Failed to parse weekday: {0}")]
+    Weekday(String),
+    #[error("Failed to parse time line: {0}")]
+    TimeLine(String),
+    #[error("Failed to parse time: {0}")]
+    Time(String, #[source] chrono::ParseError),
+    #[error("Failed to get short subject code for subject: {0}")]
+    ShortSubject(String),
+    #[error("Invalid regex in institution profile: {0}")]
+    Regex(#[from] regex::Error),
+    #[error("The {0} regex in the institution profile didn't capture group {1} of: {2}")]
+    RegexGroup(&'static str, usize, String),
+}
+
+impl ParseError {
+    /// The text from the input that couldn't be parsed, if the error is about a single line.
+    pub fn input(&self) -> Option<&str> {
+        match self {
+            Self::CourseNameLine(input)
+            | Self::MessageLine(input)
+            | Self::Date(input, _)
+            | Self::Weekday(input)
+            | Self::TimeLine(input)
+            | Self::Time(input, _)
+            | Self::ShortSubject(input)
+            | Self::RegexGroup(_, _, input) => Some(input),
+            _ => None,
+        }
+    }
+
+    /// What the parser was looking for instead, for telling the user how to fix the input.
+    pub fn expected(&self) -> Option<&'static str> {
+        match self {
+            Self::UnexpectedEnd => Some("the rest of the class's details, ending with its CRN"),
+            Self::CourseNameLine(_) => Some(
+                "a course heading like \"Discrete Mathematics | Computer Science 1200U, \
+                Section 001 | Class Begin: 09/04/2024 | Class End: 12/03/2024\"",
+            ),
+            Self::MessageLine(_) => Some(
+                "a line like \"| Schedule Type: Lecture | Instructional Method: In-Person | ... |\"",
+            ),
+            Self::Date(..) => Some("a date in the institution profile's date format, eg. 09/04/2024"),
+            Self::Weekday(_) => Some("a weekday, eg. \"Monday\" or \"lundi\""),
+            Self::TimeLine(_) => Some(
+                "a line like \"11:10 AM - 12:30 PM Type: Class Location: ... Building: ... \
+                Room: ...\"",
+            ),
+            Self::Time(..) => Some("a time like \"11:10 AM\" or \"11:10\""),
+            Self::ShortSubject(_) => Some(
+                "a subject listed in the institution profile or the registration summary table",
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// A row of the registration summary table, keyed by CRN.
+struct SummaryRow {
+    short_subject: String,
+    credit_hours: Option<f32>,
+    /// Weeks of a shortened course, eg. "First 7 Weeks".
+    part_of_term: Option<String>,
+}
+
+/// Everything that was parsed from the pasted schedule data.
+#[derive(Debug, Clone, Default)]
+pub struct Schedule {
+    pub classes: Vec<Class>,
+    pub failures: Vec<ClassFailure>,
+    /// Problems that didn't stop a class from being parsed, but that the user should check.
+    pub warnings: Vec<String>,
+    /// The layout that the data was parsed with.
+    pub format: Option<FormatVersion>,
+    /// Term from the schedule heading, eg. "Fall 2024". The first one if there were several.
+    pub term: String,
+}
+
+impl Schedule {
+    /// The terms of the classes, in the order they first appear.
+    pub fn terms(&self) -> Vec<&str> {
+        let mut terms = Vec::new();
+        for class in &self.classes {
+            let term = self.class_term(class);
+            if !terms.contains(&term) {
+                terms.push(term);
+            }
+        }
+        terms
+    }
+
+    /// Adds `events` as classes, so they're written to the calendars along with the schedule's.
+    /// Events without a title are skipped.
+    pub fn add_custom_events(&mut self, events: &[CustomEvent]) {
+        self.classes.extend(
+            events
+                .iter()
+                .filter(|event| !event.title.trim().is_empty())
+                .map(CustomEvent::to_class),
+        );
+    }
+
+    /// Adds `exams` from [`exams::parse_exams`], named after the classes with the same code.
+    pub fn add_exams(&mut self, exams: &[Class]) {
+        for exam in exams {
+            let mut exam = exam.clone();
+            if let Some(class) = self.classes.iter().find(|class| class.code == exam.code) {
+                exam.name = class.name.clone();
+                exam.term = class.term.clone();
+            }
+            self.classes.push(exam);
+        }
+    }
+
+    /// The term `class` is in, falling back to the schedule's for classes without one.
+    pub fn class_term<'a>(&'a self, class: &'a Class) -> &'a str {
+        if class.term.is_empty() {
+            &self.term
+        } else {
+            &class.term
+        }
+    }
+}
+
+/// A course block that couldn't be parsed and was left out of the calendars.
+#[derive(Debug, Clone)]
+pub struct ClassFailure {
+    pub heading: String,
+    pub error: ParseError,
+    /// Line of the pasted data that couldn't be parsed, starting from 1. `None` if the class
+    /// didn't come from pasted text.
+    pub line: Option<usize>,
+}
+
+/// Summary of a [`generate`] run, for displaying to the user.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub calendars: usize,
+    pub succeeded: Vec<String>,
+    pub failed: Vec<ClassFailure>,
+    pub warnings: Vec<String>,
+    /// Differences from the previous export, if [`GenerateOptions::update_previous`] was set.
+    pub changes: Vec<MeetingChange>,
+    pub statistics: Statistics,
+    /// The files that were written, by name.
+    pub files: Vec<PathBuf>,
+    pub write_failures: Vec<WriteFailure>,
+    /// Whether it was stopped with [`Progress::cancel`] before writing every file.
+    pub cancelled: bool,
+}
+
+impl Report {
+    /// Tries writing the files that failed again, and moves the ones that worked to
+    /// [`files`](Self::files).
+    pub fn retry_writes(&mut self) {
+        let failures = std::mem::take(&mut self.write_failures);
+        for mut failure in failures {
+            match fs::write(&failure.path, &failure.contents) {
+                Ok(()) => self.files.push(failure.path),
+                Err(e) => {
+                    failure.error = write_error(&e);
+                    self.write_failures.push(failure);
+                }
+            }
+        }
+        self.files.sort();
+    }
+}
+
+/// A file that couldn't be written, with its contents so it can be retried.
+#[derive(Debug, Clone)]
+pub struct WriteFailure {
+    pub path: PathBuf,
+    pub error: String,
+    contents: String,
+}
+
+/// Describes why a file couldn't be written, with a hint for the common causes.
+fn write_error(e: &std::io::Error) -> String {
+    match e.kind() {
+        std::io::ErrorKind::PermissionDenied | std::io::ErrorKind::ReadOnlyFilesystem => {
+            format!("{e} (check that the folder isn't read-only)")
+        }
+        std::io::ErrorKind::NotFound => format!("{e} (the folder may have been moved or deleted)"),
+        _ => e.to_string(),
+    }
+}
+
+/// Options for how the calendars are written.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenerateOptions {
+    /// Compare against the calendars already in the output folder, and only bump the `SEQUENCE`
+    /// of meetings that changed.
+    pub update_previous: bool,
+    /// Write meetings of a class that only differ by weekday as one event with `BYDAY`, eg. a
+    /// single event for a Monday/Wednesday/Friday lecture.
+    pub merge_weekdays: bool,
+    pub grouping: Grouping,
+    /// Template for event titles, eg. `{code} {type} ({room})`. See [`template::PLACEHOLDERS`].
+    /// If empty, the course name is used, along with the schedule type if the calendar has
+    /// multiple types.
+    pub summary_template: String,
+    /// Template for event descriptions, using the same placeholders as
+    /// [`summary_template`](Self::summary_template).
+    pub description_template: String,
+    /// Minutes before the start of each event to remind at, by schedule type. Types that aren't
+    /// in the map don't get a reminder.
+    pub reminders: BTreeMap<String, u32>,
+    /// Color of each schedule type's events, as RGB.
+    pub colors: BTreeMap<String, [u8; 3]>,
+    pub async_events: AsyncEvents,
+    /// Add all-day events for the first and last day of each course.
+    pub milestones: bool,
+    /// Days that follow another weekday's schedule, eg. a Tuesday with Monday classes.
+    pub swap_days: Vec<SwapDay>,
+    /// Also bundle the calendars and exports into [`archive::ZIP_FILE_NAME`].
+    pub zip: bool,
+    /// Write eg. `Fall 2024 - Lecture (2).ics` instead of replacing a calendar that's already in
+    /// the output folder.
+    pub rename_existing: bool,
+    /// Leave out sections worth 0 credit hours, eg. co-op preparation seminars. Needs the
+    /// registration summary table for the hours.
+    pub skip_zero_credit: bool,
+    /// Add a Google Maps link for the building to each event's description.
+    pub maps_links: bool,
+    /// The part of the day that counts for [`free_time`](crate::free_time), eg. 08:00 to 22:00.
+    pub free_time_start: NaiveTime,
+    pub free_time_end: NaiveTime,
+    /// Add the instructors whose emails are known as attendees of their events, with the primary
+    /// one as the organizer. Nothing is added unless the primary instructor's email is known.
+    pub instructor_attendees: bool,
+    /// Emails of instructors by their name in the schedule, eg. "Doe, John".
+    pub instructor_emails: BTreeMap<String, String>,
+    /// Full course titles by code, from [`catalog::load`]. Used in place of the schedule's names,
+    /// which are cut off when they're long.
+    pub course_titles: BTreeMap<String, String>,
+    /// Write a separate event for each occurrence instead of one recurring event, for calendar
+    /// apps that handle `RRULE` poorly. Excluded dates are left out.
+    pub expand_occurrences: bool,
+    /// Show classes as free instead of busy, eg. when sharing availability. All-day events are
+    /// always free.
+    pub show_as_free: bool,
+    /// Mark the events as private, so people the calendar is shared with only see the busy time.
+    pub private_events: bool,
+    /// Minutes to move the start and end of each class by, eg. 10 when classes actually start ten
+    /// minutes after the scheduled time.
+    pub start_offset: i64,
+    pub end_offset: i64,
+    /// Add a [`travel`] event before the first class of each day.
+    pub travel_before_first_class: bool,
+    /// Add a [`travel`] event before classes in a different building than the class before them.
+    pub travel_between_buildings: bool,
+    /// Length of the travel events.
+    pub travel_minutes: u32,
+    /// Assignment deadlines from [`deadlines::parse_deadlines`], added as all-day events to the
+    /// calendar of their course.
+    pub deadlines: Vec<Deadline>,
+}
+
+impl GenerateOptions {
+    /// Whether `class` is left out of the calendars and exports.
+    pub fn skips(&self, class: &Class) -> bool {
+        self.skip_zero_credit && class.credit_hours == Some(0.0)
+    }
+
+    /// The start and end time of events for `date_range`, with
+    /// [`start_offset`](Self::start_offset) and [`end_offset`](Self::end_offset) applied. The end
+    /// is never before the start.
+    pub fn event_times(&self, date_range: &DateRange) -> (NaiveTime, NaiveTime) {
+        let start = date_range.start_time + Duration::minutes(self.start_offset);
+        let end = date_range.end_time + Duration::minutes(self.end_offset);
+        (start, end.max(start))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwapDay {
+    pub date: NaiveDate,
+    /// The weekday whose classes happen on `date` instead of its own.
+    pub weekday: Weekday,
+}
+
+/// A weekly event that isn't in the schedule, eg. office hours, a club, or a work shift.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomEvent {
+    pub title: String,
+    pub weekday: Weekday,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// Room or place, eg. "UA 2240". Building codes are expanded the same way as for classes.
+    pub location: String,
+}
+
+/// Schedule type of [`CustomEvent`]s, so they get their own calendar.
+pub const CUSTOM_EVENT_TYPE: &str = "Personal";
+
+impl CustomEvent {
+    /// The event as a class without a code or CRN, so it's written like the schedule's classes.
+    pub fn to_class(&self) -> Class {
+        let location = self.location.trim();
+        Class {
+            name: self.title.trim().to_owned(),
+            code: String::new(),
+            date_ranges: vec![DateRange {
+                start_date: self.start_date,
+                end_date: self.end_date,
+                start_time: self.start_time,
+                end_time: self.end_time,
+                weekday: self.weekday,
+                location: None,
+                building: None,
+                room: (!location.is_empty()).then(|| location.to_owned()),
+                biweekly: false,
+                kind: DateRangeKind::Weekly,
+            }],
+            instructor: None,
+            crn: String::new(),
+            class_type: CUSTOM_EVENT_TYPE.to_owned(),
+            credit_hours: None,
+            asynchronous: Vec::new(),
+            term: String::new(),
+            meeting_url: None,
+        }
+    }
+}
+
+/// Whether to add all-day events for sections without scheduled meetings, so online courses
+/// still show up in the calendar.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AsyncEvents {
+    #[default]
+    Off,
+    /// On the first day of the section.
+    FirstDay,
+    /// Every week on the weekday the section starts on.
+    Weekly,
+}
+
+impl AsyncEvents {
+    pub const ALL: [AsyncEvents; 3] =
+        [AsyncEvents::Off, AsyncEvents::FirstDay, AsyncEvents::Weekly];
+}
+
+/// Which way to read numeric dates like 04/09/2024, since browsers order them by their language.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DateOrder {
+    /// Whichever order gives valid date ranges, preferring the profile's.
+    #[default]
+    Auto,
+    MonthFirst,
+    DayFirst,
+}
+
+impl DateOrder {
+    pub const ALL: [DateOrder; 3] = [DateOrder::Auto, DateOrder::MonthFirst, DateOrder::DayFirst];
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        Self {
+            update_previous: false,
+            merge_weekdays: false,
+            grouping: Grouping::default(),
+            summary_template: String::new(),
+            description_template: DEFAULT_DESCRIPTION_TEMPLATE.to_owned(),
+            reminders: BTreeMap::new(),
+            colors: BTreeMap::new(),
+            async_events: AsyncEvents::default(),
+            milestones: false,
+            swap_days: Vec::new(),
+            zip: false,
+            rename_existing: false,
+            skip_zero_credit: false,
+            maps_links: false,
+            free_time_start: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+            free_time_end: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            instructor_attendees: false,
+            instructor_emails: BTreeMap::new(),
+            course_titles: BTreeMap::new(),
+            expand_occurrences: false,
+            show_as_free: false,
+            private_events: false,
+            start_offset: 0,
+            end_offset: 0,
+            travel_before_first_class: false,
+            travel_between_buildings: false,
+            travel_minutes: 10,
+            deadlines: Vec::new(),
+        }
+    }
+}
+
+pub const DEFAULT_DESCRIPTION_TEMPLATE: &str =
+    "Campus: {campus}\nCode: {code}\nCRN: {crn}\nInstructor: {instructor}";
+
+/// How events are split into calendar files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Grouping {
+    /// One calendar per schedule type, eg. `Fall 2024 - Lecture.ics` and
+    /// `Fall 2024 - Tutorial.ics`.
+    #[default]
+    ClassType,
+    /// One calendar per course, eg. `Fall 2024 - CSCI 1200U.ics` with its lectures, labs, and
+    /// tutorials.
+    Course,
+    /// A single `Fall 2024 - schedule.ics` with every event.
+    Combined,
 }
 
-#[derive(Debug)]
-struct Class {
-    name: String,
-    code: String,
-    date_ranges: Vec<DateRange>,
-    instructor: String,
-    crn: String,
-    class_type: String,
+impl Grouping {
+    pub const ALL: [Grouping; 3] = [Grouping::ClassType, Grouping::Course, Grouping::Combined];
+
+    /// File name of the calendar that `class` goes in, without the extension, eg.
+    /// "Fall 2024 - Lecture". The term keeps calendars from different terms apart.
+    pub fn calendar_name(self, class: &Class, term: &str) -> String {
+        let name = match self {
+            // exams always get their own calendar
+            _ if class.is_exam() => EXAMS_CALENDAR,
+            Grouping::ClassType => &class.class_type,
+            Grouping::Course => &class.code,
+            Grouping::Combined => "schedule",
+        };
+        let name = if term.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{term} - {name}")
+        };
+        name.replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "_")
+    }
+
+    /// Display name of the calendar that `class` goes in, eg. "Fall 2024 – Lecture".
+    pub fn calendar_title(self, class: &Class, term: &str) -> String {
+        let title = match self {
+            _ if class.is_exam() => EXAMS_CALENDAR,
+            Grouping::ClassType => &class.class_type,
+            Grouping::Course => &class.code,
+            Grouping::Combined => "Schedule",
+        };
+        if term.is_empty() {
+            title.to_owned()
+        } else {
+            format!("{term} – {title}")
+        }
+    }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Browser {
+    /// Chrome, Edge, Opera, etc.
     Chromium,
     Firefox,
+    /// Text recognized from a screenshot, which loses the day abbreviations and blank lines.
+    Screenshot,
+    /// Any browser on the mobile version of the site.
+    Mobile,
 }
 
-fn parse_data(raw_data: &str) -> Vec<Class> {
-    let course_summary_re = Regex::new(r"^.+?\t([A-Z]{4}) \d{4}U, .+?\t(\d{5})").unwrap();
-    let course_name_re = Regex::new(r"^(.+?) \| (.+?) (\d+U)").unwrap();
-    let date_re = Regex::new(r"^([\d/]+) -- ([\d/]+)(?:\s+(\w+))?").unwrap();
-    let time_re = Regex::new(
-        r"^\s+(\d+:\d+ \w+) - (\d+:\d+ \w+).+?Location: (?P<location>.+?) Building: (?P<building>.+?) Room: (?P<room>.+)",
-    )
-    .unwrap();
-    let message_re = Regex::new(r"\| Schedule Type: (?P<class_type>.+?) \|").unwrap();
-    let crn_re = Regex::new(r"^CRN: (\d{5})").unwrap();
-
-    // wHY ARE THEY USING NO-BREAK SPACES NOW
-    let mut lines = raw_data.lines().map(|l| l.replace('\u{a0}', " "));
-
-    let mut crn_short_subjects: HashMap<String, String> = HashMap::new();
-    let browser = 'browser: {
-        for line in lines.by_ref() {
-            // in case long subject names keep changing
-            // also try to get the short code from the summary at the start of the data
-            if let Some(caps) = course_summary_re.captures(&line) {
-                let (_, [short_subject, crn]) = caps.extract();
-                crn_short_subjects.insert(crn.to_owned(), short_subject.to_owned());
+/// A known layout of the copied Schedule Details text. The portal keeps moving things around and
+/// each browser copies whitespace differently, so [`Parser::parse_data`] tries all of these and
+/// keeps whichever one parses most cleanly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatVersion {
+    pub name: &'static str,
+    browser: Browser,
+    /// Whether the weekday is at the end of the date line, rather than on its own line after it.
+    weekday_on_date_line: bool,
+    /// Number of lines between the weekday and the time line (the day abbreviations, plus any
+    /// blank lines).
+    day_abbreviation_lines: usize,
+    /// Whether the grey "Schedule Type" box comes right after the course name, rather than after
+    /// the CRN.
+    message_first: bool,
+    /// Whether the time line comes before the weekday, rather than after the day abbreviations.
+    time_before_weekday: bool,
+    /// Whether the CRN line comes before the instructor line.
+    crn_before_instructor: bool,
+}
+
+/// Every known layout, newest first.
+pub const FORMAT_VERSIONS: &[FormatVersion] = &[
+    FormatVersion {
+        name: "Chromium/Edge",
+        browser: Browser::Chromium,
+        weekday_on_date_line: true,
+        day_abbreviation_lines: 7,
+        message_first: true,
+        time_before_weekday: false,
+        crn_before_instructor: false,
+    },
+    FormatVersion {
+        name: "Firefox",
+        browser: Browser::Firefox,
+        weekday_on_date_line: false,
+        day_abbreviation_lines: 9,
+        message_first: true,
+        time_before_weekday: false,
+        crn_before_instructor: false,
+    },
+    FormatVersion {
+        name: "Screenshot",
+        browser: Browser::Screenshot,
+        weekday_on_date_line: true,
+        day_abbreviation_lines: 0,
+        message_first: true,
+        time_before_weekday: false,
+        crn_before_instructor: false,
+    },
+    // the mobile site stacks everything vertically and doesn't have the day abbreviations
+    FormatVersion {
+        name: "Mobile",
+        browser: Browser::Mobile,
+        weekday_on_date_line: false,
+        day_abbreviation_lines: 0,
+        message_first: true,
+        time_before_weekday: true,
+        crn_before_instructor: true,
+    },
+    // before the details box was moved to the top
+    FormatVersion {
+        name: "Chromium/Edge (details box at bottom)",
+        browser: Browser::Chromium,
+        weekday_on_date_line: true,
+        day_abbreviation_lines: 7,
+        message_first: false,
+        time_before_weekday: false,
+        crn_before_instructor: false,
+    },
+    FormatVersion {
+        name: "Firefox (details box at bottom)",
+        browser: Browser::Firefox,
+        weekday_on_date_line: false,
+        day_abbreviation_lines: 9,
+        message_first: false,
+        time_before_weekday: false,
+        crn_before_instructor: false,
+    },
+];
+
+/// Parser for schedule data copied from a Banner Schedule Details page.
+#[derive(Clone)]
+pub struct Parser {
+    course_summary_re: Regex,
+    course_name_re: Regex,
+    date_re: Regex,
+    time_re: Regex,
+    message_re: Regex,
+    crn_re: Regex,
+    instructor_re: Regex,
+    markers: Markers,
+    date_format: String,
+    date_order: DateOrder,
+    time_format: String,
+    subjects: BTreeMap<String, String>,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser {
+    /// Creates a parser for MyOntarioTech.
+    pub fn new() -> Self {
+        Self::from_profile(&InstitutionProfile::default())
+            .expect("default profile regexes should be valid")
+    }
+
+    /// Creates a parser for the given institution, failing if any of its regexes are invalid.
+    pub fn from_profile(profile: &InstitutionProfile) -> Result<Self, regex::Error> {
+        let regexes = &profile.regexes;
+        Ok(Self {
+            course_summary_re: Regex::new(&regexes.course_summary)?,
+            course_name_re: Regex::new(&regexes.course_name)?,
+            date_re: Regex::new(&regexes.date)?,
+            time_re: Regex::new(&regexes.time)?,
+            message_re: Regex::new(&regexes.message)?,
+            crn_re: Regex::new(&regexes.crn)?,
+            instructor_re: Regex::new(&regexes.instructor)?,
+            markers: profile.markers.clone(),
+            date_format: profile.date_format.clone(),
+            date_order: DateOrder::default(),
+            time_format: profile.time_format.clone(),
+            subjects: profile.subjects.clone(),
+        })
+    }
+
+    /// Adds extra subject name → code mappings on top of the profile's, replacing any existing
+    /// mappings for the same subjects.
+    pub fn with_subjects(mut self, subjects: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.subjects.extend(subjects);
+        self
+    }
+
+    /// Reads numeric dates in the given order instead of detecting it.
+    pub fn with_date_order(mut self, date_order: DateOrder) -> Self {
+        self.date_order = date_order;
+        self
+    }
+
+    /// Parses the copied page text. Classes that fail to parse are skipped and recorded in
+    /// [`Schedule::failures`]; an error is only returned if the schedule itself can't be found.
+    pub fn parse_data(&self, raw_data: &str) -> Result<Schedule, ParseError> {
+        self.parse_data_with_summary(raw_data, "")
+    }
+
+    /// Like [`parse_data`](Self::parse_data), but also uses a separately copied registration
+    /// summary table (Title/Details/Hours/CRN) to look up subject codes and credit hours.
+    pub fn parse_data_with_summary(
+        &self,
+        raw_data: &str,
+        summary_data: &str,
+    ) -> Result<Schedule, ParseError> {
+        // wHY ARE THEY USING NO-BREAK SPACES NOW
+        let lines = raw_data
+            .lines()
+            .map(|l| l.replace('\u{a0}', " "))
+            .collect::<Vec<_>>();
+
+        // the mobile layout doesn't have the tab bar with the Schedule line, so if it's missing,
+        // just look for the start of the schedule from the top
+        let (browser, marker_index) = lines
+            .iter()
+            .enumerate()
+            .find_map(|(i, line)| {
+                if *line == self.markers.chromium_schedule {
+                    Some((Browser::Chromium, i))
+                } else if *line == self.markers.firefox_schedule {
+                    Some((Browser::Firefox, i))
+                } else {
+                    None
+                }
+            })
+            .unwrap_or((Browser::Mobile, 0));
+
+        // skip unneeded prelude
+        let start_index = lines[marker_index..]
+            .iter()
+            .position(|line| line.starts_with(&self.markers.schedule_start))
+            .ok_or_else(|| {
+                if self.is_registration_page(&lines) {
+                    ParseError::RegistrationPage
+                } else {
+                    ParseError::MissingScheduleStart
+                }
+            })?
+            + marker_index;
+
+        // in case long subject names keep changing
+        // also try to get the short code from the summary at the start of the data
+        let summary_rows = lines[..start_index]
+            .iter()
+            .map(|l| l.as_str())
+            .chain(summary_data.lines())
+            .filter_map(|line| self.parse_summary_row(&line.replace('\u{a0}', " ")))
+            .collect::<HashMap<_, _>>();
+
+        // the data can have the schedules of several terms one after another, each with its own
+        // heading
+        let mut headings = lines[start_index..]
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.starts_with(&self.markers.schedule_start))
+            .map(|(i, _)| i + start_index)
+            .collect::<Vec<_>>();
+        headings.push(lines.len());
+        let sections = headings
+            .windows(2)
+            .map(|heading| {
+                let term = term::normalize(&lines[heading[0]][self.markers.schedule_start.len()..]);
+                (term, heading[0] + 1, &lines[heading[0] + 1..heading[1]])
+            })
+            .collect::<Vec<_>>();
+
+        // browsers in other languages order the day and month differently
+        let mut date_warnings = Vec::new();
+        let parser = Self {
+            date_format: self.resolve_date_format(&lines[start_index..], &mut date_warnings),
+            ..self.clone()
+        };
+
+        // try the formats for the detected browser first, but fall back to the others in case
+        // the marker lines changed too
+        let mut formats = FORMAT_VERSIONS.iter().collect::<Vec<_>>();
+        formats.sort_by_key(|format| format.browser != browser);
+
+        let mut best: Option<Schedule> = None;
+        for format in formats {
+            let mut schedule = Schedule {
+                format: Some(*format),
+                ..Default::default()
+            };
+            for (term, start, body) in &sections {
+                let mut section = parser.parse_body(body, format, &summary_rows);
+                locate_failures(&mut section.failures, body, *start);
+                if sections.len() > 1 {
+                    for class in &mut section.classes {
+                        class.term = term.clone();
+                    }
+                }
+                schedule.classes.append(&mut section.classes);
+                schedule.failures.append(&mut section.failures);
+                schedule.warnings.append(&mut section.warnings);
+            }
+            if schedule.failures.is_empty() && !schedule.classes.is_empty() {
+                best = Some(schedule);
+                break;
+            }
+            if best.as_ref().is_none_or(|best| {
+                schedule.classes.len() > best.classes.len()
+                    || schedule.classes.len() == best.classes.len()
+                        && schedule.failures.len() < best.failures.len()
+            }) {
+                best = Some(schedule);
             }
+        }
+
+        let mut schedule = best.unwrap_or_default();
+        schedule.term = sections[0].0.clone();
+        schedule.warnings.splice(0..0, date_warnings);
+        if schedule.classes.is_empty()
+            && !schedule.failures.is_empty()
+            && self.is_registration_page(&lines)
+        {
+            return Err(ParseError::RegistrationPage);
+        }
+
+        for failure in &schedule.failures {
+            warn!(
+                line = failure.line,
+                "Failed to parse class: {}: {}", failure.heading, failure.error
+            );
+        }
+        Ok(schedule)
+    }
+
+    /// The date format to read the schedule with. The profile's format is used as is unless the
+    /// order is forced, or its dates only make sense with the day and month swapped.
+    fn resolve_date_format(&self, lines: &[String], warnings: &mut Vec<String>) -> String {
+        let month_first = self.date_format.find("%m") < self.date_format.find("%d");
+        let swapped = self
+            .date_format
+            .replace("%m", "\0")
+            .replace("%d", "%m")
+            .replace('\0', "%d");
+        match self.date_order {
+            DateOrder::Auto => {}
+            DateOrder::MonthFirst if month_first => return self.date_format.clone(),
+            DateOrder::DayFirst if !month_first => return self.date_format.clone(),
+            _ => return swapped,
+        }
+        if swapped == self.date_format {
+            return swapped;
+        }
 
-            match &*line {
-                "Schedule" => break 'browser Browser::Chromium,
-                "    Schedule" => break 'browser Browser::Firefox,
-                _ => (),
+        let ranges = lines
+            .iter()
+            .filter_map(|line| self.date_re.captures(line))
+            .filter_map(|caps| Some((caps.get(1)?.as_str(), caps.get(2)?.as_str())))
+            .collect::<Vec<_>>();
+        let read = |format: &str| {
+            ranges
+                .iter()
+                .map(|(start, end)| {
+                    let start = NaiveDate::parse_from_str(start, format).ok()?;
+                    let end = NaiveDate::parse_from_str(end, format).ok()?;
+                    (start <= end && end - start <= Duration::days(366)).then_some((start, end))
+                })
+                .collect::<Option<Vec<_>>>()
+        };
+        match (read(&self.date_format), read(&swapped)) {
+            (None, Some(_)) => swapped,
+            (Some(dates), Some(swapped_dates)) if dates != swapped_dates => {
+                let (start, _) = ranges[dates
+                    .iter()
+                    .zip(&swapped_dates)
+                    .position(|(a, b)| a != b)
+                    .unwrap_or_default()];
+                warnings.push(format!(
+                    "Dates like {start} could have the month or the day first; read them {}. \
+                    Change the date order in Settings if the dates are wrong.",
+                    if month_first {
+                        "month first"
+                    } else {
+                        "day first"
+                    }
+                ));
+                self.date_format.clone()
             }
+            _ => self.date_format.clone(),
         }
-        panic!("Failed to find Schedule line to determine browser")
-    };
+    }
+
+    /// Whether `lines` were copied from the Registration Information page instead of Schedule
+    /// Details. The summary table is on both, so only the page's own markers are checked.
+    fn is_registration_page(&self, lines: &[String]) -> bool {
+        let is_schedule_details = lines.iter().any(|line| {
+            *line == self.markers.chromium_schedule
+                || *line == self.markers.firefox_schedule
+                || line.starts_with(&self.markers.schedule_start)
+        });
+        !is_schedule_details
+            && lines
+                .iter()
+                .any(|line| line.starts_with(&self.markers.registration_page))
+    }
+
+    fn parse_body(
+        &self,
+        lines: &[String],
+        format: &FormatVersion,
+        summary_rows: &HashMap<String, SummaryRow>,
+    ) -> Schedule {
+        let mut lines = lines.iter().cloned().peekable();
+        let mut schedule = Schedule {
+            format: Some(*format),
+            ..Default::default()
+        };
 
-    // skip unneeded prelude
-    while !lines
-        .next()
-        .expect("Failed to find start of schedule")
-        .starts_with("Class Schedule for ")
-    {}
+        while let Some(course_name_line) = lines.next() {
+            // handle extra newlines at the end
+            if course_name_line.is_empty() {
+                break;
+            }
 
-    let mut output = Vec::new();
+            let mut warnings = Vec::new();
+            match self.parse_class(
+                &course_name_line,
+                &mut lines,
+                format,
+                summary_rows,
+                &mut warnings,
+            ) {
+                Ok(class) => {
+                    schedule.classes.push(class);
+                    schedule.warnings.append(&mut warnings);
+                }
+                Err(error) => {
+                    schedule.failures.push(ClassFailure {
+                        heading: course_name_line,
+                        error,
+                        line: None,
+                    });
 
-    while let Some(course_name_line) = lines.next() {
-        // handle extra newlines at the end
-        if course_name_line.is_empty() {
-            break;
+                    // skip the rest of the broken block so the next class can still be parsed
+                    while lines
+                        .next_if(|l| !l.is_empty() && !self.course_name_re.is_match(l))
+                        .is_some()
+                    {}
+                }
+            }
         }
 
+        schedule
+    }
+
+    fn parse_class(
+        &self,
+        course_name_line: &str,
+        lines: &mut Peekable<impl Iterator<Item = String>>,
+        format: &FormatVersion,
+        summary_rows: &HashMap<String, SummaryRow>,
+        warnings: &mut Vec<String>,
+    ) -> Result<Class, ParseError> {
         // parse course name and code
-        let course_name_caps = course_name_re
-            .captures(&course_name_line)
-            .unwrap_or_else(|| panic!("Failed to match course name line: {}", course_name_line));
-        let name = course_name_caps.get(1).unwrap().as_str().to_string();
-        let subject = course_name_caps.get(2).unwrap().as_str();
-        let code_number = course_name_caps.get(3).unwrap().as_str();
+        let course_name_caps = self
+            .course_name_re
+            .captures(course_name_line)
+            .ok_or_else(|| ParseError::CourseNameLine(course_name_line.to_owned()))?;
+        let group = |i| capture(&course_name_caps, "course name", i, course_name_line);
+        let name = group(1)?.to_string();
+        let subject = group(2)?;
+        let code_number = group(3)?;
 
         // skip "Registered" line
         lines.next();
 
         // why did they CHANGE THE FORMAT
         // JUST TO MOVE THIS BOX TO THE TOP
-        let message_line = lines.next().unwrap();
-        let message_caps = message_re
-            .captures(&message_line)
-            .unwrap_or_else(|| panic!("Failed to parse message line: {}", message_line));
+        let mut class_type = None;
+        if format.message_first {
+            class_type = Some(self.parse_message(&next_line(lines)?)?);
+        }
 
         // parse date ranges
         let mut date_ranges = Vec::new();
-        let instructor = loop {
-            let date_line = lines.next().unwrap();
-            let date_caps = match date_re.captures(&date_line) {
+        let mut asynchronous = Vec::new();
+        let after_dates_line = loop {
+            let date_line = next_line(lines)?;
+            let date_caps = match self.date_re.captures(&date_line) {
                 Some(caps) => caps,
                 None => break date_line,
             };
 
-            let start_date = date_caps.get(1).unwrap().as_str();
-            let start_date = NaiveDate::parse_from_str(start_date, "%m/%d/%Y")
-                .unwrap_or_else(|e| panic!("Failed to parse date: {}\n{}", start_date, e));
+            let start_date = self.parse_date(capture(&date_caps, "date", 1, &date_line)?)?;
+            let end_date = self.parse_date(capture(&date_caps, "date", 2, &date_line)?)?;
 
-            let end_date = date_caps.get(2).unwrap().as_str();
-            let end_date = NaiveDate::parse_from_str(end_date, "%m/%d/%Y")
-                .unwrap_or_else(|e| panic!("Failed to parse date: {}\n{}", end_date, e));
-
-            let weekday = match browser {
-                Browser::Firefox => lines.next().unwrap(),
-                Browser::Chromium => date_caps.get(3).unwrap().as_str().to_string(),
+            let (weekday, time_line) = if format.time_before_weekday {
+                let time_line = next_line(lines)?;
+                (next_line(lines)?, Some(time_line))
+            } else if format.weekday_on_date_line {
+                let weekday = date_caps
+                    .get(3)
+                    .ok_or_else(|| ParseError::Weekday(date_line.clone()))?
+                    .as_str()
+                    .to_string();
+                (weekday, None)
+            } else {
+                (next_line(lines)?, None)
             };
             if weekday == "None" {
-                lines.nth(match browser {
-                    Browser::Chromium => 7,
-                    Browser::Firefox => 9,
-                });
+                asynchronous.push((start_date, end_date));
+                if time_line.is_none() {
+                    // skip day abbreviations and time line
+                    skip_lines(lines, format.day_abbreviation_lines + 1);
+                }
                 continue;
             }
-            let weekday = weekday
-                .parse::<Weekday>()
-                .unwrap_or_else(|_| panic!("Failed to parse weekday: {}", weekday));
-
-            // skip day abbreviations
-            lines.nth(match browser {
-                Browser::Chromium => 6,
-                Browser::Firefox => 8,
-            });
+            let weekday =
+                parse_weekday(&weekday).ok_or_else(|| ParseError::Weekday(weekday.clone()))?;
 
-            let time_line = lines.next().unwrap();
-            let time_caps = time_re
+            let time_line = match time_line {
+                Some(time_line) => time_line,
+                None => {
+                    // skip day abbreviations
+                    skip_lines(lines, format.day_abbreviation_lines);
+                    next_line(lines)?
+                }
+            };
+            let time_caps = self
+                .time_re
                 .captures(&time_line)
-                .unwrap_or_else(|| panic!("Failed to parse time line: {}", time_line));
+                .ok_or_else(|| ParseError::TimeLine(time_line.clone()))?;
 
-            let start_time = time_caps.get(1).unwrap().as_str();
-            let start_time = NaiveTime::parse_from_str(start_time, "%I:%M %p")
-                .unwrap_or_else(|e| panic!("Failed to parse time: {}\n{}", start_time, e));
+            let start_time = self.parse_time(capture(&time_caps, "time", 1, &time_line)?)?;
+            let end_time = self.parse_time(capture(&time_caps, "time", 2, &time_line)?)?;
 
-            let end_time = time_caps.get(2).unwrap().as_str();
-            let end_time = NaiveTime::parse_from_str(end_time, "%I:%M %p")
-                .unwrap_or_else(|e| panic!("Failed to parse time: {}\n{}", end_time, e));
-
-            let location = time_caps.name("location").unwrap().as_str().to_string();
-            let building = time_caps.name("building").unwrap().as_str().to_string();
-            let room = time_caps.name("room").unwrap().as_str().to_string();
+            let assigned = |name| {
+                time_caps
+                    .name(name)
+                    .map(|m| m.as_str().trim())
+                    .filter(|value| !is_tba(value))
+                    .map(str::to_owned)
+            };
+            let location = assigned("location");
+            let building = assigned("building");
+            let room = assigned("room");
 
             date_ranges.push(DateRange {
                 start_date,
@@ -213,195 +1169,2478 @@ fn parse_data(raw_data: &str) -> Vec<Class> {
                 location,
                 building,
                 room,
+                biweekly: false,
+                kind: DateRangeKind::Weekly,
             });
         };
 
-        let crn_line = lines.next().unwrap();
+        // the instructor line is left out until one's been assigned
+        let (instructor, crn_line) = if format.crn_before_instructor {
+            let instructor = lines.next_if(|line| self.instructor_re.is_match(line));
+            (instructor, after_dates_line)
+        } else if self.instructor_re.is_match(&after_dates_line) {
+            (Some(after_dates_line), next_line(lines)?)
+        } else {
+            (None, after_dates_line)
+        };
+        let instructor = instructor.filter(|line| {
+            let name = self.instructor_re.replace(line, "");
+            !is_tba(name.trim())
+        });
 
-        let short_subject = SUBJECTS
-            .get(subject)
-            .map(|s| (*s).to_owned())
-            .or_else(|| {
-                crn_re
-                    .captures(&crn_line)
-                    .and_then(|caps| caps.get(1))
-                    .and_then(|crn| crn_short_subjects.get(crn.as_str()))
-                    .cloned()
-            })
-            .unwrap_or_else(|| panic!("Failed to get short subject code for subject: {}", subject));
+        // prefer the summary table, since it has the actual code instead of relying on the
+        // subject name being in the profile
+        let summary_row = self
+            .crn_re
+            .captures(&crn_line)
+            .and_then(|caps| caps.get(1))
+            .and_then(|crn| summary_rows.get(crn.as_str()));
+        let short_subject = match summary_row
+            .map(|row| row.short_subject.clone())
+            .or_else(|| self.subjects.get(subject).cloned())
+        {
+            Some(short_subject) => short_subject,
+            None => {
+                let guess = guess_short_subject(subject)
+                    .ok_or_else(|| ParseError::ShortSubject(subject.to_owned()))?;
+                warnings.push(format!(
+                    "Guessed subject code {guess} for unknown subject \"{subject}\" ({name}). \
+                    Paste the registration summary table to get the real code."
+                ));
+                guess
+            }
+        };
         let code = format!("{short_subject} {code_number}");
 
-        output.push(Class {
+        let class_type = match class_type {
+            Some(class_type) => class_type,
+            None => self.parse_message(&next_line(lines)?)?,
+        };
+
+        if let Some(part_of_term) = summary_row.and_then(|row| row.part_of_term.as_deref()) {
+            warnings.extend(check_part_of_term(&name, part_of_term, &date_ranges));
+        }
+
+        Ok(Class {
             name,
             code,
-            date_ranges,
+            date_ranges: merge_biweekly(date_ranges),
             instructor,
             crn: crn_line,
-            class_type: message_caps
-                .name("class_type")
-                .unwrap()
-                .as_str()
-                .to_string(),
+            class_type,
+            credit_hours: summary_row.and_then(|row| row.credit_hours),
+            asynchronous,
+            term: String::new(),
+            meeting_url: None,
         })
     }
 
-    output
-}
+    fn parse_summary_row(&self, line: &str) -> Option<(String, SummaryRow)> {
+        let caps = self.course_summary_re.captures(line)?;
+        let short_subject = caps.name("subject").or_else(|| caps.get(1))?;
+        let crn = caps.name("crn").or_else(|| caps.get(2))?;
+        Some((
+            crn.as_str().to_owned(),
+            SummaryRow {
+                short_subject: short_subject.as_str().to_owned(),
+                credit_hours: caps.name("hours").and_then(|h| h.as_str().parse().ok()),
+                part_of_term: caps.name("part_of_term").map(|p| p.as_str().to_owned()),
+            },
+        ))
+    }
+
+    fn parse_message(&self, message_line: &str) -> Result<String, ParseError> {
+        let message_caps = self
+            .message_re
+            .captures(message_line)
+            .ok_or_else(|| ParseError::MessageLine(message_line.to_owned()))?;
+        // a customized regex might not name the group, so then it's the first one
+        let group = self
+            .message_re
+            .capture_names()
+            .position(|name| name == Some("class_type"))
+            .unwrap_or(1);
+        Ok(capture(&message_caps, "message", group, message_line)?.to_owned())
+    }
+
+    fn parse_date(&self, date: &str) -> Result<NaiveDate, ParseError> {
+        NaiveDate::parse_from_str(date, &self.date_format)
+            .map_err(|e| ParseError::Date(date.to_owned(), e))
+    }
 
-fn tzid(datetime: NaiveDateTime) -> String {
-    format!("TZID=America/Toronto:{}", datetime.format("%Y%m%dT%H%M%S"))
+    fn parse_time(&self, time: &str) -> Result<NaiveTime, ParseError> {
+        // browsers using a 24-hour clock leave out AM/PM
+        NaiveTime::parse_from_str(time, &self.time_format)
+            .or_else(|e| NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| e))
+            .map_err(|e| ParseError::Time(time.to_owned(), e))
+    }
 }
 
-fn fold_calendar(calendar: &mut String) {
-    let mut to_insert = Vec::new();
-    let mut line_length = 0;
-    for (index, c) in calendar.chars().enumerate() {
-        if c == '\n' {
-            line_length = 0;
-        } else {
-            line_length += 1;
-            if line_length >= 74 {
-                to_insert.push(index);
-                line_length = 0;
-            }
-        }
+/// Parses an English or French weekday name, as shown by browsers in either language.
+fn parse_weekday(weekday: &str) -> Option<Weekday> {
+    if let Ok(weekday) = weekday.parse() {
+        return Some(weekday);
     }
-    for index in to_insert.iter().rev() {
-        calendar.insert_str(*index, "\n ");
+    match weekday.to_lowercase().as_str() {
+        "lundi" => Some(Weekday::Mon),
+        "mardi" => Some(Weekday::Tue),
+        "mercredi" => Some(Weekday::Wed),
+        "jeudi" => Some(Weekday::Thu),
+        "vendredi" => Some(Weekday::Fri),
+        "samedi" => Some(Weekday::Sat),
+        "dimanche" => Some(Weekday::Sun),
+        _ => None,
     }
 }
 
-pub fn generate(output_folder: impl AsRef<Path>, data: &str, exdate: HashSet<NaiveDate>) -> usize {
-    let data = parse_data(data);
+/// Whether a location or instructor is only a placeholder for one that hasn't been assigned.
+pub(crate) fn is_tba(value: &str) -> bool {
+    value.is_empty()
+        || ["TBA", "TBD", "To Be Announced", "To Be Determined"]
+            .iter()
+            .any(|tba| value.eq_ignore_ascii_case(tba))
+}
 
-    println!("Data: {:#?}\nExcluded dates: {:?}", data, exdate);
+/// Sets the line numbers of the failures in a section that starts at index `start` of the input,
+/// by looking for the text that failed to parse after each class's heading.
+fn locate_failures(failures: &mut [ClassFailure], body: &[String], start: usize) {
+    let mut from = 0;
+    for failure in failures {
+        let Some(heading) = body[from..]
+            .iter()
+            .position(|line| *line == failure.heading)
+            .map(|i| from + i)
+        else {
+            continue;
+        };
+        let index = match (&failure.error, failure.error.input()) {
+            (_, Some(input)) => body[heading..]
+                .iter()
+                .position(|line| line.contains(input))
+                .map_or(heading, |i| heading + i),
+            (ParseError::UnexpectedEnd, None) => body.len() - 1,
+            _ => heading,
+        };
+        failure.line = Some(start + index + 1);
+        from = heading + 1;
+    }
+}
 
-    let mut calendars = HashMap::new();
-    let mut summary: BTreeMap<String, BTreeMap<String, u32>> = BTreeMap::new();
+/// Group `group` of a match of the profile's `regex` on `line`, which a customized regex might
+/// not have, or only capture sometimes.
+fn capture<'h>(
+    caps: &Captures<'h>,
+    regex: &'static str,
+    group: usize,
+    line: &str,
+) -> Result<&'h str, ParseError> {
+    caps.get(group)
+        .map(|m| m.as_str())
+        .ok_or_else(|| ParseError::RegexGroup(regex, group, line.to_owned()))
+}
 
-    for class in &data {
-        let calendar = calendars
-            .entry(class.class_type.clone())
-            .or_insert_with(|| {
-                indoc! {"
-                    BEGIN:VCALENDAR
-                    VERSION:2.0
-                    PRODID:MYCAMPUS-CALENDAR-RS
-                    CALSCALE:GREGORIAN
-                    BEGIN:VTIMEZONE
-                    TZID:America/Toronto
-                    LAST-MODIFIED:20201011T015911Z
-                    TZURL:http://tzurl.org/zoneinfo-outlook/America/Toronto
-                    X-LIC-LOCATION:America/Toronto
-                    BEGIN:DAYLIGHT
-                    TZNAME:EDT
-                    TZOFFSETFROM:-0500
-                    TZOFFSETTO:-0400
-                    DTSTART:19700308T020000
-                    RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU
-                    END:DAYLIGHT
-                    BEGIN:STANDARD
-                    TZNAME:EST
-                    TZOFFSETFROM:-0400
-                    TZOFFSETTO:-0500
-                    DTSTART:19701101T020000
-                    RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU
-                    END:STANDARD
-                    END:VTIMEZONE
-                "}
-                .to_string()
-            });
-        let class_summary_count = summary
-            .entry(class.name.clone())
-            .or_default()
-            .entry(class.class_type.clone())
-            .or_default();
+fn next_line(lines: &mut impl Iterator<Item = String>) -> Result<String, ParseError> {
+    lines.next().ok_or(ParseError::UnexpectedEnd)
+}
 
-        for date_range in &class.date_ranges {
-            let first_date = date_range.start_date
-                + Duration::days(
-                    (date_range.weekday.num_days_from_sunday() as i32
-                        - date_range.start_date.weekday().num_days_from_sunday() as i32)
-                        .rem_euclid(7)
-                        .into(),
-                );
-            let exdate = format!(
-                "EXDATE;TZID=America/Toronto:{}",
-                exdate
-                    .iter()
-                    .map(|d| d
-                        .and_time(date_range.start_time)
-                        .format("%Y%m%dT%H%M%S")
-                        .to_string())
-                    .collect::<Vec<_>>()
-                    .join(",")
-            );
+/// Makes up a plausible short code for a subject, eg. "Computer Science" → "CSCI", by taking the
+/// initials of each word and filling the rest from the last word.
+fn guess_short_subject(subject: &str) -> Option<String> {
+    const IGNORED_WORDS: [&str; 4] = ["and", "of", "the", "for"];
 
-            write!(
-                calendar,
-                indoc! {r#"
-                    BEGIN:VEVENT
-                    DTSTAMP:{dtstamp}
-                    UID:{uid}
-                    DTSTART;{dtstart}
-                    DTEND;{dtend}
-                    RRULE:FREQ=WEEKLY;TZID=America/Toronto;UNTIL={until}
-                    {exdate}
-                    SUMMARY:{name}
-                    DESCRIPTION:Campus: {location}\nCode: {code}\n{crn}\n{instructor}
-                    LOCATION:{building} - {room}
-                    END:VEVENT
-                "#},
-                dtstamp = Utc::now().format("%Y%m%dT%H%M%SZ"),
-                uid = Uuid::new_v4(),
-                dtstart = tzid(first_date.and_time(date_range.start_time)),
-                dtend = tzid(first_date.and_time(date_range.end_time)),
-                until = date_range
-                    .end_date
-                    .and_hms_opt(23, 59, 59)
-                    .unwrap()
-                    .format("%Y%m%dT%H%M%S"),
-                exdate = exdate,
-                name = class.name,
-                code = class.code,
-                crn = class.crn,
-                instructor = class.instructor,
-                location = date_range.location,
-                building = date_range.building,
-                room = date_range.room,
-            )
-            .ok();
+    let words = subject
+        .split_whitespace()
+        .filter(|w| !IGNORED_WORDS.contains(&w.to_lowercase().as_str()))
+        .map(|w| w.chars().filter(|c| c.is_alphabetic()).collect::<String>())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>();
 
-            *class_summary_count += 1;
-        }
+    let mut code = words
+        .iter()
+        .take(4)
+        .filter_map(|w| w.chars().next())
+        .collect::<String>();
+    if let Some(last) = words.last() {
+        code.extend(last.chars().skip(1).take(4 - code.chars().count()));
     }
 
-    for (name, calendar) in &mut calendars {
-        calendar.push_str("END:VCALENDAR");
-        fold_calendar(calendar);
-        *calendar = calendar.replace('\n', "\r\n");
+    (!code.is_empty()).then(|| code.to_uppercase())
+}
 
-        let output_path = output_folder.as_ref().join(format!("{name}.ics"));
-        println!("Writing calendar: {}", output_path.display());
-        fs::write(output_path, calendar).ok();
+fn skip_lines(lines: &mut impl Iterator<Item = String>, n: usize) {
+    if n > 0 {
+        lines.nth(n - 1);
     }
+}
 
-    let max_name_len = summary.keys().map(|n| n.len()).max().unwrap();
-    for (name, class_summary) in summary {
-        println!(
-            "{:indent$}{} → {}",
-            "",
-            name,
-            class_summary
-                .iter()
-                .map(|(class_type, count)| format!("{}: {}", class_type, count))
-                .collect::<Vec<String>>()
-                .join(", "),
-            indent = max_name_len - name.len()
-        );
-    }
+/// Escapes a `TEXT` property value (RFC 5545 section 3.3.11).
+pub(crate) fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
 
-    let n = calendars.len();
-    println!("Wrote {n} .ics file(s).");
-    n
+fn hex_color([r, g, b]: [u8; 3]) -> String {
+    format!("#{r:02X}{g:02X}{b:02X}")
+}
+
+/// The `COLOR` property only accepts CSS color names (RFC 7986), so use the closest one.
+fn css_color_name([r, g, b]: [u8; 3]) -> &'static str {
+    const COLORS: [(&str, [u8; 3]); 20] = [
+        ("black", [0x00, 0x00, 0x00]),
+        ("gray", [0x80, 0x80, 0x80]),
+        ("silver", [0xc0, 0xc0, 0xc0]),
+        ("white", [0xff, 0xff, 0xff]),
+        ("maroon", [0x80, 0x00, 0x00]),
+        ("red", [0xff, 0x00, 0x00]),
+        ("orange", [0xff, 0xa5, 0x00]),
+        ("gold", [0xff, 0xd7, 0x00]),
+        ("yellow", [0xff, 0xff, 0x00]),
+        ("olive", [0x80, 0x80, 0x00]),
+        ("lime", [0x00, 0xff, 0x00]),
+        ("green", [0x00, 0x80, 0x00]),
+        ("teal", [0x00, 0x80, 0x80]),
+        ("aqua", [0x00, 0xff, 0xff]),
+        ("dodgerblue", [0x1e, 0x90, 0xff]),
+        ("blue", [0x00, 0x00, 0xff]),
+        ("navy", [0x00, 0x00, 0x80]),
+        ("purple", [0x80, 0x00, 0x80]),
+        ("fuchsia", [0xff, 0x00, 0xff]),
+        ("pink", [0xff, 0xc0, 0xcb]),
+    ];
+    COLORS
+        .iter()
+        .min_by_key(|(_, [cr, cg, cb])| {
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(r, *cr) + d(g, *cg) + d(b, *cb)
+        })
+        .map(|(name, _)| *name)
+        .unwrap()
+}
+
+/// `first`, then every week (or every other week) after it until `until`.
+fn weekly_dates(
+    first: NaiveDate,
+    until: NaiveDate,
+    biweekly: bool,
+) -> impl Iterator<Item = NaiveDate> {
+    let step = Duration::days(if biweekly { 14 } else { 7 });
+    std::iter::successors(Some(first), move |date| Some(*date + step))
+        .take_while(move |date| *date <= until)
+}
+
+fn first_on_or_after(date: NaiveDate, weekday: Weekday) -> NaiveDate {
+    date + Duration::days(
+        (weekday.num_days_from_sunday() as i32 - date.weekday().num_days_from_sunday() as i32)
+            .rem_euclid(7)
+            .into(),
+    )
+}
+
+/// Warns if the meetings of a shortened course, eg. "First 7 Weeks", run outside of those weeks,
+/// which usually means the dates on the schedule are the whole term's instead of the course's.
+fn check_part_of_term(name: &str, part_of_term: &str, date_ranges: &[DateRange]) -> Option<String> {
+    let (Some(start), Some(end)) = (
+        date_ranges.iter().map(|range| range.start_date).min(),
+        date_ranges.iter().map(|range| range.end_date).max(),
+    ) else {
+        return None;
+    };
+    let (which, weeks) = match part_of_term.split_whitespace().collect::<Vec<_>>()[..] {
+        [which, weeks, "Week" | "Weeks"] => (which, weeks.parse::<i64>().ok()?),
+        _ => return None,
+    };
+    let length = Duration::weeks(weeks);
+
+    match which {
+        "First" if end >= start + length => Some(format!(
+            "{name} is only in the {part_of_term} of the term, but its meetings go until {end}. \
+            Check the course's end date and add the rest as excluded dates if needed."
+        )),
+        "Second" | "Last" if start <= end - length => Some(format!(
+            "{name} is only in the {part_of_term} of the term, but its meetings start on {start}. \
+            Check the course's start date and add the weeks before it as excluded dates if needed."
+        )),
+        _ => None,
+    }
+}
+
+/// Banner lists a class that meets every other week as a separate one-week date range for each
+/// meeting, so merge runs of those that are two weeks apart into one biweekly date range.
+pub(crate) fn merge_biweekly(date_ranges: Vec<DateRange>) -> Vec<DateRange> {
+    let mut merged: Vec<DateRange> = Vec::new();
+    for date_range in date_ranges {
+        let is_single = |range: &DateRange| (range.end_date - range.start_date).num_days() < 7;
+        let meeting = first_on_or_after(date_range.start_date, date_range.weekday);
+
+        let previous = merged.iter_mut().find(|previous| {
+            (previous.biweekly || is_single(previous))
+                && is_single(&date_range)
+                && meeting > previous.end_date
+                && meeting - Duration::days(14) <= previous.end_date
+                && (meeting - first_on_or_after(previous.start_date, previous.weekday)).num_days()
+                    % 14
+                    == 0
+                && **previous
+                    == DateRange {
+                        start_date: previous.start_date,
+                        end_date: previous.end_date,
+                        biweekly: previous.biweekly,
+                        ..date_range.clone()
+                    }
+        });
+        match previous {
+            Some(previous) => {
+                previous.end_date = date_range.end_date;
+                previous.biweekly = true;
+            }
+            None => merged.push(date_range),
+        }
+    }
+    merged
+}
+
+pub(crate) fn tzid(timezone: &str, datetime: NaiveDateTime) -> String {
+    format!("TZID={timezone}:{}", datetime.format("%Y%m%dT%H%M%S"))
+}
+
+/// A UID that stays the same between exports, so re-importing updates the existing events
+/// instead of duplicating them.
+///
+/// `meeting` distinguishes multiple meetings of the same class on the same weekday.
+fn event_uid(term: &str, class: &Class, slot: &str, meeting: usize) -> String {
+    let slug = |s: &str| {
+        s.split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+            .to_lowercase()
+    };
+
+    let crn = class
+        .crn
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>();
+    // classes without a CRN fall back to their code and type, which are unique enough in practice
+    let class_id = if class.is_custom() {
+        slug(&format!("{} {}", class.class_type, class.name))
+    } else if crn.is_empty() {
+        slug(&format!("{} {}", class.code, class.class_type))
+    } else {
+        crn
+    };
+
+    let mut uid = format!("{}-{class_id}-{}", slug(term), slug(slot));
+    if meeting > 0 {
+        write!(uid, "-{meeting}").ok();
+    }
+    uid + "@mycampus-calendar-rs"
+}
+
+/// Folds lines longer than 75 octets (RFC 5545 section 3.1) without splitting any characters.
+fn fold_calendar(calendar: &mut String) {
+    let mut folded = String::with_capacity(calendar.len() + calendar.len() / 64);
+    for line in calendar.split_inclusive('\n') {
+        let mut length = 0;
+        for c in line.chars() {
+            if c != '\n' && length + c.len_utf8() > 75 {
+                folded.push_str("\n ");
+                length = 1;
+            }
+            length += c.len_utf8();
+            folded.push(c);
+        }
+    }
+    *calendar = folded;
+}
+
+/// Parses `data` and writes one `.ics` file per class type into `output_folder`.
+pub fn generate(
+    output_folder: impl AsRef<Path>,
+    data: &str,
+    exdate: HashSet<NaiveDate>,
+) -> Result<Report, ParseError> {
+    let schedule = Parser::new().parse_data(data)?;
+    Ok(write_calendars(
+        output_folder,
+        &schedule,
+        exdate,
+        &InstitutionProfile::default(),
+        &GenerateOptions::default(),
+    ))
+}
+
+/// One `VEVENT`, before it's written to a calendar.
+pub(crate) struct Meeting<'a> {
+    pub class: &'a Class,
+    /// `None` for all-day events, eg. for asynchronous sections.
+    pub date_range: Option<&'a DateRange>,
+    /// More than one if weekdays were merged.
+    pub weekdays: Vec<Weekday>,
+    /// The first and last date the meeting can happen on.
+    pub first_date: NaiveDate,
+    pub until: NaiveDate,
+    pub uid: String,
+    pub event: Event,
+    /// Latitude and longitude of the building, if it's known.
+    pub geo: Option<[f64; 2]>,
+    /// Whether the event has its class as [`import::CLASS_PROPERTY`]. Only the first event of each
+    /// class in a calendar needs it.
+    pub embed_class: bool,
+}
+
+impl Meeting<'_> {
+    /// Every date this meeting actually happens on, in order.
+    pub fn occurrences(
+        &self,
+        exdate: &HashSet<NaiveDate>,
+        swap_days: &[SwapDay],
+    ) -> Vec<NaiveDate> {
+        let Some(date_range) = self.date_range else {
+            return weekly_dates(self.first_date, self.until, false).collect();
+        };
+        // one-off events like exams don't follow the weekly exclusions
+        if !date_range.is_weekly() {
+            return vec![date_range.start_date];
+        }
+        let mut dates = self
+            .weekdays
+            .iter()
+            .flat_map(|&weekday| {
+                weekly_dates(
+                    first_on_or_after(date_range.start_date, weekday),
+                    date_range.end_date,
+                    date_range.biweekly,
+                )
+            })
+            .filter(|date| {
+                !exdate.contains(date) && !swap_days.iter().any(|swap_day| swap_day.date == *date)
+            })
+            .chain(self.swap_dates(swap_days, exdate))
+            .collect::<Vec<_>>();
+        dates.sort();
+        dates
+    }
+
+    /// Whether the meeting should show as busy time.
+    pub fn is_busy(&self, options: &GenerateOptions) -> bool {
+        self.date_range.is_some() && !options.show_as_free
+    }
+
+    /// A meeting without a recurrence for each of [`occurrences`](Self::occurrences), with the
+    /// date added to its UID.
+    pub fn expand(
+        &self,
+        exdate: &HashSet<NaiveDate>,
+        options: &GenerateOptions,
+        timezone: &str,
+    ) -> Vec<Self> {
+        let (uid, domain) = self.uid.split_once('@').unwrap_or((&self.uid, ""));
+        self.occurrences(exdate, &options.swap_days)
+            .into_iter()
+            .map(|date| Meeting {
+                class: self.class,
+                date_range: self.date_range,
+                weekdays: vec![date.weekday()],
+                first_date: date,
+                until: date,
+                uid: format!("{uid}-{}@{domain}", date.format("%Y%m%d")),
+                event: match self.date_range {
+                    Some(date_range) => {
+                        let (start_time, end_time) = options.event_times(date_range);
+                        Event {
+                            start: tzid(timezone, date.and_time(start_time)),
+                            end: tzid(timezone, date.and_time(end_time)),
+                            rrule: String::new(),
+                            ..self.event.clone()
+                        }
+                    }
+                    None => all_day_event(
+                        self.event.summary.clone(),
+                        self.event.description.clone(),
+                        date,
+                        String::new(),
+                    ),
+                },
+                geo: self.geo,
+                embed_class: self.embed_class,
+            })
+            .collect()
+    }
+
+    /// The swap days that follow this meeting's schedule, in order.
+    pub fn swap_dates(&self, swap_days: &[SwapDay], exdate: &HashSet<NaiveDate>) -> Vec<NaiveDate> {
+        let Some(date_range) = self.date_range else {
+            return Vec::new();
+        };
+        let mut dates = swap_days
+            .iter()
+            .filter(|swap_day| {
+                (date_range.start_date..=date_range.end_date).contains(&swap_day.date)
+                    && self.weekdays.contains(&swap_day.weekday)
+                    && !exdate.contains(&swap_day.date)
+                    && (!date_range.biweekly
+                        || (swap_day.date
+                            - first_on_or_after(date_range.start_date, swap_day.weekday))
+                        .num_days()
+                            % 14
+                            < 7)
+            })
+            .map(|swap_day| swap_day.date)
+            .collect::<Vec<_>>();
+        dates.sort();
+        dates
+    }
+
+    /// The dates in `exdate` that this meeting would otherwise happen on, in order.
+    pub fn excluded_dates(&self, exdate: &HashSet<NaiveDate>) -> Vec<NaiveDate> {
+        let Some(date_range) = self.date_range else {
+            return Vec::new();
+        };
+        let mut dates = exdate
+            .iter()
+            .copied()
+            .filter(|date| {
+                (date_range.start_date..=date_range.end_date).contains(date)
+                    && self.weekdays.contains(&date.weekday())
+                    && (!date_range.biweekly
+                        || (*date - first_on_or_after(date_range.start_date, date.weekday()))
+                            .num_days()
+                            % 14
+                            == 0)
+            })
+            .collect::<Vec<_>>();
+        dates.sort();
+        dates
+    }
+}
+
+fn all_day_event(summary: String, description: String, date: NaiveDate, rrule: String) -> Event {
+    Event {
+        summary,
+        description,
+        start: format!("VALUE=DATE:{}", date.format("%Y%m%d")),
+        end: format!("VALUE=DATE:{}", date.succ_opt().unwrap().format("%Y%m%d")),
+        rrule,
+        location: String::new(),
+        sequence: 0,
+    }
+}
+
+/// Every meeting in `schedule`, in the order they're written.
+pub(crate) fn meetings<'a>(
+    schedule: &'a Schedule,
+    profile: &InstitutionProfile,
+    options: &GenerateOptions,
+) -> Vec<Meeting<'a>> {
+    let timezone = &profile.timezone;
+    let mut meetings = Vec::new();
+    let mut milestone_courses = HashSet::new();
+    for class in schedule
+        .classes
+        .iter()
+        .filter(|class| !options.skips(class))
+    {
+        let name = catalog::full_title(&options.course_titles, &class.code).unwrap_or(&class.name);
+        // campuses can be renamed in the profile
+        let value = |date_range: Option<&DateRange>, placeholder: &str| match date_range {
+            _ if placeholder == "name" => Some(name.to_owned()),
+            Some(date_range) if placeholder == "campus" => {
+                Some(profile.campus(date_range).unwrap_or_default())
+            }
+            _ => template::event_value(class, date_range, placeholder),
+        };
+        let summary = |date_range| match options.grouping {
+            // the templates are for course details, which custom events don't have
+            _ if class.is_custom() => name.to_owned(),
+            _ if class.is_exam() => format!("{name} ({})", class.class_type),
+            _ if !options.summary_template.is_empty() => {
+                template::render(&options.summary_template, |placeholder| {
+                    value(date_range, placeholder)
+                })
+            }
+            Grouping::ClassType => name.to_owned(),
+            // lectures and labs end up in the same calendar, so tell them apart
+            _ => format!("{name} ({})", class.class_type),
+        };
+        let description = |date_range: Option<&DateRange>| {
+            let mut lines = Vec::new();
+            if !class.is_custom() {
+                lines.push(template::render(
+                    &options.description_template,
+                    |placeholder| value(date_range, placeholder),
+                ));
+            }
+            // so the link is there even if the template leaves it out
+            if let Some(url) = class
+                .meeting_url
+                .as_ref()
+                .filter(|_| !options.description_template.contains("{url}"))
+            {
+                lines.push(format!("Join: {url}"));
+            }
+            if let Some(url) = date_range
+                .filter(|_| options.maps_links)
+                .and_then(|date_range| profile.maps_url(date_range))
+            {
+                lines.push(format!("Map: {url}"));
+            }
+            lines.retain(|line| !line.is_empty());
+            lines.join("\n")
+        };
+
+        let mut slots: Vec<(&DateRange, Vec<Weekday>)> = Vec::new();
+        for date_range in &class.date_ranges {
+            let same_slot = slots.iter_mut().find(|(first, _)| {
+                options.merge_weekdays
+                    && **first
+                        == DateRange {
+                            weekday: first.weekday,
+                            ..date_range.clone()
+                        }
+            });
+            match same_slot {
+                Some((_, weekdays)) if !weekdays.contains(&date_range.weekday) => {
+                    weekdays.push(date_range.weekday)
+                }
+                Some(_) => (),
+                None => slots.push((date_range, vec![date_range.weekday])),
+            }
+        }
+
+        let mut meetings_per_slot: HashMap<String, usize> = HashMap::new();
+        let mut next_uid = |slot: String| {
+            let meeting = meetings_per_slot.entry(slot.clone()).or_default();
+            let uid = event_uid(schedule.class_term(class), class, &slot, *meeting);
+            *meeting += 1;
+            uid
+        };
+
+        for (date_range, weekdays) in slots {
+            let uid = next_uid(
+                weekdays
+                    .iter()
+                    .map(|weekday| weekday.to_string())
+                    .collect::<Vec<_>>()
+                    .join("-"),
+            );
+
+            let first_date = weekdays
+                .iter()
+                .map(|&weekday| first_on_or_after(date_range.start_date, weekday))
+                .min()
+                .unwrap_or(date_range.start_date);
+
+            let interval = if date_range.biweekly {
+                "INTERVAL=2;"
+            } else {
+                ""
+            };
+            let by_day = if weekdays.len() > 1 {
+                format!(
+                    "BYDAY={};",
+                    weekdays
+                        .iter()
+                        .map(|weekday| weekday.to_string()[..2].to_uppercase())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                )
+            } else {
+                String::new()
+            };
+
+            let (start_time, end_time) = options.event_times(date_range);
+            meetings.push(Meeting {
+                class,
+                date_range: Some(date_range),
+                weekdays,
+                first_date,
+                until: date_range.end_date,
+                uid,
+                event: Event {
+                    summary: summary(Some(date_range)),
+                    start: tzid(timezone, first_date.and_time(start_time)),
+                    end: tzid(timezone, first_date.and_time(end_time)),
+                    rrule: match date_range.kind {
+                        DateRangeKind::Weekly => format!(
+                            "FREQ=WEEKLY;{interval}{by_day}TZID={timezone};UNTIL={}",
+                            date_range
+                                .end_date
+                                .and_hms_opt(23, 59, 59)
+                                .unwrap()
+                                .format("%Y%m%dT%H%M%S")
+                        ),
+                        DateRangeKind::Single => String::new(),
+                    },
+                    location: profile.location(date_range),
+                    description: description(Some(date_range)),
+                    sequence: 0,
+                },
+                geo: profile.geo(date_range),
+                embed_class: true,
+            });
+        }
+
+        if options.async_events != AsyncEvents::Off {
+            for &(start_date, end_date) in &class.asynchronous {
+                let rrule = match options.async_events {
+                    AsyncEvents::Weekly => {
+                        format!("FREQ=WEEKLY;UNTIL={}", end_date.format("%Y%m%d"))
+                    }
+                    _ => String::new(),
+                };
+                meetings.push(Meeting {
+                    class,
+                    date_range: None,
+                    weekdays: vec![start_date.weekday()],
+                    first_date: start_date,
+                    until: match options.async_events {
+                        AsyncEvents::Weekly => end_date,
+                        _ => start_date,
+                    },
+                    uid: next_uid("async".to_owned()),
+                    event: all_day_event(
+                        format!("{} (asynchronous)", summary(None)),
+                        description(None),
+                        start_date,
+                        rrule,
+                    ),
+                    geo: None,
+                    embed_class: true,
+                });
+            }
+        }
+
+        // once per course, since the lectures, labs, etc. are usually in the same term
+        if options.milestones && !class.is_exam() && milestone_courses.insert(&class.code) {
+            let dates = schedule
+                .classes
+                .iter()
+                .filter(|section| section.code == class.code && !section.is_exam())
+                .flat_map(|section| {
+                    section
+                        .date_ranges
+                        .iter()
+                        .map(|date_range| (date_range.start_date, date_range.end_date))
+                        .chain(section.asynchronous.iter().copied())
+                })
+                .collect::<Vec<_>>();
+            let first_day = dates.iter().map(|&(start_date, _)| start_date).min();
+            let last_day = dates.iter().map(|&(_, end_date)| end_date).max();
+
+            for (slot, label, date) in [
+                ("first-day", "First day", first_day),
+                ("last-day", "Last day", last_day),
+            ] {
+                let Some(date) = date else {
+                    continue;
+                };
+                meetings.push(Meeting {
+                    class,
+                    date_range: None,
+                    weekdays: vec![date.weekday()],
+                    first_date: date,
+                    until: date,
+                    uid: next_uid(slot.to_owned()),
+                    event: all_day_event(
+                        format!("{label} of {name}"),
+                        description(None),
+                        date,
+                        String::new(),
+                    ),
+                    geo: None,
+                    embed_class: true,
+                });
+            }
+        }
+    }
+    meetings
+}
+
+/// Calendar apps only replace an existing event if the sequence is higher, so use the time since
+/// an arbitrary epoch to always increase it between exports.
+pub(crate) fn default_sequence() -> i64 {
+    (Utc::now().naive_utc()
+        - NaiveDate::from_ymd_opt(2020, 1, 1)
+            .unwrap()
+            .and_time(NaiveTime::MIN))
+    .num_minutes()
+    .max(0)
+}
+
+/// `exdate` plus the swap days, since those don't follow their usual schedule.
+pub(crate) fn with_swap_days(
+    exdate: &HashSet<NaiveDate>,
+    swap_days: &[SwapDay],
+) -> HashSet<NaiveDate> {
+    exdate
+        .iter()
+        .copied()
+        .chain(swap_days.iter().map(|swap_day| swap_day.date))
+        .collect()
+}
+
+/// Appends the `VEVENT` for `meeting` to `calendar`.
+pub(crate) fn write_event(
+    calendar: &mut String,
+    meeting: &Meeting,
+    exdate: &HashSet<NaiveDate>,
+    options: &GenerateOptions,
+    timezone: &str,
+) {
+    write!(
+        calendar,
+        indoc! {"
+            BEGIN:VEVENT
+            DTSTAMP:{dtstamp}
+            UID:{uid}
+            SEQUENCE:{sequence}
+            DTSTART;{dtstart}
+            DTEND;{dtend}
+        "},
+        dtstamp = Utc::now().format("%Y%m%dT%H%M%SZ"),
+        uid = meeting.uid,
+        sequence = meeting.event.sequence,
+        dtstart = meeting.event.start,
+        dtend = meeting.event.end,
+    )
+    .ok();
+    if !meeting.event.rrule.is_empty() {
+        writeln!(calendar, "RRULE:{}", meeting.event.rrule).ok();
+    }
+    // expanded occurrences are already on the right dates
+    if let Some(date_range) = meeting
+        .date_range
+        .filter(|_| !meeting.event.rrule.is_empty())
+    {
+        let (start_time, _) = options.event_times(date_range);
+        let date_times = |dates: Vec<NaiveDate>| {
+            dates
+                .iter()
+                .map(|d| d.and_time(start_time).format("%Y%m%dT%H%M%S").to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let swap_dates = meeting.swap_dates(&options.swap_days, exdate);
+        if !swap_dates.is_empty() {
+            writeln!(calendar, "RDATE;TZID={timezone}:{}", date_times(swap_dates)).ok();
+        }
+        let excluded_dates = meeting.excluded_dates(&with_swap_days(exdate, &options.swap_days));
+        if !excluded_dates.is_empty() {
+            writeln!(
+                calendar,
+                "EXDATE;TZID={timezone}:{}",
+                date_times(excluded_dates)
+            )
+            .ok();
+        }
+    }
+    write!(
+        calendar,
+        indoc! {"
+            SUMMARY:{name}
+            DESCRIPTION:{description}
+        "},
+        name = escape_text(&meeting.event.summary),
+        description = escape_text(&meeting.event.description),
+    )
+    .ok();
+    if let Some(url) = &meeting.class.meeting_url {
+        writeln!(calendar, "URL:{url}").ok();
+    }
+    if meeting.date_range.is_some() {
+        writeln!(
+            calendar,
+            "LOCATION:{}",
+            escape_text(&meeting.event.location)
+        )
+        .ok();
+        if let Some([latitude, longitude]) = meeting.geo {
+            // Apple Calendar only shows a map and travel time with its own property
+            write!(
+                calendar,
+                indoc! {r#"
+                    GEO:{latitude};{longitude}
+                    X-APPLE-STRUCTURED-LOCATION;VALUE=URI;X-APPLE-RADIUS=70;X-TITLE="{title}":geo:{latitude},{longitude}
+                "#},
+                latitude = latitude,
+                longitude = longitude,
+                title = meeting.event.location.replace('"', "'"),
+            )
+            .ok();
+        }
+    }
+    let busy = meeting.is_busy(options);
+    write!(
+        calendar,
+        indoc! {"
+            TRANSP:{transp}
+            X-MICROSOFT-CDO-BUSYSTATUS:{busy_status}
+            CLASS:{class}
+        "},
+        transp = if busy { "OPAQUE" } else { "TRANSPARENT" },
+        busy_status = if busy { "BUSY" } else { "FREE" },
+        class = if options.private_events {
+            "PRIVATE"
+        } else {
+            "PUBLIC"
+        },
+    )
+    .ok();
+    writeln!(
+        calendar,
+        "CATEGORIES:{}",
+        escape_text(&meeting.class.class_type)
+    )
+    .ok();
+    if options.instructor_attendees {
+        write_instructors(calendar, meeting.class, options);
+    }
+    // so the calendar can be loaded back into the preview, see `import`
+    if let Some(class) = meeting
+        .embed_class
+        .then(|| serde_json::to_string(meeting.class).ok())
+        .flatten()
+    {
+        writeln!(
+            calendar,
+            "{}:{}",
+            import::CLASS_PROPERTY,
+            escape_text(&class)
+        )
+        .ok();
+    }
+    if let Some(&color) = options.colors.get(&meeting.class.class_type) {
+        writeln!(calendar, "COLOR:{}", css_color_name(color)).ok();
+    }
+    if let Some(minutes) = options.reminders.get(&meeting.class.class_type) {
+        write!(
+            calendar,
+            indoc! {"
+                BEGIN:VALARM
+                ACTION:DISPLAY
+                DESCRIPTION:{name}
+                TRIGGER:-PT{minutes}M
+                END:VALARM
+            "},
+            name = escape_text(&meeting.event.summary),
+            minutes = minutes,
+        )
+        .ok();
+    }
+    calendar.push_str("END:VEVENT\n");
+}
+
+/// Appends an `ORGANIZER` for `class`'s primary instructor and an `ATTENDEE` for each instructor
+/// whose email is known. Without the primary instructor's email nothing is written, since an event
+/// with attendees needs an organizer; the instructors are still in the description.
+fn write_instructors(calendar: &mut String, class: &Class, options: &GenerateOptions) {
+    let email = |instructor: &Instructor| {
+        options
+            .instructor_emails
+            .get(&instructor.name)
+            .map(|email| email.trim())
+            .filter(|email| !email.is_empty())
+    };
+    let name = |instructor: &Instructor| instructor.display_name().replace('"', "'");
+
+    let instructors = class.instructors();
+    let Some((organizer, organizer_email)) = instructors
+        .iter()
+        .filter(|instructor| instructor.primary)
+        .find_map(|instructor| Some((instructor, email(instructor)?)))
+    else {
+        return;
+    };
+    writeln!(
+        calendar,
+        "ORGANIZER;CN=\"{}\":mailto:{organizer_email}",
+        name(organizer)
+    )
+    .ok();
+    for instructor in &instructors {
+        let Some(email) = email(instructor) else {
+            continue;
+        };
+        writeln!(
+            calendar,
+            "ATTENDEE;CN=\"{}\";ROLE={role}:mailto:{email}",
+            name(instructor),
+            role = if instructor.primary {
+                "CHAIR"
+            } else {
+                "REQ-PARTICIPANT"
+            },
+        )
+        .ok();
+    }
+}
+
+/// Ends `calendar` and converts it to the line endings and line lengths required by RFC 5545.
+pub(crate) fn finish_calendar(calendar: &mut String) {
+    calendar.push_str("END:VCALENDAR");
+    fold_calendar(calendar);
+    *calendar = calendar.replace('\n', "\r\n");
+}
+
+/// The calendars in `output_folder` that [`write_calendars`] would replace.
+pub fn existing_calendars(
+    output_folder: impl AsRef<Path>,
+    schedule: &Schedule,
+    profile: &InstitutionProfile,
+    options: &GenerateOptions,
+) -> Vec<PathBuf> {
+    let names = meetings(schedule, profile, options)
+        .iter()
+        .map(|meeting| {
+            options
+                .grouping
+                .calendar_name(meeting.class, schedule.class_term(meeting.class))
+        })
+        .collect::<BTreeSet<_>>();
+    names
+        .into_iter()
+        .map(|name| output_folder.as_ref().join(format!("{name}.ics")))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// Writes the calendars for an already-parsed schedule into `output_folder`, split into files
+/// according to [`GenerateOptions::grouping`].
+pub fn write_calendars(
+    output_folder: impl AsRef<Path>,
+    schedule: &Schedule,
+    exdate: HashSet<NaiveDate>,
+    profile: &InstitutionProfile,
+    options: &GenerateOptions,
+) -> Report {
+    write_calendars_with_progress(
+        output_folder,
+        schedule,
+        exdate,
+        profile,
+        options,
+        &Progress::default(),
+    )
+}
+
+/// How far along [`write_calendars_with_progress`] is, shared with another thread so it can
+/// show the progress and cancel.
+#[derive(Debug, Default)]
+pub struct Progress {
+    done: AtomicUsize,
+    total: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+impl Progress {
+    /// Between 0 and 1.
+    pub fn fraction(&self) -> f32 {
+        match self.total.load(Ordering::Relaxed) {
+            0 => 0.0,
+            total => self.done.load(Ordering::Relaxed) as f32 / total as f32,
+        }
+    }
+
+    /// Stops before the next event or file. Files that were already written are kept.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    fn step(&self) {
+        self.done.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// The finished calendars for `schedule` by name, without the extension, and the events in them
+/// by UID. Sequences are kept from `previous` unless the event changed.
+fn build_calendars(
+    schedule: &Schedule,
+    exdate: &HashSet<NaiveDate>,
+    profile: &InstitutionProfile,
+    options: &GenerateOptions,
+    previous: &HashMap<String, Event>,
+    progress: &Progress,
+) -> (BTreeMap<String, String>, Vec<(String, Event)>) {
+    let timezone = &profile.timezone;
+    let default_sequence = default_sequence();
+
+    let mut calendars = BTreeMap::new();
+    let mut summary: BTreeMap<String, BTreeMap<String, u32>> = BTreeMap::new();
+    let mut current = Vec::new();
+    // the calendar each class is in only needs it once
+    let mut embedded_classes = HashSet::new();
+
+    let meetings = meetings(schedule, profile, options);
+    let buffers = travel::travel_buffers(&meetings, exdate, options);
+    progress.total.store(meetings.len(), Ordering::Relaxed);
+    for (index, meeting) in meetings.into_iter().enumerate() {
+        if progress.is_cancelled() {
+            calendars.clear();
+            break;
+        }
+        progress.step();
+        let class = meeting.class;
+
+        let calendar = calendars
+            .entry(
+                options
+                    .grouping
+                    .calendar_name(class, schedule.class_term(class)),
+            )
+            .or_insert_with(|| {
+                let mut calendar = format!(
+                    indoc! {"
+                        BEGIN:VCALENDAR
+                        VERSION:2.0
+                        PRODID:MYCAMPUS-CALENDAR-RS
+                        CALSCALE:GREGORIAN
+                        METHOD:PUBLISH
+                        X-WR-CALNAME:{name}
+                        X-WR-TIMEZONE:{timezone}
+                    "},
+                    name = escape_text(
+                        &options
+                            .grouping
+                            .calendar_title(class, schedule.class_term(class)),
+                    ),
+                    timezone = timezone,
+                );
+                if let (Grouping::ClassType, Some(&color)) =
+                    (options.grouping, options.colors.get(&class.class_type))
+                {
+                    writeln!(calendar, "X-APPLE-CALENDAR-COLOR:{}", hex_color(color)).ok();
+                }
+                if let Some(vtimezone) = profile.vtimezone() {
+                    calendar.push_str(vtimezone);
+                }
+                calendar
+            });
+        *summary
+            .entry(class.name.clone())
+            .or_default()
+            .entry(class.class_type.clone())
+            .or_default() += 1;
+
+        for buffer in buffers.iter().filter(|buffer| buffer.meeting == index) {
+            travel::write_buffer(calendar, buffer, &meeting, timezone);
+        }
+
+        let meetings = if options.expand_occurrences && !meeting.event.rrule.is_empty() {
+            meeting.expand(exdate, options, timezone)
+        } else {
+            vec![meeting]
+        };
+        for mut meeting in meetings {
+            meeting.event.sequence = match previous.get(&meeting.uid) {
+                Some(previous) if meeting.event.changes_from(previous).is_empty() => {
+                    previous.sequence
+                }
+                Some(previous) => previous.sequence + 1,
+                None => default_sequence,
+            };
+            meeting.embed_class = embedded_classes.insert(ptr::from_ref(meeting.class));
+
+            write_event(calendar, &meeting, exdate, options, timezone);
+
+            current.push((meeting.uid, meeting.event));
+        }
+    }
+
+    for deadline in &options.deadlines {
+        let class = schedule.classes.iter().find(|class| {
+            class.code == deadline.course
+                && !class.is_exam()
+                && !class.date_ranges.is_empty()
+                && !options.skips(class)
+        });
+        if let Some(calendar) = class.and_then(|class| {
+            calendars.get_mut(
+                &options
+                    .grouping
+                    .calendar_name(class, schedule.class_term(class)),
+            )
+        }) {
+            deadlines::write_deadline(calendar, deadline);
+        }
+    }
+    for calendar in calendars.values_mut() {
+        finish_calendar(calendar);
+    }
+
+    let max_name_len = summary.keys().map(|n| n.len()).max().unwrap_or(0);
+    for (name, class_summary) in summary {
+        info!(
+            "{:indent$}{} → {}",
+            "",
+            name,
+            class_summary
+                .iter()
+                .map(|(class_type, count)| format!("{}: {}", class_type, count))
+                .collect::<Vec<String>>()
+                .join(", "),
+            indent = max_name_len - name.len()
+        );
+    }
+
+    (calendars, current)
+}
+
+/// The calendars for `schedule` by file name, named like by [`write_calendars`], without writing
+/// them anywhere, eg. to offer them as downloads in the web build. Previous exports aren't
+/// compared with, so every event gets a new sequence.
+pub fn generate_calendars(
+    schedule: &Schedule,
+    exdate: &HashSet<NaiveDate>,
+    profile: &InstitutionProfile,
+    options: &GenerateOptions,
+) -> BTreeMap<String, String> {
+    let (calendars, _) = build_calendars(
+        schedule,
+        exdate,
+        profile,
+        options,
+        &HashMap::new(),
+        &Progress::default(),
+    );
+    calendars
+        .into_iter()
+        .map(|(name, calendar)| (format!("{name}.ics"), calendar))
+        .collect()
+}
+
+/// Like [`write_calendars`], but updates `progress` after each event and file, and stops early
+/// if it's cancelled.
+pub fn write_calendars_with_progress(
+    output_folder: impl AsRef<Path>,
+    schedule: &Schedule,
+    exdate: HashSet<NaiveDate>,
+    profile: &InstitutionProfile,
+    options: &GenerateOptions,
+    progress: &Progress,
+) -> Report {
+    let data = &schedule.classes;
+
+    debug!("Data: {:?}", data);
+    debug!("Excluded dates: {:?}", exdate);
+
+    let mut warnings = schedule.warnings.clone();
+    let previous = if options.update_previous {
+        update::read_export(&output_folder).unwrap_or_else(|e| {
+            warnings.push(format!("Failed to read the previous export: {e}"));
+            HashMap::new()
+        })
+    } else {
+        HashMap::new()
+    };
+
+    let (calendars, current) =
+        build_calendars(schedule, &exdate, profile, options, &previous, progress);
+    let mut files = Vec::new();
+    let mut write_failures = Vec::new();
+
+    progress.total.fetch_add(calendars.len(), Ordering::Relaxed);
+    for (name, calendar) in &calendars {
+        if progress.is_cancelled() {
+            break;
+        }
+        progress.step();
+
+        let mut output_path = output_folder.as_ref().join(format!("{name}.ics"));
+        if options.rename_existing {
+            // eg. "Fall 2024 - Lecture (2).ics"
+            for n in 2.. {
+                if !output_path.exists() {
+                    break;
+                }
+                output_path = output_folder.as_ref().join(format!("{name} ({n}).ics"));
+            }
+        }
+        info!("Writing calendar: {}", output_path.display());
+        match fs::write(&output_path, calendar) {
+            Ok(()) => files.push(output_path),
+            Err(e) => write_failures.push(WriteFailure {
+                path: output_path,
+                error: write_error(&e),
+                contents: calendar.clone(),
+            }),
+        }
+    }
+    files.sort();
+
+    let n = calendars.len();
+    info!("Wrote {n} .ics file(s).");
+    if options.zip && !progress.is_cancelled() {
+        match archive::zip_outputs(&output_folder) {
+            Ok(path) => {
+                info!("Wrote {}", path.display());
+                files.push(path);
+            }
+            Err(e) => warnings.push(format!("Failed to write {}: {e}", archive::ZIP_FILE_NAME)),
+        }
+    }
+    Report {
+        calendars: n,
+        succeeded: data
+            .iter()
+            .map(|class| format!("{} ({})", class.name, class.class_type))
+            .collect(),
+        failed: schedule.failures.clone(),
+        warnings,
+        changes: if options.update_previous {
+            update::diff(&previous, &current)
+        } else {
+            Vec::new()
+        },
+        statistics: stats::statistics(data),
+        files,
+        write_failures,
+        cancelled: progress.is_cancelled(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_browser_formats() {
+        do_test(include_str!("../tests/data/chromium.txt"), "Chromium/Edge");
+        do_test(include_str!("../tests/data/firefox.txt"), "Firefox");
+        do_test(include_str!("../tests/data/mobile.txt"), "Mobile");
+    }
+
+    #[test]
+    fn test_parse_tba() {
+        let data = include_str!("../tests/data/chromium.txt")
+            .replace("Instructor: Doe, John (Primary)\n", "")
+            .replace(
+                "Location: North Oshawa Building: Software and Informatics Research Centre Room: SIRC 3110",
+                "Location: TBA",
+            )
+            .replace("Instructor: Smith, Alice (Primary)", "Instructor: TBA");
+        let schedule = Parser::new().parse_data(&data).unwrap();
+
+        assert!(schedule.failures.is_empty());
+        let (lecture, lab) = (&schedule.classes[0], &schedule.classes[1]);
+        assert_eq!(lecture.instructor, None);
+        assert_eq!(lecture.crn, "CRN: 40001");
+        assert_eq!(lab.instructor, None);
+        assert_eq!(lab.date_ranges[0].location, None);
+        assert_eq!(lab.date_ranges[0].room, None);
+        assert_eq!(lab.date_ranges[0].place(), TBA);
+    }
+
+    #[test]
+    fn test_parse_skips_broken_class() {
+        let data = include_str!("../tests/data/chromium.txt").replacen(
+            "11:10 AM - 12:30 PM",
+            "11:10 AM to 12:30 PM",
+            1,
+        );
+        let schedule = Parser::new().parse_data(&data).unwrap();
+
+        assert_eq!(schedule.classes.len(), 2);
+        assert_eq!(schedule.failures.len(), 1);
+        assert!(matches!(
+            schedule.failures[0].error,
+            ParseError::TimeLine(_)
+        ));
+        assert_eq!(schedule.failures[0].line, Some(21));
+        assert!(schedule.failures[0].error.expected().is_some());
+    }
+
+    #[test]
+    fn test_parse_broken_class_fixture() {
+        // the second class ends on December 33
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/broken_class.txt"))
+            .unwrap();
+
+        let names = schedule
+            .classes
+            .iter()
+            .map(|class| class.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "Discrete Mathematics for Comp. Sci",
+                "Science Co-op Success Program"
+            ]
+        );
+        assert_eq!(schedule.failures.len(), 1);
+        let failure = &schedule.failures[0];
+        assert!(failure.heading.starts_with("Programming Workshop I |"));
+        assert!(matches!(&failure.error, ParseError::Date(date, _) if date == "12/33/2024"));
+        assert_eq!(failure.line, Some(36));
+
+        // and the others still get calendars
+        let calendars = generate_calendars(
+            &schedule,
+            &HashSet::new(),
+            &InstitutionProfile::default(),
+            &GenerateOptions::default(),
+        );
+        assert!(!calendars.is_empty());
+        assert!(calendars
+            .values()
+            .all(|calendar| !calendar.contains("Programming Workshop")));
+    }
+
+    #[test]
+    fn test_parse_error_lines() {
+        let data = include_str!("../tests/data/chromium.txt");
+        let only_failure = |parser: Parser, data: &str| {
+            let schedule = parser.parse_data(data).unwrap();
+            assert_eq!(schedule.failures.len(), 1);
+            schedule.failures.into_iter().next().unwrap()
+        };
+
+        let failure = only_failure(
+            Parser::new(),
+            &data.replacen(
+                "09/04/2024 -- 12/03/2024   Monday",
+                "09/44/2024 -- 12/03/2024   Monday",
+                1,
+            ),
+        );
+        assert!(matches!(&failure.error, ParseError::Date(date, _) if date == "09/44/2024"));
+        assert_eq!(failure.line, Some(13));
+
+        let failure = only_failure(
+            Parser::new(),
+            &data.replacen("11:10 AM - 12:30 PM", "11:70 AM - 12:30 PM", 1),
+        );
+        assert!(matches!(&failure.error, ParseError::Time(time, _) if time == "11:70 AM"));
+        assert_eq!(failure.line, Some(21));
+
+        // the date line is read as the message line instead
+        let failure = only_failure(
+            Parser::new(),
+            &data.replacen(
+                "| Schedule Type: Lecture | Instructional Method: In-Person | Campus: North Oshawa | Credit Hours: 3 |\n",
+                "",
+                1,
+            ),
+        );
+        assert!(matches!(failure.error, ParseError::MessageLine(_)));
+        assert_eq!(failure.line, Some(12));
+
+        let mut profile = InstitutionProfile::default();
+        profile.regexes.time = r"^\s*(\d+:\d+ [AP]M)?(?: - (\d+:\d+ [AP]M))?".to_owned();
+        let failure = only_failure(
+            Parser::from_profile(&profile).unwrap(),
+            &data.replacen("11:10 AM - 12:30 PM", "11:10 AM to 12:30 PM", 1),
+        );
+        assert!(matches!(
+            failure.error,
+            ParseError::RegexGroup("time", 2, _)
+        ));
+        assert_eq!(failure.line, Some(21));
+    }
+
+    #[test]
+    fn test_parse_day_first_dates() {
+        let want = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        // as shown by a browser set to French (Canada)
+        let data = include_str!("../tests/data/chromium.txt")
+            .replace("09/04/2024", "04/09/2024")
+            .replace("12/03/2024", "03/12/2024")
+            .replace("Monday", "lundi")
+            .replace("Wednesday", "mercredi")
+            .replace("Thursday", "jeudi")
+            .replace("11:10 AM - 12:30 PM", "11:10 - 12:30")
+            .replace("02:10 PM - 05:00 PM", "14:10 - 17:00");
+        let schedule = Parser::new().parse_data(&data).unwrap();
+
+        assert!(schedule.failures.is_empty());
+        assert!(schedule.warnings.is_empty());
+        assert_eq!(
+            serde_json::to_value(&schedule.classes).unwrap(),
+            serde_json::to_value(&want.classes).unwrap()
+        );
+
+        // both orders give valid ranges, so it's ambiguous
+        let data = data.replace("03/12/2024", "10/12/2024");
+        let schedule = Parser::new().parse_data(&data).unwrap();
+        assert_eq!(schedule.warnings.len(), 1);
+        assert_eq!(
+            schedule.classes[0].date_ranges[0].start_date,
+            NaiveDate::from_ymd_opt(2024, 4, 9).unwrap()
+        );
+        let schedule = Parser::new()
+            .with_date_order(DateOrder::DayFirst)
+            .parse_data(&data)
+            .unwrap();
+        assert!(schedule.warnings.is_empty());
+        assert_eq!(
+            schedule.classes[0].date_ranges[0].start_date,
+            NaiveDate::from_ymd_opt(2024, 9, 4).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_multiple_terms() {
+        let fall = include_str!("../tests/data/chromium.txt");
+        let lab_start = fall.find("Programming Workshop I |").unwrap();
+        let lab_end = fall.find("CRN: 40002").unwrap() + "CRN: 40002".len();
+        let winter = fall[lab_start..lab_end]
+            .replace("09/04/2024", "01/06/2025")
+            .replace("12/03/2024", "04/08/2025")
+            .replace("40002", "70002");
+        let data = format!(
+            "{}\nClass Schedule for Winter 2025\n{winter}\n",
+            fall.trim_end()
+        );
+        let schedule = Parser::new().parse_data(&data).unwrap();
+
+        assert!(schedule.failures.is_empty());
+        assert_eq!(schedule.classes.len(), 4);
+        assert_eq!(schedule.term, "Fall 2024");
+        assert_eq!(schedule.terms(), ["Fall 2024", "Winter 2025"]);
+        assert_eq!(schedule.classes[2].term, "Fall 2024");
+        let winter_lab = &schedule.classes[3];
+        assert_eq!(winter_lab.term, "Winter 2025");
+        assert_eq!(winter_lab.crn, "CRN: 70002");
+        assert_eq!(
+            winter_lab.date_ranges[0].start_date,
+            NaiveDate::from_ymd_opt(2025, 1, 6).unwrap()
+        );
+
+        let meetings = meetings(
+            &schedule,
+            &InstitutionProfile::default(),
+            &GenerateOptions::default(),
+        );
+        assert!(meetings
+            .iter()
+            .any(|meeting| meeting.uid.starts_with("winter-2025-70002-")));
+
+        let names = [&schedule.classes[1], winter_lab]
+            .map(|class| Grouping::ClassType.calendar_name(class, schedule.class_term(class)));
+        assert_eq!(
+            names,
+            ["Fall 2024 - Laboratory", "Winter 2025 - Laboratory"]
+        );
+    }
+
+    #[test]
+    fn test_event_uid_is_stable() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let class = &schedule.classes[0];
+        let weekday = class.date_ranges[0].weekday;
+        assert_eq!(
+            event_uid(&schedule.term, class, &weekday.to_string(), 0),
+            "fall-2024-40001-mon@mycampus-calendar-rs"
+        );
+
+        assert_eq!(schedule.term, "Fall 2024");
+        assert_eq!(
+            event_uid(&schedule.term, class, &weekday.to_string(), 0),
+            event_uid(&schedule.term, &class.clone(), &weekday.to_string(), 0)
+        );
+        assert_ne!(
+            event_uid(&schedule.term, class, &weekday.to_string(), 0),
+            event_uid(&schedule.term, class, &weekday.to_string(), 1)
+        );
+        assert_ne!(
+            event_uid(&schedule.term, class, &weekday.to_string(), 0),
+            event_uid("Winter 2025", class, &weekday.to_string(), 0)
+        );
+    }
+
+    #[test]
+    fn test_merge_weekdays() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let profile = InstitutionProfile::default();
+
+        let separate = meetings(&schedule, &profile, &GenerateOptions::default());
+        let merged = meetings(
+            &schedule,
+            &profile,
+            &GenerateOptions {
+                merge_weekdays: true,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(merged.len(), separate.len() - 1);
+        assert!(merged[0].event.rrule.contains("BYDAY=MO,WE;"));
+        // starts on the first Wednesday, since the term starts after Monday
+        assert_eq!(merged[0].event.start, separate[1].event.start);
+    }
+
+    #[test]
+    fn test_merge_biweekly() {
+        let range = |start: u32, end: u32, weekday| DateRange {
+            start_date: NaiveDate::from_ymd_opt(2024, 9, start).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2024, 9, end).unwrap(),
+            start_time: NaiveTime::from_hms_opt(14, 10, 0).unwrap(),
+            end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            weekday,
+            location: Some("North Oshawa".to_owned()),
+            building: Some("Science Building".to_owned()),
+            room: Some("UA 1350".to_owned()),
+            biweekly: false,
+            kind: DateRangeKind::Weekly,
+        };
+
+        let merged = merge_biweekly(vec![
+            range(5, 5, Weekday::Thu),
+            range(2, 30, Weekday::Mon),
+            range(16, 22, Weekday::Thu),
+            range(26, 26, Weekday::Thu),
+        ]);
+        assert_eq!(
+            merged,
+            vec![
+                DateRange {
+                    biweekly: true,
+                    ..range(5, 22, Weekday::Thu)
+                },
+                range(2, 30, Weekday::Mon),
+                range(26, 26, Weekday::Thu),
+            ]
+        );
+
+        // a week apart isn't biweekly
+        let weekly = vec![range(5, 5, Weekday::Thu), range(12, 12, Weekday::Thu)];
+        assert_eq!(merge_biweekly(weekly.clone()), weekly);
+    }
+
+    #[test]
+    fn test_combined_calendar() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let folder = crate::test_folder("combined-calendar");
+
+        let report = write_calendars(
+            &folder,
+            &schedule,
+            HashSet::new(),
+            &InstitutionProfile::default(),
+            &GenerateOptions {
+                grouping: Grouping::Combined,
+                ..Default::default()
+            },
+        );
+        let files = fs::read_dir(&folder)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<_>>();
+        fs::remove_dir_all(&folder).ok();
+
+        assert_eq!(report.calendars, 1);
+        assert_eq!(files, ["Fall 2024 - schedule.ics"]);
+        assert_eq!(report.files, [folder.join("Fall 2024 - schedule.ics")]);
+    }
+
+    #[test]
+    fn test_rename_existing() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let folder = crate::test_folder("rename-existing");
+        let profile = InstitutionProfile::default();
+        let options = GenerateOptions {
+            grouping: Grouping::Combined,
+            rename_existing: true,
+            ..Default::default()
+        };
+
+        assert!(existing_calendars(&folder, &schedule, &profile, &options).is_empty());
+        write_calendars(&folder, &schedule, HashSet::new(), &profile, &options);
+        let existing = existing_calendars(&folder, &schedule, &profile, &options);
+        let report = write_calendars(&folder, &schedule, HashSet::new(), &profile, &options);
+        fs::remove_dir_all(&folder).ok();
+
+        assert_eq!(existing, [folder.join("Fall 2024 - schedule.ics")]);
+        assert_eq!(report.files, [folder.join("Fall 2024 - schedule (2).ics")]);
+    }
+
+    #[test]
+    fn test_retry_writes() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let folder = std::env::temp_dir().join("mycampus-calendar-rs-test-retry-writes");
+        fs::remove_dir_all(&folder).ok();
+
+        let mut report = write_calendars(
+            &folder,
+            &schedule,
+            HashSet::new(),
+            &InstitutionProfile::default(),
+            &GenerateOptions {
+                grouping: Grouping::Combined,
+                ..Default::default()
+            },
+        );
+        assert!(report.files.is_empty());
+        assert_eq!(report.write_failures.len(), 1);
+        assert!(report.write_failures[0].error.contains("moved or deleted"));
+
+        fs::create_dir_all(&folder).unwrap();
+        report.retry_writes();
+        let written = fs::read_to_string(folder.join("Fall 2024 - schedule.ics")).unwrap();
+        fs::remove_dir_all(&folder).ok();
+
+        assert!(report.write_failures.is_empty());
+        assert_eq!(report.files, [folder.join("Fall 2024 - schedule.ics")]);
+        assert!(written.starts_with("BEGIN:VCALENDAR"));
+    }
+
+    #[test]
+    fn test_generate_calendars() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let folder = crate::test_folder("generate");
+        let profile = InstitutionProfile::default();
+        let options = GenerateOptions::default();
+
+        let generated = generate_calendars(&schedule, &HashSet::new(), &profile, &options);
+        let report = write_calendars(&folder, &schedule, HashSet::new(), &profile, &options);
+        let lecture = fs::read_to_string(folder.join("Fall 2024 - Lecture.ics")).unwrap();
+        fs::remove_dir_all(&folder).ok();
+
+        assert_eq!(generated.len(), report.calendars);
+        // both depend on the current time
+        let without_timestamps = |calendar: &str| {
+            calendar
+                .lines()
+                .filter(|line| !line.starts_with("DTSTAMP:") && !line.starts_with("SEQUENCE:"))
+                .map(str::to_owned)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            without_timestamps(&generated["Fall 2024 - Lecture.ics"]),
+            without_timestamps(&lecture)
+        );
+    }
+
+    #[test]
+    fn test_cancel() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let folder = crate::test_folder("cancel");
+        let progress = Progress::default();
+        progress.cancel();
+
+        let report = write_calendars_with_progress(
+            &folder,
+            &schedule,
+            HashSet::new(),
+            &InstitutionProfile::default(),
+            &GenerateOptions::default(),
+            &progress,
+        );
+        let written = fs::read_dir(&folder).unwrap().count();
+        fs::remove_dir_all(&folder).ok();
+
+        assert!(report.cancelled);
+        assert!(report.files.is_empty());
+        assert_eq!(written, 0);
+    }
+
+    #[test]
+    fn test_reminders() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let folder = crate::test_folder("reminders");
+
+        write_calendars(
+            &folder,
+            &schedule,
+            HashSet::new(),
+            &InstitutionProfile::default(),
+            &GenerateOptions {
+                reminders: [("Lecture".to_owned(), 15)].into(),
+                ..Default::default()
+            },
+        );
+        let lecture = fs::read_to_string(folder.join("Fall 2024 - Lecture.ics")).unwrap();
+        let laboratory = fs::read_to_string(folder.join("Fall 2024 - Laboratory.ics")).unwrap();
+        fs::remove_dir_all(&folder).ok();
+
+        assert!(lecture.contains("X-WR-CALNAME:Fall 2024 – Lecture\r\n"));
+        assert_eq!(
+            lecture
+                .matches("TRIGGER:-PT15M\r\nEND:VALARM\r\nEND:VEVENT")
+                .count(),
+            2
+        );
+        assert!(!laboratory.contains("VALARM"));
+    }
+
+    #[test]
+    fn test_write_event_escapes_text() {
+        let mut schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        schedule.add_custom_events(&[CustomEvent {
+            title: "Work, late; shift".to_owned(),
+            weekday: Weekday::Fri,
+            start_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            end_time: NaiveTime::from_hms_opt(21, 0, 0).unwrap(),
+            start_date: NaiveDate::from_ymd_opt(2024, 9, 4).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2024, 12, 3).unwrap(),
+            location: "Library, desk; 2".to_owned(),
+        }]);
+        let options = GenerateOptions {
+            reminders: [(CUSTOM_EVENT_TYPE.to_owned(), 15)].into(),
+            ..Default::default()
+        };
+        let meetings = meetings(&schedule, &InstitutionProfile::default(), &options);
+        let meeting = meetings
+            .iter()
+            .find(|meeting| meeting.class.is_custom())
+            .unwrap();
+
+        let mut calendar = String::new();
+        write_event(
+            &mut calendar,
+            meeting,
+            &HashSet::new(),
+            &options,
+            "America/Toronto",
+        );
+        assert!(calendar.contains("\nSUMMARY:Work\\, late\\; shift\n"));
+        assert!(calendar.contains("\nLOCATION:Library\\, desk\\; 2\n"));
+        assert!(calendar.contains("\nDESCRIPTION:Work\\, late\\; shift\nTRIGGER:"));
+    }
+
+    #[test]
+    fn test_css_color_name() {
+        assert_eq!(css_color_name([0xff, 0x00, 0x00]), "red");
+        assert_eq!(css_color_name([0x20, 0x80, 0xf0]), "dodgerblue");
+        assert_eq!(hex_color([0x20, 0x80, 0xf0]), "#2080F0");
+    }
+
+    #[test]
+    fn test_fold_calendar() {
+        for name in [
+            "Études françaises – littérature et société".repeat(4),
+            "–".repeat(60),
+            "a".repeat(75),
+            "a".repeat(200),
+        ] {
+            let original = format!("BEGIN:VEVENT\nSUMMARY:{name}\nEND:VEVENT\n");
+            let mut calendar = original.clone();
+            fold_calendar(&mut calendar);
+
+            for line in calendar.lines() {
+                assert!(line.len() <= 75, "line too long: {line:?}");
+            }
+            assert_eq!(calendar.replace("\n ", ""), original);
+        }
+
+        let mut short = "SUMMARY:é\n".to_owned();
+        fold_calendar(&mut short);
+        assert_eq!(short, "SUMMARY:é\n");
+    }
+
+    #[test]
+    fn test_excluded_dates() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let meetings = meetings(
+            &schedule,
+            &InstitutionProfile::default(),
+            &GenerateOptions::default(),
+        );
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        // Monday and Wednesday of reading week, a Tuesday, and a Monday after the term
+        let exdate = HashSet::from([date(10, 14), date(10, 16), date(10, 15), date(12, 9)]);
+
+        assert_eq!(meetings[0].weekdays, [Weekday::Mon]);
+        assert_eq!(meetings[0].excluded_dates(&exdate), [date(10, 14)]);
+        assert_eq!(meetings[1].excluded_dates(&exdate), [date(10, 16)]);
+        assert_eq!(meetings[2].excluded_dates(&exdate), []);
+    }
+
+    #[test]
+    fn test_async_events() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let seminar = &schedule.classes[2];
+        assert!(seminar.date_ranges.is_empty());
+        assert_eq!(seminar.asynchronous.len(), 1);
+
+        let profile = InstitutionProfile::default();
+        assert!(meetings(&schedule, &profile, &GenerateOptions::default())
+            .iter()
+            .all(|meeting| meeting.date_range.is_some()));
+
+        let meetings = meetings(
+            &schedule,
+            &profile,
+            &GenerateOptions {
+                async_events: AsyncEvents::Weekly,
+                ..Default::default()
+            },
+        );
+        let event = &meetings.last().unwrap().event;
+        assert_eq!(
+            event.summary,
+            "Science Co-op Success Program (asynchronous)"
+        );
+        assert_eq!(event.start, "VALUE=DATE:20240904");
+        assert_eq!(event.end, "VALUE=DATE:20240905");
+        assert_eq!(event.rrule, "FREQ=WEEKLY;UNTIL=20241203");
+    }
+
+    #[test]
+    fn test_time_offsets() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let exdate = HashSet::from([NaiveDate::from_ymd_opt(2024, 10, 14).unwrap()]);
+        let options = GenerateOptions {
+            start_offset: 10,
+            end_offset: -5,
+            ..Default::default()
+        };
+        let meetings = meetings(&schedule, &InstitutionProfile::default(), &options);
+        assert_eq!(
+            meetings[0].event.start,
+            "TZID=America/Toronto:20240909T112000"
+        );
+        assert_eq!(
+            meetings[0].event.end,
+            "TZID=America/Toronto:20240909T122500"
+        );
+
+        let mut calendar = String::new();
+        write_event(
+            &mut calendar,
+            &meetings[0],
+            &exdate,
+            &options,
+            "America/Toronto",
+        );
+        assert!(calendar.contains("EXDATE;TZID=America/Toronto:20241014T112000\n"));
+
+        let backwards = GenerateOptions {
+            end_offset: -120,
+            ..Default::default()
+        };
+        let (start, end) = backwards.event_times(&schedule.classes[0].date_ranges[0]);
+        assert_eq!(start, end);
+    }
+
+    #[test]
+    fn test_busy_status() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let profile = InstitutionProfile::default();
+        let event = |options: &GenerateOptions| {
+            let meetings = meetings(&schedule, &profile, options);
+            let mut calendar = String::new();
+            write_event(
+                &mut calendar,
+                &meetings[0],
+                &HashSet::new(),
+                options,
+                "America/Toronto",
+            );
+            calendar
+        };
+
+        let default = event(&GenerateOptions::default());
+        assert!(default.contains("TRANSP:OPAQUE\nX-MICROSOFT-CDO-BUSYSTATUS:BUSY\nCLASS:PUBLIC\n"));
+
+        let shared = event(&GenerateOptions {
+            show_as_free: true,
+            private_events: true,
+            ..Default::default()
+        });
+        assert!(
+            shared.contains("TRANSP:TRANSPARENT\nX-MICROSOFT-CDO-BUSYSTATUS:FREE\nCLASS:PRIVATE\n")
+        );
+    }
+
+    #[test]
+    fn test_meeting_url() {
+        let mut schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let lecture = &mut schedule.classes[0];
+        lecture.date_ranges[0].location = Some("Virtual".to_owned());
+        lecture.meeting_url = Some("https://example.zoom.us/j/123".to_owned());
+        assert!(lecture.is_online());
+        assert_eq!(lecture.date_ranges[0].place(), "Virtual");
+        assert!(!schedule.classes[1].is_online());
+
+        let options = GenerateOptions::default();
+        let meetings = meetings(&schedule, &InstitutionProfile::default(), &options);
+        assert!(meetings[0]
+            .event
+            .description
+            .ends_with("\nJoin: https://example.zoom.us/j/123"));
+        let mut calendar = String::new();
+        write_event(
+            &mut calendar,
+            &meetings[0],
+            &HashSet::new(),
+            &options,
+            "America/Toronto",
+        );
+        assert!(calendar.contains("\nURL:https://example.zoom.us/j/123\nLOCATION:Virtual\n"));
+        assert!(!meetings[2].event.description.contains("Join:"));
+    }
+
+    #[test]
+    fn test_geo() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let options = GenerateOptions::default();
+        let meetings = meetings(&schedule, &InstitutionProfile::default(), &options);
+        assert_eq!(meetings[0].event.location, "Science Building – 1350");
+        assert_eq!(meetings[0].geo, Some([43.9447, -78.8966]));
+
+        let mut calendar = String::new();
+        write_event(
+            &mut calendar,
+            &meetings[0],
+            &HashSet::new(),
+            &options,
+            "America/Toronto",
+        );
+        assert!(calendar.contains(
+            "GEO:43.9447;-78.8966\nX-APPLE-STRUCTURED-LOCATION;VALUE=URI;X-APPLE-RADIUS=70;\
+            X-TITLE=\"Science Building – 1350\":geo:43.9447,-78.8966\n"
+        ));
+    }
+
+    #[test]
+    fn test_instructor_attendees() {
+        let mut schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        schedule.classes[0].instructor =
+            Some("Instructor: Doe, John (Primary), Smith, Alice".to_owned());
+        assert_eq!(
+            schedule.classes[0].instructors(),
+            [
+                Instructor {
+                    name: "Doe, John".to_owned(),
+                    primary: true,
+                },
+                Instructor {
+                    name: "Smith, Alice".to_owned(),
+                    primary: false,
+                },
+            ]
+        );
+
+        let options = GenerateOptions {
+            instructor_attendees: true,
+            instructor_emails: BTreeMap::from([(
+                "Doe, John".to_owned(),
+                "john.doe@example.com".to_owned(),
+            )]),
+            ..Default::default()
+        };
+        let meetings = meetings(&schedule, &InstitutionProfile::default(), &options);
+        let mut calendar = String::new();
+        write_event(
+            &mut calendar,
+            &meetings[0],
+            &HashSet::new(),
+            &options,
+            "America/Toronto",
+        );
+        assert!(calendar.contains(
+            "ORGANIZER;CN=\"John Doe\":mailto:john.doe@example.com\n\
+            ATTENDEE;CN=\"John Doe\";ROLE=CHAIR:mailto:john.doe@example.com\n"
+        ));
+        assert_eq!(calendar.matches("ATTENDEE").count(), 1);
+
+        // without the organizer's email, the instructors are only in the description
+        let options = GenerateOptions {
+            instructor_emails: BTreeMap::from([(
+                "Smith, Alice".to_owned(),
+                "alice.smith@example.com".to_owned(),
+            )]),
+            ..options
+        };
+        let mut calendar = String::new();
+        write_event(
+            &mut calendar,
+            &meetings[0],
+            &HashSet::new(),
+            &options,
+            "America/Toronto",
+        );
+        assert!(!calendar.contains("ORGANIZER"));
+        assert!(!calendar.contains("ATTENDEE"));
+    }
+
+    #[test]
+    fn test_course_titles() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let options = GenerateOptions {
+            course_titles: catalog::parse_csv(
+                "code,title\nCSCI 1200U,Discrete Mathematics for Computer Science\n",
+            )
+            .unwrap(),
+            ..Default::default()
+        };
+        let meetings = meetings(&schedule, &InstitutionProfile::default(), &options);
+        assert_eq!(
+            meetings[0].event.summary,
+            "Discrete Mathematics for Computer Science"
+        );
+        let lab = meetings
+            .iter()
+            .find(|meeting| meeting.class.code == "CSCI 1060U")
+            .unwrap();
+        assert_eq!(lab.event.summary, "Programming Workshop I");
+    }
+
+    #[test]
+    fn test_custom_events() {
+        let mut schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let shift = |title: &str, weekday| CustomEvent {
+            title: title.to_owned(),
+            weekday,
+            start_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            end_time: NaiveTime::from_hms_opt(21, 0, 0).unwrap(),
+            start_date: NaiveDate::from_ymd_opt(2024, 9, 4).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2024, 12, 3).unwrap(),
+            location: "UA 1350".to_owned(),
+        };
+        schedule.add_custom_events(&[
+            shift("Work", Weekday::Mon),
+            shift("Office hours", Weekday::Mon),
+            shift("  ", Weekday::Tue),
+        ]);
+        assert_eq!(schedule.classes.len(), 5);
+
+        let options = GenerateOptions {
+            summary_template: "{code} {type}".to_owned(),
+            ..Default::default()
+        };
+        let meetings = meetings(&schedule, &InstitutionProfile::default(), &options)
+            .into_iter()
+            .filter(|meeting| meeting.class.is_custom())
+            .collect::<Vec<_>>();
+        assert_eq!(meetings.len(), 2);
+        assert_eq!(meetings[0].event.summary, "Work");
+        assert_eq!(meetings[0].event.location, "Science Building – 1350");
+        assert_eq!(meetings[0].event.description, "");
+        assert_ne!(meetings[0].uid, meetings[1].uid);
+        assert_eq!(
+            Grouping::ClassType.calendar_name(meetings[0].class, &schedule.term),
+            "Fall 2024 - Personal"
+        );
+    }
+
+    #[test]
+    fn test_milestones() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let meetings = meetings(
+            &schedule,
+            &InstitutionProfile::default(),
+            &GenerateOptions {
+                milestones: true,
+                ..Default::default()
+            },
+        );
+        let milestones = meetings
+            .iter()
+            .filter(|meeting| meeting.date_range.is_none())
+            .map(|meeting| (meeting.event.summary.as_str(), meeting.event.start.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(milestones.len(), 6);
+        assert_eq!(
+            milestones[..2],
+            [
+                (
+                    "First day of Discrete Mathematics for Comp. Sci",
+                    "VALUE=DATE:20240904"
+                ),
+                (
+                    "Last day of Discrete Mathematics for Comp. Sci",
+                    "VALUE=DATE:20241203"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_swap_days() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let folder = crate::test_folder("swap-days");
+
+        // Tuesday Dec 3 follows a Monday schedule
+        write_calendars(
+            &folder,
+            &schedule,
+            HashSet::new(),
+            &InstitutionProfile::default(),
+            &GenerateOptions {
+                swap_days: vec![SwapDay {
+                    date: NaiveDate::from_ymd_opt(2024, 12, 3).unwrap(),
+                    weekday: Weekday::Mon,
+                }],
+                ..Default::default()
+            },
+        );
+        let lecture = fs::read_to_string(folder.join("Fall 2024 - Lecture.ics")).unwrap();
+        fs::remove_dir_all(&folder).ok();
+
+        let events = lecture.split("BEGIN:VEVENT").skip(1).collect::<Vec<_>>();
+        assert!(events[0].contains("RDATE;TZID=America/Toronto:20241203T111000\r\n"));
+        assert!(!events[1].contains("RDATE"));
+        assert!(!lecture.contains("EXDATE"));
+    }
+
+    #[test]
+    fn test_expand_occurrences() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let folder = crate::test_folder("expand");
+
+        let exdate = HashSet::from([NaiveDate::from_ymd_opt(2024, 10, 14).unwrap()]);
+        write_calendars(
+            &folder,
+            &schedule,
+            exdate.clone(),
+            &InstitutionProfile::default(),
+            &GenerateOptions {
+                expand_occurrences: true,
+                ..Default::default()
+            },
+        );
+        let lecture = fs::read_to_string(folder.join("Fall 2024 - Lecture.ics")).unwrap();
+        fs::remove_dir_all(&folder).ok();
+
+        let options = GenerateOptions::default();
+        let occurrences = meetings(&schedule, &InstitutionProfile::default(), &options)
+            .iter()
+            .filter(|meeting| meeting.class.class_type == "Lecture")
+            .map(|meeting| meeting.occurrences(&exdate, &[]).len())
+            .sum::<usize>();
+        assert_eq!(lecture.matches("BEGIN:VEVENT").count(), occurrences);
+        let events = lecture.split("BEGIN:VEVENT").skip(1).collect::<String>();
+        assert!(!events.contains("RRULE"));
+        assert!(!events.contains("EXDATE"));
+        assert!(!lecture.contains(":20241014T"));
+        assert!(lecture.contains("DTSTART;TZID=America/Toronto:20241016T111000\r\n"));
+        assert!(lecture.contains("-20241016@mycampus-calendar-rs"));
+    }
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(
+            escape_text("Zoom: https://example.com/j/1?pwd=a;b\nDoe, John"),
+            "Zoom: https://example.com/j/1?pwd=a\\;b\\nDoe\\, John"
+        );
+    }
+
+    #[test]
+    fn test_parse_registration_page() {
+        let result =
+            Parser::new().parse_data(include_str!("../tests/data/registration_information.txt"));
+        assert!(matches!(result, Err(ParseError::RegistrationPage)));
+    }
+
+    #[test]
+    fn test_regex_missing_groups() {
+        let mut profile = InstitutionProfile::default();
+        // fewer groups than the parser uses
+        profile.regexes.course_name = r"^(.+?) \| ".to_owned();
+        let schedule = Parser::from_profile(&profile)
+            .unwrap()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        assert!(schedule.classes.is_empty());
+        assert!(schedule
+            .failures
+            .iter()
+            .all(|failure| matches!(failure.error, ParseError::RegexGroup("course name", 2, _))));
+
+        // optional groups that don't capture anything on the broken time lines
+        profile.regexes = Default::default();
+        profile.regexes.time = r"^\s*(\d+:\d+ [AP]M)?(?: - (\d+:\d+ [AP]M))?".to_owned();
+        let schedule = Parser::from_profile(&profile)
+            .unwrap()
+            .parse_data(&include_str!("../tests/data/chromium.txt").replace("M - ", "M to "))
+            .unwrap();
+        assert!(matches!(
+            schedule.failures[0].error,
+            ParseError::RegexGroup("time", 2, _)
+        ));
+
+        // no class type group at all
+        profile.regexes = Default::default();
+        profile.regexes.message = r"\| Schedule Type: .+? \|".to_owned();
+        let schedule = Parser::from_profile(&profile)
+            .unwrap()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        assert!(schedule.classes.is_empty());
+        assert!(!schedule.failures.is_empty());
+        assert!(schedule
+            .failures
+            .iter()
+            .all(|failure| matches!(failure.error, ParseError::RegexGroup("message", 1, _))));
+    }
+
+    #[test]
+    fn test_parse_broken_classes_with_summary() {
+        // Schedule Details has the summary table too, so broken classes aren't mistaken for the
+        // Registration Information page
+        let data = include_str!("../tests/data/chromium.txt")
+            .replace("M - ", "M to ")
+            .replace("   None", "   Online");
+        let schedule = Parser::new().parse_data(&data).unwrap();
+        assert!(schedule.classes.is_empty());
+        assert!(!schedule.failures.is_empty());
+    }
+
+    #[test]
+    fn test_parse_with_summary() {
+        // not in the subject map, so this only works with the summary table
+        let data = include_str!("../tests/data/mobile.txt")
+            .replace("Science Co-op 0999U", "Science Co-op Prep 0999U");
+
+        let schedule = Parser::new().parse_data(&data).unwrap();
+        assert_eq!(schedule.classes[2].code, "SCPR 0999U");
+        assert_eq!(schedule.warnings.len(), 1);
+
+        let schedule = Parser::new()
+            .parse_data_with_summary(
+                &data,
+                include_str!("../tests/data/registration_information.txt"),
+            )
+            .unwrap();
+
+        assert!(schedule.failures.is_empty());
+        assert_eq!(schedule.classes[2].code, "SCCO 0999U");
+        assert_eq!(
+            schedule
+                .classes
+                .iter()
+                .map(|c| c.credit_hours)
+                .collect::<Vec<_>>(),
+            vec![Some(3.0), Some(3.0), Some(0.0)]
+        );
+    }
+
+    #[test]
+    fn test_part_of_term() {
+        let summary = include_str!("../tests/data/registration_information.txt")
+            .replace("\tLecture\t", "\tLecture\tFirst 14 Weeks\t")
+            .replace("\tLaboratory\t", "\tLaboratory\tFirst 7 Weeks\t")
+            .replace("\tSeminar\t", "\tSeminar\tSecond 7 Weeks\t");
+        let schedule = Parser::new()
+            .parse_data_with_summary(include_str!("../tests/data/chromium.txt"), &summary)
+            .unwrap();
+
+        assert!(schedule.failures.is_empty());
+        // the seminar has no meetings to check
+        assert_eq!(schedule.warnings.len(), 1);
+        assert!(schedule.warnings[0].starts_with(
+            "Programming Workshop I is only in the First 7 Weeks of the term, but its meetings go \
+            until 2024-12-03."
+        ));
+    }
+
+    #[test]
+    fn test_skip_zero_credit() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let mut options = GenerateOptions {
+            async_events: AsyncEvents::FirstDay,
+            ..Default::default()
+        };
+        let codes = |options: &GenerateOptions| {
+            meetings(&schedule, &InstitutionProfile::default(), options)
+                .iter()
+                .map(|meeting| meeting.class.code.clone())
+                .collect::<BTreeSet<_>>()
+        };
+
+        assert!(codes(&options).contains("SCCO 0999U"));
+        options.skip_zero_credit = true;
+        assert_eq!(
+            codes(&options),
+            BTreeSet::from(["CSCI 1060U".to_owned(), "CSCI 1200U".to_owned()])
+        );
+    }
+
+    #[test]
+    fn test_guess_short_subject() {
+        for (subject, want) in [
+            ("Biology", Some("BIOL")),
+            ("Computer Science", Some("CSCI")),
+            ("Science Co-op Prep", Some("SCPR")),
+            ("History of the Ancient World", Some("HAWO")),
+            ("Arts", Some("ARTS")),
+            ("X", Some("X")),
+            ("123", None),
+        ] {
+            assert_eq!(guess_short_subject(subject).as_deref(), want, "{subject}");
+        }
+    }
+
+    fn do_test(data: &str, want_format: &str) {
+        let schedule = Parser::new().parse_data(data).unwrap();
+
+        assert_eq!(schedule.format.unwrap().name, want_format);
+        assert!(schedule.failures.is_empty(), "{:?}", schedule.failures);
+        assert_eq!(
+            schedule
+                .classes
+                .iter()
+                .map(|c| (&*c.code, &*c.class_type, c.date_ranges.len()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("CSCI 1200U", "Lecture", 2),
+                ("CSCI 1060U", "Laboratory", 1),
+                ("SCCO 0999U", "Seminar", 0),
+            ]
+        );
+
+        let lab = &schedule.classes[1].date_ranges[0];
+        assert_eq!(lab.weekday, Weekday::Thu);
+        assert_eq!(lab.start_time, NaiveTime::from_hms_opt(14, 10, 0).unwrap());
+        assert_eq!(lab.end_time, NaiveTime::from_hms_opt(17, 0, 0).unwrap());
+        assert_eq!(lab.room.as_deref(), Some("SIRC 3110"));
+    }
 }
@@ -0,0 +1,203 @@
+//! Importing the printable PDF schedule, for students who only have that instead of the page.
+//!
+//! Its text is laid out differently from the copied page, so it's read with the profile's
+//! [`PdfRegexes`](crate::profile::PdfRegexes) instead of the regular [`Parser`](crate::Parser).
+
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use regex::{Captures, Regex};
+use thiserror::Error;
+
+use crate::{
+    parser::{is_tba, merge_biweekly, ClassFailure, DateRangeKind},
+    term, Class, DateRange, InstitutionProfile, ParseError, Schedule,
+};
+
+#[derive(Debug, Error)]
+pub enum PdfError {
+    #[error("Failed to read the PDF: {0}")]
+    Extract(#[from] pdf_extract::OutputError),
+    #[error("{0}")]
+    Parse(#[from] ParseError),
+    #[error("No classes were found in the PDF")]
+    NoClasses,
+}
+
+/// Reads the classes from the contents of a printable PDF schedule.
+pub fn parse_pdf(pdf: &[u8], profile: &InstitutionProfile) -> Result<Schedule, PdfError> {
+    let text = pdf_extract::extract_text_from_mem(pdf)?;
+    let schedule = PdfParser::from_profile(profile)?.parse_text(&text);
+    if schedule.classes.is_empty() && schedule.failures.is_empty() {
+        return Err(PdfError::NoClasses);
+    }
+    Ok(schedule)
+}
+
+/// Parser for the text extracted from a printable PDF schedule.
+pub struct PdfParser {
+    term_re: Regex,
+    section_re: Regex,
+    instructor_re: Regex,
+    meeting_re: Regex,
+    asynchronous_re: Regex,
+    date_format: String,
+    time_format: String,
+}
+
+impl PdfParser {
+    pub fn from_profile(profile: &InstitutionProfile) -> Result<Self, ParseError> {
+        let regexes = &profile.pdf_regexes;
+        Ok(Self {
+            term_re: Regex::new(&regexes.term)?,
+            section_re: Regex::new(&regexes.section)?,
+            instructor_re: Regex::new(&regexes.instructor)?,
+            meeting_re: Regex::new(&regexes.meeting)?,
+            asynchronous_re: Regex::new(&regexes.asynchronous)?,
+            date_format: profile.date_format.clone(),
+            time_format: profile.time_format.clone(),
+        })
+    }
+
+    /// Each class starts with its title, followed by a section line. The lines after it up to the
+    /// next class are its instructor and meetings; anything else, like page headers, is skipped.
+    pub fn parse_text(&self, text: &str) -> Schedule {
+        let lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+
+        let mut schedule = Schedule::default();
+        // the title and class being read, or why it couldn't be
+        let mut class: Option<(String, Result<Class, ParseError>)> = None;
+        for (i, line) in lines.iter().enumerate() {
+            if let Some(caps) = self.term_re.captures(line) {
+                schedule.term = term::normalize(&caps["term"]);
+            } else if let Some(caps) = self.section_re.captures(line) {
+                finish_class(&mut schedule, class.take());
+                let name = i.checked_sub(1).map_or("", |i| lines[i]).to_owned();
+                class = Some((name.clone(), Ok(section_class(name, &caps))));
+            } else if let Some((name, Ok(current))) = &mut class {
+                if let Err(error) = self.parse_class_line(current, line) {
+                    class = Some((name.clone(), Err(error)));
+                }
+            }
+        }
+        finish_class(&mut schedule, class);
+        schedule
+    }
+
+    /// Adds the instructor or meeting on `line` to `class`, if it's either.
+    fn parse_class_line(&self, class: &mut Class, line: &str) -> Result<(), ParseError> {
+        if self.instructor_re.is_match(line) {
+            class.instructor = Some(line.to_owned());
+        } else if let Some(caps) = self.meeting_re.captures(line) {
+            let weekday = caps["weekday"]
+                .parse::<Weekday>()
+                .map_err(|_| ParseError::Weekday(line.to_owned()))?;
+            class.date_ranges.push(DateRange {
+                start_date: self.parse_date(&caps["start_date"])?,
+                end_date: self.parse_date(&caps["end_date"])?,
+                start_time: self.parse_time(&caps["start_time"])?,
+                end_time: self.parse_time(&caps["end_time"])?,
+                weekday,
+                location: assigned(&caps["location"]),
+                building: assigned(&caps["building"]),
+                room: assigned(&caps["room"]),
+                biweekly: false,
+                kind: DateRangeKind::Weekly,
+            });
+        } else if let Some(caps) = self.asynchronous_re.captures(line) {
+            class.asynchronous.push((
+                self.parse_date(&caps["start_date"])?,
+                self.parse_date(&caps["end_date"])?,
+            ));
+        }
+        Ok(())
+    }
+
+    fn parse_date(&self, date: &str) -> Result<NaiveDate, ParseError> {
+        NaiveDate::parse_from_str(date, &self.date_format)
+            .map_err(|e| ParseError::Date(date.to_owned(), e))
+    }
+
+    fn parse_time(&self, time: &str) -> Result<NaiveTime, ParseError> {
+        NaiveTime::parse_from_str(time, &self.time_format)
+            .map_err(|e| ParseError::Time(time.to_owned(), e))
+    }
+}
+
+/// `value`, unless it's a placeholder for a location that hasn't been assigned.
+fn assigned(value: &str) -> Option<String> {
+    (!is_tba(value.trim())).then(|| value.trim().to_owned())
+}
+
+/// A class without meetings yet, from its section line.
+fn section_class(name: String, caps: &Captures) -> Class {
+    Class {
+        name,
+        code: format!("{} {}", &caps["subject"], &caps["number"]),
+        date_ranges: Vec::new(),
+        instructor: None,
+        crn: format!("CRN: {}", &caps["crn"]),
+        class_type: caps["class_type"].to_owned(),
+        credit_hours: caps.name("hours").and_then(|h| h.as_str().parse().ok()),
+        asynchronous: Vec::new(),
+        term: String::new(),
+        meeting_url: None,
+    }
+}
+
+fn finish_class(schedule: &mut Schedule, class: Option<(String, Result<Class, ParseError>)>) {
+    match class {
+        Some((_, Ok(mut class))) => {
+            class.date_ranges = merge_biweekly(class.date_ranges);
+            schedule.classes.push(class);
+        }
+        Some((heading, Err(error))) => schedule.failures.push(ClassFailure {
+            heading,
+            error,
+            line: None,
+        }),
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    #[test]
+    fn test_parse_pdf() {
+        let profile = InstitutionProfile::default();
+        let schedule = parse_pdf(include_bytes!("../tests/data/schedule.pdf"), &profile).unwrap();
+        let want = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+
+        assert!(schedule.failures.is_empty());
+        assert_eq!(schedule.term, want.term);
+        assert_eq!(
+            serde_json::to_value(&schedule.classes).unwrap(),
+            serde_json::to_value(&want.classes).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_bad_meeting() {
+        let parser = PdfParser::from_profile(&InstitutionProfile::default()).unwrap();
+        let schedule = parser.parse_text(
+            "Programming Workshop I\n\
+            CSCI 1060U, Section 002 | CRN: 40002 | Laboratory | 3 Credit Hours\n\
+            Thursday 02:10 PM - 05:00 PM 13/45/2024 - 12/03/2024 North Oshawa | SIRC | SIRC 3110\n\
+            Science Co-op Success Program\n\
+            SCCO 0999U, Section 001 | CRN: 40003 | Seminar | 0 Credit Hours\n\
+            No scheduled meetings 09/04/2024 - 12/03/2024 Online",
+        );
+
+        assert_eq!(schedule.failures.len(), 1);
+        assert_eq!(schedule.failures[0].heading, "Programming Workshop I");
+        assert_eq!(schedule.classes.len(), 1);
+        assert_eq!(schedule.classes[0].code, "SCCO 0999U");
+    }
+}
@@ -0,0 +1,195 @@
+use chrono::{NaiveTime, Timelike, Weekday};
+use eframe::egui::{self, ComboBox, DragValue, TextEdit};
+use egui_extras::{Column, TableBuilder};
+use mycampus_calendar_rs::parser::{Class, Schedule, TBA};
+
+use crate::i18n::{tr, weekday_name};
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Message keys of the column titles, and their widths.
+const COLUMNS: [(&str, f32); 13] = [
+    ("", 20.0),
+    ("preview-name", 180.0),
+    ("preview-code", 80.0),
+    ("preview-crn", 80.0),
+    ("preview-type", 80.0),
+    ("preview-instructor", 140.0),
+    ("preview-meeting-url", 140.0),
+    ("preview-weekday", 70.0),
+    ("preview-start", 70.0),
+    ("preview-end", 70.0),
+    ("preview-biweekly", 60.0),
+    ("preview-building", 140.0),
+    ("preview-room", 80.0),
+];
+
+/// Editable table of every parsed meeting, shown before the calendars are generated.
+///
+/// `selected` has one entry per class, controlling whether it's included in the calendars.
+pub fn preview_table(ui: &mut egui::Ui, schedule: &mut Schedule, selected: &mut [bool]) {
+    let mut table = TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .max_scroll_height(250.0);
+
+    for (_, width) in COLUMNS {
+        table = table.column(Column::initial(width).at_least(40.0).clip(true));
+    }
+
+    table
+        .header(20.0, |mut header| {
+            for (key, _) in COLUMNS {
+                header.col(|ui| {
+                    if !key.is_empty() {
+                        ui.strong(tr(key));
+                    }
+                });
+            }
+        })
+        .body(|mut body| {
+            for (i, (class, selected)) in schedule
+                .classes
+                .iter_mut()
+                .zip(selected.iter_mut())
+                .enumerate()
+            {
+                let online = class.is_online();
+                let Class {
+                    name,
+                    code,
+                    date_ranges,
+                    instructor,
+                    crn,
+                    class_type,
+                    meeting_url,
+                    ..
+                } = class;
+
+                // classes without any meetings (eg. asynchronous) still get a row so they can be edited
+                let rows = date_ranges.len().max(1);
+                for j in 0..rows {
+                    body.row(22.0, |mut row| {
+                        row.col(|ui| {
+                            if j == 0 {
+                                ui.checkbox(selected, "")
+                                    .on_hover_text(tr("preview-selected-hover"));
+                            }
+                        });
+
+                        for field in [&mut *name, code, crn, class_type] {
+                            row.col(|ui| {
+                                if j == 0 {
+                                    ui.add(
+                                        TextEdit::singleline(field).desired_width(f32::INFINITY),
+                                    );
+                                }
+                            });
+                        }
+                        row.col(|ui| {
+                            if j == 0 {
+                                optional_text_edit(ui, instructor, TBA);
+                            }
+                        });
+                        row.col(|ui| {
+                            if j == 0 {
+                                optional_text_edit(
+                                    ui,
+                                    meeting_url,
+                                    if online { "https://" } else { "" },
+                                )
+                                .on_hover_text(tr("preview-meeting-url-hover"));
+                            }
+                        });
+
+                        let Some(date_range) = date_ranges.get_mut(j) else {
+                            for _ in 0..6 {
+                                row.col(|_| ());
+                            }
+                            return;
+                        };
+
+                        row.col(|ui| {
+                            ComboBox::from_id_source(("weekday", i, j))
+                                .selected_text(weekday_name(date_range.weekday))
+                                .width(ui.available_width())
+                                .show_ui(ui, |ui| {
+                                    for weekday in WEEKDAYS {
+                                        ui.selectable_value(
+                                            &mut date_range.weekday,
+                                            weekday,
+                                            weekday_name(weekday),
+                                        );
+                                    }
+                                });
+                        });
+                        row.col(|ui| {
+                            time_picker(ui, &mut date_range.start_time);
+                        });
+                        row.col(|ui| {
+                            time_picker(ui, &mut date_range.end_time);
+                        });
+                        row.col(|ui| {
+                            ui.checkbox(&mut date_range.biweekly, "")
+                                .on_hover_text(tr("preview-biweekly-hover"));
+                        });
+                        for field in [&mut date_range.building, &mut date_range.room] {
+                            row.col(|ui| {
+                                optional_text_edit(ui, field, TBA);
+                            });
+                        }
+                    });
+                }
+            }
+        });
+}
+
+/// A text field for a value that may not have been assigned yet, which is left empty until it
+/// has.
+fn optional_text_edit(ui: &mut egui::Ui, value: &mut Option<String>, hint: &str) -> egui::Response {
+    let mut text = value.clone().unwrap_or_default();
+    let response = ui.add(
+        TextEdit::singleline(&mut text)
+            .hint_text(hint)
+            .desired_width(f32::INFINITY),
+    );
+    if response.changed() {
+        *value = (!text.is_empty()).then_some(text);
+    }
+    response
+}
+
+fn time_picker(ui: &mut egui::Ui, time: &mut NaiveTime) -> egui::Response {
+    let mut hour = time.hour();
+    let mut minute = time.minute();
+
+    let response = ui
+        .horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 2.0;
+            let hour_response = ui.add(DragValue::new(&mut hour).range(0..=23));
+            ui.label(":");
+            let minute_response = ui.add(
+                DragValue::new(&mut minute)
+                    .range(0..=59)
+                    .custom_formatter(|n, _| format!("{n:02}")),
+            );
+            hour_response | minute_response
+        })
+        .inner;
+
+    if response.changed() {
+        if let Some(new_time) = NaiveTime::from_hms_opt(hour, minute, 0) {
+            *time = new_time;
+        }
+    }
+
+    response
+}
@@ -0,0 +1,509 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use chrono::NaiveDate;
+use indoc::indoc;
+use phf::phf_map;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{http::percent_encode, DateRange};
+
+static SUBJECTS: phf::Map<&'static str, &'static str> = phf_map! {
+    "Academic Learning and Success" => "ALSU",
+    "Biology" => "BIOL",
+    "Business" => "BUSI",
+    "Chemistry" => "CHEM",
+    "Communications" => "COMM",
+    "Computer Science" => "CSCI",
+    "Criminology and Justice" => "CRMN",
+    "Curriculum Studies" => "CURS",
+    "Economics" => "ECON",
+    "Education" => "EDUC",
+    "Educational Studies and Digital Technology" => "AEDT",
+    "Electrical Engineering" => "ELEE",
+    "Energy Systems and Nuclear Science" => "ESNS",
+    "Engineering" => "ENGR",
+    "Environmental Science" => "ENVS",
+    "Forensic Science" => "FSCI",
+    "Health Science" => "HLSC",
+    "Indigenous Studies" => "INDG",
+    "Information Technology" => "INFR",
+    "Integrated Mathematics and Computer Science" => "IMCS",
+    "Kinesiology" => "KINE",
+    "Legal Studies" => "LGLS",
+    "Liberal Studies" => "LBAT",
+    "Manufacturing Engineering" => "MANE",
+    "Mathematics" => "MATH",
+    "Mechanical Engineering" => "MECE",
+    "Mechatronics Engineering" => "METE",
+    "Medical Laboratory Science" => "MLSC",
+    "Neuroscience" => "NSCI",
+    "Nuclear" => "NUCL",
+    "Nursing" => "NURS",
+    "Physics" => "PHY",
+    "Political Science" => "POSC",
+    "Psychology" => "PSYC",
+    "Radiation Science" => "RADI",
+    "Science" => "SCIE",
+    "Science Co-op" => "SCCO",
+    "Science Co-op Work Term" => "SCCO",
+    "Social Science" => "SSCI",
+    "Sociology" => "SOCI",
+    "Software Engineering" => "SOFE",
+    "Statistics" => "STAT",
+    "Sustainable Energy Systems" => "ENSY",
+};
+
+/// Ontario statutory holidays during the fall and winter terms, and Ontario Tech's study weeks.
+/// Check these against https://ontariotechu.ca/current-students/academics/important-dates-and-deadlines.php
+/// when adding a new term.
+const DATE_PRESETS: [(&str, &str, &str); 12] = [
+    ("Fall 2024 study week", "2024-10-14", "2024-10-18"),
+    ("Thanksgiving 2024", "2024-10-14", "2024-10-14"),
+    ("Winter 2025 study week", "2025-02-17", "2025-02-21"),
+    ("Good Friday 2025", "2025-04-18", "2025-04-18"),
+    ("Fall 2025 study week", "2025-10-13", "2025-10-17"),
+    ("Thanksgiving 2025", "2025-10-13", "2025-10-13"),
+    ("Winter 2026 study week", "2026-02-16", "2026-02-20"),
+    ("Good Friday 2026", "2026-04-03", "2026-04-03"),
+    ("Fall 2026 study week", "2026-10-12", "2026-10-16"),
+    ("Thanksgiving 2026", "2026-10-12", "2026-10-12"),
+    ("Winter 2027 study week", "2027-02-15", "2027-02-19"),
+    ("Good Friday 2027", "2027-03-26", "2027-03-26"),
+];
+
+/// Building codes used in room numbers, as `(code, name, [latitude, longitude])`. They're all on
+/// the North Oshawa campus, and the coordinates are roughly the middle of each building.
+const BUILDINGS: [(&str, &str, [f64; 2]); 6] = [
+    (
+        "ACE",
+        "Automotive Centre of Excellence",
+        [43.9459, -78.8932],
+    ),
+    ("ERC", "Energy Research Centre", [43.9457, -78.8968]),
+    ("SHA", "Shawenjigewining Hall", [43.9462, -78.8994]),
+    (
+        "SIRC",
+        "Software and Informatics Research Centre",
+        [43.9482, -78.8989],
+    ),
+    ("UA", "Science Building", [43.9447, -78.8966]),
+    (
+        "UB",
+        "Business and Information Technology Building",
+        [43.9452, -78.8957],
+    ),
+];
+
+fn date(s: &str) -> NaiveDate {
+    s.parse().expect("date presets should be valid")
+}
+
+/// Everything that's specific to one school's Banner deployment: how to recognize and parse
+/// the copied schedule page, and which timezone the classes are in.
+///
+/// The default profile is for MyOntarioTech. Other schools can provide their own as a TOML file
+/// (see `profiles/myontariotech.toml` for a template); any missing fields use the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InstitutionProfile {
+    pub name: String,
+    /// Page to open when fetching the schedule with a browser. The user signs in and opens their
+    /// schedule from there.
+    pub login_url: String,
+    /// Base URL of the Banner 9 registration app, used to fetch the schedule as JSON with a
+    /// session cookie.
+    pub banner_url: String,
+    /// IANA timezone name used for `TZID` parameters.
+    pub timezone: String,
+    /// Raw `VTIMEZONE` component to embed in each calendar. It's only used if its `TZID` matches
+    /// [`timezone`](Self::timezone), so profiles for other timezones can leave it out.
+    pub vtimezone: String,
+    pub markers: Markers,
+    pub regexes: Regexes,
+    /// Regexes for the text of the printable PDF schedule, which is laid out differently.
+    pub pdf_regexes: PdfRegexes,
+    pub date_format: String,
+    pub time_format: String,
+    /// Long subject name → short subject code, eg. "Computer Science" → "CSCI".
+    pub subjects: BTreeMap<String, String>,
+    /// Building code → building, for expanding room numbers like "ERC 1040" in event locations.
+    pub buildings: BTreeMap<String, Building>,
+    /// Campus name from the schedule → name to use in events, eg. "North Oshawa" → "North
+    /// Campus".
+    pub campus_aliases: BTreeMap<String, String>,
+    /// Holidays and study weeks that can be added to the excluded dates with one click.
+    pub date_presets: Vec<DatePreset>,
+}
+
+/// A building that room numbers refer to by its code.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Building {
+    pub name: String,
+    /// The campus the building is on, used when the schedule doesn't say.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub campus: Option<String>,
+    /// Latitude and longitude, so calendar apps can show a map.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geo: Option<[f64; 2]>,
+}
+
+/// A named date range without classes, eg. a reading week.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatePreset {
+    pub name: String,
+    pub start: NaiveDate,
+    /// Same as `start` for a single day.
+    pub end: NaiveDate,
+}
+
+/// Lines used to find where the schedule starts in the copied page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Markers {
+    /// Schedule tab line when copied from a Chromium-based browser.
+    pub chromium_schedule: String,
+    /// Schedule tab line when copied from Firefox.
+    pub firefox_schedule: String,
+    /// Prefix of the line right before the first course.
+    pub schedule_start: String,
+    /// Prefix of a line that only appears on the Registration Information page, which users
+    /// sometimes copy instead of Schedule Details.
+    pub registration_page: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Regexes {
+    /// A row of the registration summary table. Uses the `subject`, `crn`, and optional `hours`
+    /// and `part_of_term` named groups, or the first two groups as the subject and CRN.
+    pub course_summary: String,
+    pub course_name: String,
+    pub date: String,
+    /// The start and end times, then the optional `location`, `building` and `room` named
+    /// groups, which are left empty when the room hasn't been assigned yet.
+    pub time: String,
+    /// The line with the schedule type, in the `class_type` named group, or else the first group.
+    pub message: String,
+    pub crn: String,
+    /// The instructor line, which is left out when no instructor has been assigned.
+    pub instructor: String,
+}
+
+/// Lines of the printable PDF schedule. Dates and times use the profile's formats.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PdfRegexes {
+    /// The term, in the `term` group.
+    pub term: String,
+    /// The line after the course title, with the `subject`, `number`, `crn`, `class_type` and
+    /// optional `hours` named groups.
+    pub section: String,
+    pub instructor: String,
+    /// A weekly meeting, with the `weekday`, `start_time`, `end_time`, `start_date`, `end_date`,
+    /// `location`, `building` and `room` named groups.
+    pub meeting: String,
+    /// A section without scheduled meetings, with the `start_date` and `end_date` named groups.
+    pub asynchronous: String,
+}
+
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    #[error("Failed to read profile: {0}")]
+    Io(#[from] io::Error),
+    #[error("Failed to parse profile: {0}")]
+    Toml(#[from] toml::de::Error),
+}
+
+impl Default for InstitutionProfile {
+    fn default() -> Self {
+        Self {
+            name: "MyOntarioTech".to_owned(),
+            login_url: "https://ontariotechu.ca/mycampus/".to_owned(),
+            banner_url: "https://ssp.mycampus.ca/StudentRegistrationSsb".to_owned(),
+            timezone: "America/Toronto".to_owned(),
+            vtimezone: indoc! {"
+                BEGIN:VTIMEZONE
+                TZID:America/Toronto
+                LAST-MODIFIED:20201011T015911Z
+                TZURL:http://tzurl.org/zoneinfo-outlook/America/Toronto
+                X-LIC-LOCATION:America/Toronto
+                BEGIN:DAYLIGHT
+                TZNAME:EDT
+                TZOFFSETFROM:-0500
+                TZOFFSETTO:-0400
+                DTSTART:19700308T020000
+                RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU
+                END:DAYLIGHT
+                BEGIN:STANDARD
+                TZNAME:EST
+                TZOFFSETFROM:-0400
+                TZOFFSETTO:-0500
+                DTSTART:19701101T020000
+                RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU
+                END:STANDARD
+                END:VTIMEZONE
+            "}
+            .to_owned(),
+            markers: Markers::default(),
+            regexes: Regexes::default(),
+            pdf_regexes: PdfRegexes::default(),
+            date_format: "%m/%d/%Y".to_owned(),
+            time_format: "%I:%M %p".to_owned(),
+            subjects: SUBJECTS
+                .entries()
+                .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+                .collect(),
+            buildings: BUILDINGS
+                .iter()
+                .map(|&(code, name, geo)| {
+                    (
+                        code.to_owned(),
+                        Building {
+                            name: name.to_owned(),
+                            campus: Some("North Oshawa".to_owned()),
+                            geo: Some(geo),
+                        },
+                    )
+                })
+                .collect(),
+            campus_aliases: BTreeMap::new(),
+            date_presets: DATE_PRESETS
+                .iter()
+                .map(|&(name, start, end)| DatePreset {
+                    name: name.to_owned(),
+                    start: date(start),
+                    end: date(end),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Default for Markers {
+    fn default() -> Self {
+        Self {
+            chromium_schedule: "Schedule".to_owned(),
+            firefox_schedule: "    Schedule".to_owned(),
+            schedule_start: "Class Schedule for ".to_owned(),
+            registration_page: "Registration Information".to_owned(),
+        }
+    }
+}
+
+impl Default for Regexes {
+    fn default() -> Self {
+        Self {
+            course_summary: r"^.+?\t(?P<subject>[A-Z]{3,4}) \d{4}U, [^\t]+\t(?:(?P<hours>\d+(?:\.\d+)?)\t)?(?P<crn>\d{5})(?:.*?\t(?P<part_of_term>(?:First|Second|Last) \d+ Weeks?|Full Term)(?:\t|$))?".to_owned(),
+            course_name: r"^(.+?) \| (.+?) (\d+U)".to_owned(),
+            date: r"^([\d/]+) -- ([\d/]+)(?:\s+(\w+))?".to_owned(),
+            time: r"^\s*(\d+:\d+(?: [AaPp][Mm])?) - (\d+:\d+(?: [AaPp][Mm])?)(?:.*?Location: (?P<location>.+?)(?: Building: (?P<building>.+?) Room: (?P<room>.+))?$|.*)".to_owned(),
+            message: r"\| Schedule Type: (?P<class_type>.+?) \|".to_owned(),
+            crn: r"^CRN: (\d{5})".to_owned(),
+            instructor: r"^Instructor: ".to_owned(),
+        }
+    }
+}
+
+impl Default for PdfRegexes {
+    fn default() -> Self {
+        Self {
+            term: r"^Term: (?P<term>.+)".to_owned(),
+            section: r"^(?P<subject>[A-Z]{3,4}) (?P<number>\d{4}U), Section \w+ \| CRN: (?P<crn>\d{5}) \| (?P<class_type>.+?) \| (?:(?P<hours>\d+(?:\.\d+)?) Credit Hours)?".to_owned(),
+            instructor: r"^Instructor: ".to_owned(),
+            meeting: r"^(?P<weekday>\w+day) (?P<start_time>\d+:\d+ \w+) - (?P<end_time>\d+:\d+ \w+) (?P<start_date>[\d/]+) - (?P<end_date>[\d/]+) (?P<location>.+?) \| (?P<building>.+?) \| (?P<room>.+)".to_owned(),
+            asynchronous: r"^No scheduled meetings (?P<start_date>[\d/]+) - (?P<end_date>[\d/]+)".to_owned(),
+        }
+    }
+}
+
+impl InstitutionProfile {
+    pub fn from_toml(s: &str) -> Result<Self, ProfileError> {
+        Ok(toml::from_str(s)?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ProfileError> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// The `VTIMEZONE` component to embed, if it matches the profile's timezone.
+    pub fn vtimezone(&self) -> Option<&str> {
+        self.vtimezone
+            .lines()
+            .any(|l| l.strip_prefix("TZID:") == Some(&self.timezone))
+            .then_some(&*self.vtimezone)
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).expect("profile should always be serializable")
+    }
+
+    /// The building a room like "ERC 1040" is in, and the room number without the code.
+    pub fn building<'a>(&self, room: &'a str) -> Option<(&Building, &'a str)> {
+        let (code, number) = room.trim().split_once(' ')?;
+        Some((self.buildings.get(code)?, number.trim()))
+    }
+
+    /// The event location for `date_range`, with the building code in its room expanded, eg.
+    /// "Energy Research Centre – 1040".
+    pub fn location(&self, date_range: &DateRange) -> String {
+        match date_range
+            .room
+            .as_deref()
+            .and_then(|room| self.building(room))
+        {
+            Some((building, number)) if !date_range.is_online() => {
+                format!("{} – {number}", building.name)
+            }
+            _ => date_range.place(),
+        }
+    }
+
+    /// The latitude and longitude of the building `date_range` is in, if it's known.
+    pub fn geo(&self, date_range: &DateRange) -> Option<[f64; 2]> {
+        if date_range.is_online() {
+            return None;
+        }
+        let (building, _) = self.building(date_range.room.as_deref()?)?;
+        building.geo
+    }
+
+    /// A Google Maps search for the building `date_range` is in, by its coordinates if they're
+    /// known, or else its name and campus.
+    pub fn maps_url(&self, date_range: &DateRange) -> Option<String> {
+        if date_range.is_online() {
+            return None;
+        }
+        let query = match self.geo(date_range) {
+            Some([latitude, longitude]) => format!("{latitude},{longitude}"),
+            None => {
+                let building = date_range
+                    .room
+                    .as_deref()
+                    .and_then(|room| self.building(room))
+                    .map(|(building, _)| &building.name)
+                    .or(date_range.building.as_ref())?;
+                match self.campus(date_range) {
+                    Some(campus) => format!("{building}, {campus}"),
+                    None => building.clone(),
+                }
+            }
+        };
+        Some(format!(
+            "https://www.google.com/maps/search/?api=1&query={}",
+            percent_encode(&query)
+        ))
+    }
+
+    /// The campus `date_range` is on, from the schedule or else its building, renamed by
+    /// [`campus_aliases`](Self::campus_aliases).
+    pub fn campus(&self, date_range: &DateRange) -> Option<String> {
+        let campus = date_range.location.as_ref().or_else(|| {
+            let (building, _) = self.building(date_range.room.as_deref()?)?;
+            building.campus.as_ref()
+        })?;
+        Some(self.campus_aliases.get(campus).unwrap_or(campus).clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::DateRangeKind;
+
+    #[test]
+    fn test_default_roundtrip() {
+        let profile = InstitutionProfile::default();
+        assert_eq!(
+            InstitutionProfile::from_toml(&profile.to_toml()).unwrap(),
+            profile
+        );
+    }
+
+    #[test]
+    fn test_shipped_profile_is_default() {
+        assert_eq!(
+            InstitutionProfile::from_toml(include_str!("../profiles/myontariotech.toml")).unwrap(),
+            InstitutionProfile::default()
+        );
+    }
+
+    #[test]
+    fn test_building_codes() {
+        let mut profile = InstitutionProfile::default();
+        profile
+            .campus_aliases
+            .insert("North Oshawa".to_owned(), "North Campus".to_owned());
+        let mut date_range = DateRange {
+            start_date: NaiveDate::from_ymd_opt(2024, 9, 4).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2024, 12, 3).unwrap(),
+            start_time: Default::default(),
+            end_time: Default::default(),
+            weekday: chrono::Weekday::Mon,
+            location: None,
+            building: Some("Energy Research Centre".to_owned()),
+            room: Some("ERC 1040".to_owned()),
+            biweekly: false,
+            kind: DateRangeKind::Weekly,
+        };
+
+        assert_eq!(
+            profile.location(&date_range),
+            "Energy Research Centre – 1040"
+        );
+        assert_eq!(profile.campus(&date_range).as_deref(), Some("North Campus"));
+
+        date_range.room = Some("XYZ 1040".to_owned());
+        assert_eq!(
+            profile.location(&date_range),
+            "Energy Research Centre - XYZ 1040"
+        );
+        assert_eq!(profile.campus(&date_range), None);
+    }
+
+    #[test]
+    fn test_maps_url() {
+        let profile = InstitutionProfile::default();
+        let mut date_range = DateRange {
+            start_date: NaiveDate::from_ymd_opt(2024, 9, 4).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2024, 12, 3).unwrap(),
+            start_time: Default::default(),
+            end_time: Default::default(),
+            weekday: chrono::Weekday::Mon,
+            location: Some("Downtown Oshawa".to_owned()),
+            building: Some("Charles Hall".to_owned()),
+            room: Some("CH 101".to_owned()),
+            biweekly: false,
+            kind: DateRangeKind::Weekly,
+        };
+        assert_eq!(
+            profile.maps_url(&date_range).unwrap(),
+            "https://www.google.com/maps/search/?api=1&query=Charles%20Hall%2C%20Downtown%20Oshawa"
+        );
+
+        date_range.room = Some("UA 1350".to_owned());
+        assert_eq!(
+            profile.maps_url(&date_range).unwrap(),
+            "https://www.google.com/maps/search/?api=1&query=43.9447%2C-78.8966"
+        );
+
+        date_range.location = Some("Online".to_owned());
+        assert_eq!(profile.maps_url(&date_range), None);
+    }
+
+    #[test]
+    fn test_partial_profile() {
+        let profile = InstitutionProfile::from_toml(indoc! {r#"
+            name = "Example University"
+            timezone = "America/Vancouver"
+
+            [subjects]
+            "Computer Science" = "CPSC"
+        "#})
+        .unwrap();
+
+        assert_eq!(profile.name, "Example University");
+        assert_eq!(profile.subjects.len(), 1);
+        assert_eq!(profile.regexes, Regexes::default());
+    }
+}
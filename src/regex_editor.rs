@@ -0,0 +1,198 @@
+use eframe::egui::{self, Color32, RichText, TextEdit};
+use mycampus_calendar_rs::profile::Regexes;
+use regex::Regex;
+
+use crate::i18n::{tr, tr_args};
+
+pub const OK_COLOR: Color32 = Color32::from_rgb(0x4c, 0xaf, 0x50);
+
+/// Editor for the parser regexes, showing how each one behaves on a test line and on the pasted
+/// schedule data. Returns true if any regex was changed.
+pub fn regex_editor(
+    ui: &mut egui::Ui,
+    regexes: &mut Regexes,
+    data: &str,
+    test_line: &mut String,
+) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label(tr("regex-test-line"));
+        ui.add(
+            TextEdit::singleline(test_line)
+                .code_editor()
+                .desired_width(f32::INFINITY)
+                .hint_text(tr("regex-test-line-hint")),
+        );
+    });
+
+    ui.add_space(6.0);
+
+    let lines = data
+        .lines()
+        .map(|l| l.replace('\u{a0}', " "))
+        .collect::<Vec<_>>();
+
+    for (label, pattern, groups) in [
+        (
+            "regex-course-summary",
+            &mut regexes.course_summary,
+            &[(Some("subject"), 1), (Some("crn"), 2)][..],
+        ),
+        (
+            "regex-course-name",
+            &mut regexes.course_name,
+            &[(None, 1), (None, 2), (None, 3)],
+        ),
+        ("regex-date", &mut regexes.date, &[(None, 1), (None, 2)]),
+        ("regex-time", &mut regexes.time, &[(None, 1), (None, 2)]),
+        (
+            "regex-message",
+            &mut regexes.message,
+            &[(Some("class_type"), 1)],
+        ),
+        ("regex-crn", &mut regexes.crn, &[(None, 1)]),
+        ("regex-instructor", &mut regexes.instructor, &[]),
+    ] {
+        ui.strong(tr(label));
+        changed |= ui
+            .add(
+                TextEdit::singleline(pattern)
+                    .code_editor()
+                    .desired_width(f32::INFINITY),
+            )
+            .changed();
+
+        let checked = Regex::new(pattern)
+            .map_err(|e| format!("✖ {e}"))
+            .and_then(|re| match missing_group(&re, groups) {
+                Some(group) => Err(tr_args("regex-missing-group", &[("group", group.into())])),
+                None => Ok(re),
+            });
+        match checked {
+            Ok(re) => {
+                if !test_line.is_empty() {
+                    match test_line_groups(&re, test_line) {
+                        Some(groups) => {
+                            ui.colored_label(
+                                OK_COLOR,
+                                tr_args("regex-matches-test-line", &[("groups", groups.into())]),
+                            );
+                        }
+                        None => {
+                            ui.colored_label(
+                                ui.visuals().warn_fg_color,
+                                tr("regex-no-match-test-line"),
+                            );
+                        }
+                    }
+                }
+
+                let mut matches = lines.iter().filter(|l| re.is_match(l));
+                match matches.next() {
+                    Some(first) => {
+                        ui.label(tr_args(
+                            "regex-matching-lines",
+                            &[("lines", (1 + matches.count()).into())],
+                        ));
+                        ui.label(RichText::new(first.trim_end()).monospace());
+                    }
+                    None if !data.is_empty() => {
+                        ui.colored_label(ui.visuals().warn_fg_color, tr("regex-no-matching-lines"));
+                    }
+                    None => (),
+                }
+            }
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+        }
+
+        ui.add_space(6.0);
+    }
+
+    if ui
+        .add_enabled(
+            *regexes != Regexes::default(),
+            egui::Button::new(tr("reset-to-defaults")),
+        )
+        .clicked()
+    {
+        *regexes = Regexes::default();
+        changed = true;
+    }
+
+    changed
+}
+
+/// The first of the `(name, index)` groups the parser reads that `re` doesn't have, by name or
+/// else index. Like the parser, a group counts if it has the name or `re` has a group at `index`.
+fn missing_group(re: &Regex, groups: &[(Option<&str>, usize)]) -> Option<String> {
+    groups
+        .iter()
+        .find(|&&(name, index)| {
+            !name.is_some_and(|name| re.capture_names().any(|n| n == Some(name)))
+                && index >= re.captures_len()
+        })
+        .map(|&(name, index)| name.map_or_else(|| index.to_string(), str::to_owned))
+}
+
+/// The groups captured by `re` on `line`, eg. `"Calculus I", "MATH", "1010U"`, or `None` if it
+/// doesn't match.
+fn test_line_groups(re: &Regex, line: &str) -> Option<String> {
+    let caps = re.captures(line)?;
+    Some(
+        caps.iter()
+            .skip(1)
+            .map(|m| format!("{:?}", m.map_or("", |m| m.as_str())))
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_test_line_groups() {
+        let re = Regex::new(&Regexes::default().course_name).unwrap();
+        assert_eq!(
+            test_line_groups(&re, "Calculus I | MATH 1010U | Section 001").as_deref(),
+            Some(r#""Calculus I", "MATH", "1010U""#)
+        );
+        assert_eq!(test_line_groups(&re, "CRN: 12345"), None);
+
+        // groups that didn't take part in the match are shown empty
+        let re = Regex::new(r"^(\d+)(?: \((\w+)\))?").unwrap();
+        assert_eq!(test_line_groups(&re, "42").as_deref(), Some(r#""42", """#));
+    }
+
+    #[test]
+    fn test_missing_group() {
+        let class_type = [(Some("class_type"), 1)];
+        let numbered = [(None, 1), (None, 2), (None, 3)];
+
+        let default = Regexes::default();
+        assert_eq!(
+            missing_group(&Regex::new(&default.message).unwrap(), &class_type),
+            None
+        );
+        assert_eq!(
+            missing_group(&Regex::new(&default.course_name).unwrap(), &numbered),
+            None
+        );
+
+        // an unnamed group works too, as the first one
+        let unnamed = Regex::new(r"\| Schedule Type: (.+?) \|").unwrap();
+        assert_eq!(missing_group(&unnamed, &class_type), None);
+
+        let no_groups = Regex::new(r"\| Schedule Type: .+? \|").unwrap();
+        assert_eq!(
+            missing_group(&no_groups, &class_type).as_deref(),
+            Some("class_type")
+        );
+        let two_groups = Regex::new(r"^(.+?) \| (.+?) \d+U").unwrap();
+        assert_eq!(missing_group(&two_groups, &numbered).as_deref(), Some("3"));
+    }
+}
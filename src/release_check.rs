@@ -0,0 +1,82 @@
+//! Checking GitHub for a newer release, so fixes for changes to the schedule page reach people
+//! running an old build.
+
+use serde::Deserialize;
+use thiserror::Error;
+
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/object-Object/mycampus-calendar-rs/releases/latest";
+
+#[derive(Debug, Error)]
+pub enum UpdateError {
+    #[error("Request to GitHub failed: {0}")]
+    Http(#[from] ureq::Error),
+    #[error("Unexpected response from GitHub: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// A published release.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Release {
+    /// Its version, eg. "v0.3.0".
+    pub tag_name: String,
+    /// The release page, with the downloads.
+    pub html_url: String,
+}
+
+/// The latest release, if it's newer than `current` (eg. "v0.2.1").
+pub fn check_for_update(current: &str) -> Result<Option<Release>, UpdateError> {
+    let body = ureq::get(LATEST_RELEASE_URL)
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "mycampus-calendar-rs")
+        .call()?
+        .body_mut()
+        .read_to_string()?;
+    let release: Release = serde_json::from_str(&body)?;
+    Ok(is_newer(&release.tag_name, current).then_some(release))
+}
+
+/// Whether version `tag` is newer than `current`. Both can have a leading "v", and anything after
+/// a "-", like "-beta", is ignored.
+pub fn is_newer(tag: &str, current: &str) -> bool {
+    let parse = |version: &str| -> Option<Vec<u64>> {
+        let version = version.trim().trim_start_matches('v');
+        let version = version
+            .split_once('-')
+            .map_or(version, |(version, _)| version);
+        version.split('.').map(|part| part.parse().ok()).collect()
+    };
+    match (parse(tag), parse(current)) {
+        (Some(tag), Some(current)) => tag > current,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("v0.3.0", "v0.2.1"));
+        assert!(is_newer("v0.2.10", "v0.2.9"));
+        assert!(is_newer("1.0.0", "v0.2.1"));
+        assert!(!is_newer("v0.2.1", "v0.2.1"));
+        assert!(!is_newer("v0.2.0", "v0.2.1"));
+        assert!(!is_newer("v0.2.1-beta", "v0.2.1"));
+        assert!(!is_newer("nightly", "v0.2.1"));
+    }
+
+    #[test]
+    fn test_parse_release() {
+        let release: Release = serde_json::from_str(
+            r#"{
+                "tag_name": "v0.3.0",
+                "html_url": "https://github.com/object-Object/mycampus-calendar-rs/releases/tag/v0.3.0",
+                "draft": false
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(release.tag_name, "v0.3.0");
+    }
+}
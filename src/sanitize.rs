@@ -0,0 +1,100 @@
+//! Taking personal details out of text before it's logged or shared in a bug report.
+
+use std::{env, path::MAIN_SEPARATOR};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Headings of the pages that have the student's name on the next line.
+const NAME_HEADINGS: [&str; 2] = ["Student Schedule", "Registration Information"];
+const REDACTED_NAME: &str = "Student Name";
+
+/// Student numbers, eg. 100123456.
+static STUDENT_ID_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b\d{9}\b").unwrap());
+static EMAIL_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"[\w.+-]+@[\w-]+(?:\.[\w-]+)+").unwrap());
+
+/// Removes the student's name, student numbers, email addresses, and the user's home folder from
+/// text.
+#[derive(Debug, Clone)]
+pub struct Sanitizer {
+    names: Vec<Regex>,
+    /// Replaced with `~`, since it usually has the user's name in it.
+    home: Option<String>,
+}
+
+impl Default for Sanitizer {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl Sanitizer {
+    /// A sanitizer that also knows the student's name from the pasted schedule `data`, so it's
+    /// removed wherever it shows up, not only on the line after the page heading.
+    pub fn new(data: &str) -> Self {
+        let lines = data.lines().collect::<Vec<_>>();
+        let names = lines
+            .windows(2)
+            .filter(|pair| NAME_HEADINGS.contains(&pair[0].trim()))
+            .map(|pair| pair[1].trim())
+            .filter(|name| !name.is_empty() && *name != REDACTED_NAME)
+            .map(|name| Regex::new(&format!(r"\b{}\b", regex::escape(name))).unwrap())
+            .collect();
+        let home = env::var("HOME")
+            .or_else(|_| env::var("USERPROFILE"))
+            .ok()
+            .map(|home| home.trim_end_matches(MAIN_SEPARATOR).to_owned())
+            .filter(|home| home.len() > 1);
+        Self { names, home }
+    }
+
+    pub fn sanitize(&self, text: &str) -> String {
+        let mut text = text.to_owned();
+        for name in &self.names {
+            text = name.replace_all(&text, REDACTED_NAME).into_owned();
+        }
+        if let Some(home) = &self.home {
+            text = text.replace(home.as_str(), "~");
+        }
+        text = STUDENT_ID_RE
+            .replace_all(&text, "[student ID]")
+            .into_owned();
+        EMAIL_RE.replace_all(&text, "[email]").into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize() {
+        let data = include_str!("../tests/data/chromium.txt");
+        let sanitizer = Sanitizer::new(data);
+        let sanitized = sanitizer.sanitize(data);
+        assert!(!sanitized.contains("Jane Student"));
+        assert!(sanitized.contains("Student Schedule\nStudent Name\n"));
+        assert_eq!(sanitized.lines().count(), data.lines().count());
+
+        assert_eq!(
+            sanitizer.sanitize(
+                "Jane Student (100123456, jane.student@ontariotechu.net) wrote CRN: 40001"
+            ),
+            "Student Name ([student ID], [email]) wrote CRN: 40001"
+        );
+        assert_eq!(
+            Sanitizer::default().sanitize("Jane Student"),
+            "Jane Student",
+            "names are only known from the pasted data"
+        );
+
+        let sanitizer = Sanitizer {
+            home: Some("/home/jane".to_owned()),
+            ..Sanitizer::default()
+        };
+        assert_eq!(
+            sanitizer.sanitize("Wrote /home/jane/Calendars/Fall 2024 - Lecture.ics"),
+            "Wrote ~/Calendars/Fall 2024 - Lecture.ics"
+        );
+    }
+}
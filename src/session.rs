@@ -0,0 +1,82 @@
+//! Saving the work in progress to disk as it changes, so it can be restored after a crash.
+
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use eframe::egui;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::logging;
+
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5);
+const SESSION_FILE: &str = "session.json";
+
+/// Writes a snapshot of the app's state to the data folder when it changes, at most every few
+/// seconds.
+pub struct Autosave<T> {
+    saved: Option<T>,
+    last_save: Instant,
+}
+
+impl<T> Default for Autosave<T> {
+    fn default() -> Self {
+        Self {
+            saved: None,
+            last_save: Instant::now(),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq + Serialize + DeserializeOwned> Autosave<T> {
+    /// The state saved by the last run, if there is one.
+    pub fn load() -> Option<T> {
+        let json = fs::read_to_string(path()?).ok()?;
+        serde_json::from_str(&json)
+            .inspect_err(|err| tracing::warn!("Failed to read the previous session: {err}"))
+            .ok()
+    }
+
+    /// Saves `state` if it changed, or asks for a repaint once it's been long enough to.
+    pub fn update(&mut self, ctx: &egui::Context, state: &T) {
+        if self.saved.as_ref() == Some(state) {
+            return;
+        }
+        let remaining = AUTOSAVE_INTERVAL.saturating_sub(self.last_save.elapsed());
+        if !remaining.is_zero() {
+            ctx.request_repaint_after(remaining);
+            return;
+        }
+        if let Err(err) = write(state) {
+            tracing::warn!("Failed to save the session: {err}");
+        }
+        self.saved = Some(state.clone());
+        self.last_save = Instant::now();
+    }
+
+    /// Deletes the saved state.
+    pub fn clear(&mut self) {
+        if let Some(path) = path() {
+            fs::remove_file(path).ok();
+        }
+        self.saved = None;
+    }
+}
+
+fn path() -> Option<PathBuf> {
+    Some(logging::log_folder()?.join(SESSION_FILE))
+}
+
+/// Writes to a temporary file first, so a crash while writing doesn't lose the previous save.
+fn write(state: &impl Serialize) -> io::Result<()> {
+    let path = path().ok_or_else(|| io::Error::other("no data folder"))?;
+    if let Some(folder) = path.parent() {
+        fs::create_dir_all(folder)?;
+    }
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, serde_json::to_string(state)?)?;
+    fs::rename(temp, path)?;
+    Ok(())
+}
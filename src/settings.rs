@@ -0,0 +1,396 @@
+use std::collections::BTreeSet;
+
+use eframe::egui::{self, Button, ComboBox, DragValue, Grid, RichText, TextEdit};
+use mycampus_calendar_rs::{
+    parser::{
+        AsyncEvents, Class, DateOrder, GenerateOptions, Grouping, Schedule,
+        DEFAULT_DESCRIPTION_TEMPLATE,
+    },
+    template::{self, PLACEHOLDERS},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{tr, tr_args};
+
+/// Everything in the Settings window, stored together and exported as JSON to move them to
+/// another computer.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub options: GenerateOptions,
+    pub date_order: DateOrder,
+    /// Replaces the institution profile's timezone, if set.
+    pub timezone: Option<String>,
+}
+
+/// Light or dark visuals. Kept out of [`Settings`], since it's about this computer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    /// Whatever the OS uses, falling back to dark if that's unknown.
+    #[default]
+    System,
+    Dark,
+    Light,
+}
+
+impl Theme {
+    pub const ALL: [Self; 3] = [Self::System, Self::Dark, Self::Light];
+
+    pub fn name(self) -> String {
+        tr(match self {
+            Self::System => "theme-system",
+            Self::Dark => "theme-dark",
+            Self::Light => "theme-light",
+        })
+    }
+
+    /// Whether to use dark visuals, given the OS's theme if it's known.
+    pub fn is_dark(self, system: Option<eframe::Theme>) -> bool {
+        match self {
+            Self::System => system != Some(eframe::Theme::Light),
+            Self::Dark => true,
+            Self::Light => false,
+        }
+    }
+}
+
+/// Settings for how the events are written. `schedule` is used to preview the templates and to
+/// list the schedule types.
+///
+/// Returns true if any setting was changed.
+pub fn settings(
+    ui: &mut egui::Ui,
+    options: &mut GenerateOptions,
+    schedule: Option<&Schedule>,
+) -> bool {
+    let mut changed = false;
+    let example = schedule.and_then(|schedule| schedule.classes.first());
+
+    ui.horizontal(|ui| {
+        ui.strong(tr("settings-calendars"));
+        ComboBox::from_id_source("settings_grouping")
+            .selected_text(grouping_name(options.grouping))
+            .show_ui(ui, |ui| {
+                for grouping in Grouping::ALL {
+                    changed |= ui
+                        .selectable_value(&mut options.grouping, grouping, grouping_name(grouping))
+                        .changed();
+                }
+            });
+    });
+    ui.add_space(6.0);
+
+    ui.strong(tr("settings-event-title"));
+    changed |= ui
+        .add(
+            TextEdit::singleline(&mut options.summary_template)
+                .code_editor()
+                .desired_width(f32::INFINITY)
+                .hint_text(tr("placeholder-name")),
+        )
+        .changed();
+    template_preview(ui, &options.summary_template, example);
+
+    ui.add_space(6.0);
+    ui.horizontal(|ui| {
+        ui.strong(tr("settings-event-description"));
+        if ui
+            .add_enabled(
+                options.description_template != DEFAULT_DESCRIPTION_TEMPLATE,
+                Button::new(tr("reset")),
+            )
+            .clicked()
+        {
+            options.description_template = DEFAULT_DESCRIPTION_TEMPLATE.to_owned();
+            changed = true;
+        }
+    });
+    changed |= ui
+        .add(
+            TextEdit::multiline(&mut options.description_template)
+                .code_editor()
+                .desired_rows(4)
+                .desired_width(f32::INFINITY),
+        )
+        .changed();
+    template_preview(ui, &options.description_template, example);
+
+    ui.add_space(6.0);
+    ui.collapsing(tr("settings-placeholders"), |ui| {
+        Grid::new("placeholders").striped(true).show(ui, |ui| {
+            for (placeholder, _) in PLACEHOLDERS {
+                ui.label(RichText::new(format!("{{{placeholder}}}")).monospace());
+                ui.label(tr(&format!("placeholder-{placeholder}")));
+                ui.end_row();
+            }
+        });
+    });
+
+    let class_types = schedule
+        .into_iter()
+        .flat_map(|schedule| &schedule.classes)
+        .map(|class| class.class_type.clone())
+        .chain(options.reminders.keys().cloned())
+        .chain(options.colors.keys().cloned())
+        .collect::<BTreeSet<_>>();
+
+    ui.add_space(6.0);
+    ui.horizontal(|ui| {
+        ui.strong(tr("settings-async-events"));
+        ComboBox::from_id_source("async_events")
+            .selected_text(async_events_name(options.async_events))
+            .show_ui(ui, |ui| {
+                for async_events in AsyncEvents::ALL {
+                    changed |= ui
+                        .selectable_value(
+                            &mut options.async_events,
+                            async_events,
+                            async_events_name(async_events),
+                        )
+                        .changed();
+                }
+            });
+    });
+
+    changed |= ui
+        .checkbox(&mut options.milestones, tr("settings-milestones"))
+        .changed();
+
+    changed |= ui
+        .checkbox(&mut options.maps_links, tr("settings-maps-links"))
+        .on_hover_text(tr("settings-maps-links-hover"))
+        .changed();
+
+    ui.horizontal(|ui| {
+        ui.label(tr("settings-start-offset"))
+            .on_hover_text(tr("settings-start-offset-hover"));
+        changed |= ui
+            .add(
+                DragValue::new(&mut options.start_offset)
+                    .range(-60..=60)
+                    .suffix(" min"),
+            )
+            .changed();
+        ui.label(tr("settings-end-offset"));
+        changed |= ui
+            .add(
+                DragValue::new(&mut options.end_offset)
+                    .range(-60..=60)
+                    .suffix(" min"),
+            )
+            .changed();
+    });
+
+    ui.horizontal(|ui| {
+        changed |= ui
+            .checkbox(
+                &mut options.travel_before_first_class,
+                tr("settings-travel-first-class"),
+            )
+            .changed();
+        changed |= ui
+            .checkbox(
+                &mut options.travel_between_buildings,
+                tr("settings-travel-between-buildings"),
+            )
+            .changed();
+        changed |= ui
+            .add(
+                DragValue::new(&mut options.travel_minutes)
+                    .range(1..=60)
+                    .suffix(" min"),
+            )
+            .changed();
+    })
+    .response
+    .on_hover_text(tr("settings-travel-hover"));
+
+    changed |= ui
+        .checkbox(
+            &mut options.expand_occurrences,
+            tr("settings-expand-occurrences"),
+        )
+        .on_hover_text(tr("settings-expand-occurrences-hover"))
+        .changed();
+
+    changed |= ui
+        .checkbox(&mut options.show_as_free, tr("settings-show-as-free"))
+        .on_hover_text(tr("settings-show-as-free-hover"))
+        .changed();
+
+    changed |= ui
+        .checkbox(&mut options.private_events, tr("settings-private-events"))
+        .on_hover_text(tr("settings-private-events-hover"))
+        .changed();
+
+    changed |= ui
+        .checkbox(
+            &mut options.skip_zero_credit,
+            tr("settings-skip-zero-credit"),
+        )
+        .on_hover_text(tr("settings-skip-zero-credit-hover"))
+        .changed();
+
+    changed |= ui
+        .checkbox(
+            &mut options.instructor_attendees,
+            tr("settings-instructor-attendees"),
+        )
+        .on_hover_text(tr("settings-instructor-attendees-hover"))
+        .changed();
+    if options.instructor_attendees {
+        changed |= instructor_emails(ui, options, schedule);
+    }
+
+    ui.add_space(6.0);
+    ui.strong(tr("settings-reminders"));
+    changed |= reminders(ui, options, &class_types);
+
+    ui.add_space(6.0);
+    ui.strong(tr("settings-colors"));
+    changed |= colors(ui, options, &class_types);
+
+    changed
+}
+
+/// The name of `grouping` in the current language.
+pub fn grouping_name(grouping: Grouping) -> String {
+    tr(match grouping {
+        Grouping::ClassType => "grouping-class-type",
+        Grouping::Course => "grouping-course",
+        Grouping::Combined => "grouping-combined",
+    })
+}
+
+fn async_events_name(async_events: AsyncEvents) -> String {
+    tr(match async_events {
+        AsyncEvents::Off => "async-events-off",
+        AsyncEvents::FirstDay => "async-events-first-day",
+        AsyncEvents::Weekly => "async-events-weekly",
+    })
+}
+
+pub fn date_order_name(date_order: DateOrder) -> String {
+    tr(match date_order {
+        DateOrder::Auto => "date-order-auto",
+        DateOrder::MonthFirst => "date-order-month-first",
+        DateOrder::DayFirst => "date-order-day-first",
+    })
+}
+
+fn instructor_emails(
+    ui: &mut egui::Ui,
+    options: &mut GenerateOptions,
+    schedule: Option<&Schedule>,
+) -> bool {
+    let mut changed = false;
+    let names = schedule
+        .into_iter()
+        .flat_map(|schedule| &schedule.classes)
+        .flat_map(Class::instructors)
+        .map(|instructor| instructor.name)
+        .chain(options.instructor_emails.keys().cloned())
+        .collect::<BTreeSet<_>>();
+    if names.is_empty() {
+        ui.label(tr("settings-instructor-emails-empty"));
+        return false;
+    }
+
+    Grid::new("instructor_emails").show(ui, |ui| {
+        for name in names {
+            ui.label(&name);
+            let email = options.instructor_emails.entry(name.clone()).or_default();
+            changed |= ui
+                .add(TextEdit::singleline(email).hint_text(tr("settings-email-hint")))
+                .changed();
+            if email.is_empty() {
+                options.instructor_emails.remove(&name);
+            }
+            ui.end_row();
+        }
+    });
+
+    changed
+}
+
+fn reminders(
+    ui: &mut egui::Ui,
+    options: &mut GenerateOptions,
+    class_types: &BTreeSet<String>,
+) -> bool {
+    let mut changed = false;
+    if class_types.is_empty() {
+        ui.label(tr("settings-reminders-empty"));
+        return false;
+    }
+
+    Grid::new("reminders").show(ui, |ui| {
+        for class_type in class_types {
+            let mut enabled = options.reminders.contains_key(class_type);
+            if ui.checkbox(&mut enabled, class_type).changed() {
+                if enabled {
+                    options.reminders.insert(class_type.clone(), 15);
+                } else {
+                    options.reminders.remove(class_type);
+                }
+                changed = true;
+            }
+            if let Some(minutes) = options.reminders.get_mut(class_type) {
+                ui.horizontal(|ui| {
+                    changed |= ui.add(DragValue::new(minutes).range(0..=24 * 60)).changed();
+                    ui.label(tr("settings-minutes-before"));
+                });
+            }
+            ui.end_row();
+        }
+    });
+
+    changed
+}
+
+fn colors(
+    ui: &mut egui::Ui,
+    options: &mut GenerateOptions,
+    class_types: &BTreeSet<String>,
+) -> bool {
+    let mut changed = false;
+    if class_types.is_empty() {
+        ui.label(tr("settings-colors-empty"));
+        return false;
+    }
+
+    Grid::new("colors").show(ui, |ui| {
+        for class_type in class_types {
+            let mut enabled = options.colors.contains_key(class_type);
+            if ui.checkbox(&mut enabled, class_type).changed() {
+                if enabled {
+                    options
+                        .colors
+                        .insert(class_type.clone(), [0x1e, 0x90, 0xff]);
+                } else {
+                    options.colors.remove(class_type);
+                }
+                changed = true;
+            }
+            if let Some(color) = options.colors.get_mut(class_type) {
+                changed |= ui.color_edit_button_srgb(color).changed();
+            }
+            ui.end_row();
+        }
+    });
+
+    changed
+}
+
+fn template_preview(ui: &mut egui::Ui, template: &str, example: Option<&Class>) {
+    let Some((class, date_range)) = example
+        .and_then(|class| Some((class, class.date_ranges.first()?)))
+        .filter(|_| !template.is_empty())
+    else {
+        return;
+    };
+    let example = template::render(template, |placeholder| {
+        template::event_value(class, Some(date_range), placeholder)
+    });
+    ui.label(tr_args("settings-example", &[("example", example.into())]));
+}
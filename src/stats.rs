@@ -0,0 +1,69 @@
+//! Weekly in-class hours of a schedule, for the summary shown after generating.
+
+use std::collections::BTreeMap;
+
+use crate::parser::{Class, DateRange};
+
+/// In-class hours in a typical week, in total and broken down by course and schedule type.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Statistics {
+    pub weekly_hours: f32,
+    /// Keyed by course name.
+    pub courses: BTreeMap<String, f32>,
+    /// Keyed by schedule type, eg. "Lecture".
+    pub class_types: BTreeMap<String, f32>,
+}
+
+/// Hours per week of `classes`. Biweekly meetings count for half.
+pub fn statistics<'a>(classes: impl IntoIterator<Item = &'a Class>) -> Statistics {
+    let mut statistics = Statistics::default();
+    for class in classes {
+        let hours = class.date_ranges.iter().map(weekly_hours).sum::<f32>();
+        statistics.weekly_hours += hours;
+        *statistics.courses.entry(class.name.clone()).or_default() += hours;
+        *statistics
+            .class_types
+            .entry(class.class_type.clone())
+            .or_default() += hours;
+    }
+    statistics
+}
+
+fn weekly_hours(date_range: &DateRange) -> f32 {
+    if !date_range.is_weekly() {
+        return 0.0;
+    }
+    let hours = (date_range.end_time - date_range.start_time).num_minutes() as f32 / 60.0;
+    if date_range.biweekly {
+        hours / 2.0
+    } else {
+        hours
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    #[test]
+    fn test_statistics() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let statistics = statistics(&schedule.classes);
+
+        assert!(statistics.weekly_hours > 0.0);
+        for breakdown in [&statistics.courses, &statistics.class_types] {
+            let total = breakdown.values().sum::<f32>();
+            assert!((total - statistics.weekly_hours).abs() < 0.01);
+        }
+
+        let mut biweekly = schedule.classes[0].clone();
+        let full = super::statistics([&biweekly]).weekly_hours;
+        for date_range in &mut biweekly.date_ranges {
+            date_range.biweekly = true;
+        }
+        assert_eq!(super::statistics([&biweekly]).weekly_hours, full / 2.0);
+    }
+}
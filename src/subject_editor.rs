@@ -0,0 +1,63 @@
+use eframe::egui::{self, Grid, TextEdit};
+
+use crate::i18n::tr;
+
+/// Editor for user-defined subject codes, which are added on top of the institution profile's
+/// subject map. Returns true if anything was changed.
+pub fn subject_editor(
+    ui: &mut egui::Ui,
+    subjects: &mut Vec<(String, String)>,
+    built_in: impl IntoIterator<Item = (String, String)>,
+) -> bool {
+    let mut changed = false;
+
+    ui.label(tr("subjects-description"));
+    ui.add_space(6.0);
+
+    if !subjects.is_empty() {
+        Grid::new("custom_subjects")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong(tr("subjects-subject"));
+                ui.strong(tr("subjects-code"));
+                ui.end_row();
+
+                subjects.retain_mut(|(name, code)| {
+                    changed |= ui
+                        .add(TextEdit::singleline(name).desired_width(250.0))
+                        .changed();
+                    changed |= ui
+                        .add(TextEdit::singleline(code).desired_width(60.0))
+                        .changed();
+                    let should_delete = ui.button("❌").clicked();
+                    ui.end_row();
+
+                    changed |= should_delete;
+                    !should_delete
+                });
+            });
+        ui.add_space(6.0);
+    }
+
+    if ui.button(tr("subjects-add")).clicked() {
+        subjects.push(Default::default());
+        changed = true;
+    }
+
+    ui.add_space(6.0);
+    ui.collapsing(tr("subjects-built-in"), |ui| {
+        Grid::new("built_in_subjects")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                for (name, code) in built_in {
+                    ui.label(name);
+                    ui.label(code);
+                    ui.end_row();
+                }
+            });
+    });
+
+    changed
+}
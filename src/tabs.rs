@@ -0,0 +1,104 @@
+use eframe::egui::{self, Button, TextEdit};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{tr, tr_args};
+
+/// A schedule with its own data and settings, eg. "Fall" and "Winter", or "Mine" and "Partner's".
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tab<T> {
+    pub name: String,
+    pub state: T,
+}
+
+/// The open tabs. Like [`crate::configs::Configs`], the active tab's state is whatever's
+/// currently in the app, so it's only saved here when switching away from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, bound(deserialize = "T: Deserialize<'de> + Default"))]
+pub struct Tabs<T> {
+    pub active: usize,
+    pub tabs: Vec<Tab<T>>,
+}
+
+impl<T: Default> Default for Tabs<T> {
+    fn default() -> Self {
+        Self {
+            active: 0,
+            tabs: vec![Tab {
+                name: tab_name(1),
+                state: T::default(),
+            }],
+        }
+    }
+}
+
+impl<T> Tabs<T> {
+    /// Adds a tab with the next unused number in its name, returning its index.
+    pub fn push(&mut self, state: T) -> usize {
+        let number = (1..)
+            .find(|&number| {
+                let name = tab_name(number);
+                self.tabs.iter().all(|tab| tab.name != name)
+            })
+            .unwrap_or_default();
+        self.tabs.push(Tab {
+            name: tab_name(number),
+            state,
+        });
+        self.tabs.len() - 1
+    }
+}
+
+fn tab_name(number: usize) -> String {
+    tr_args("tab-name", &[("number", number.into())])
+}
+
+pub enum TabAction {
+    Switch(usize),
+    New,
+    Close,
+}
+
+/// A button per tab, with buttons to add a tab and to close the active one, and a field to rename
+/// it. Switching is disabled unless `enabled`, eg. while generating.
+pub fn tabs_bar<T>(ui: &mut egui::Ui, tabs: &mut Tabs<T>, enabled: bool) -> Option<TabAction> {
+    let mut action = None;
+    ui.horizontal_wrapped(|ui| {
+        for (index, tab) in tabs.tabs.iter().enumerate() {
+            let name = if tab.name.is_empty() {
+                "…"
+            } else {
+                &tab.name
+            };
+            if ui
+                .add_enabled(
+                    enabled || index == tabs.active,
+                    Button::new(name).selected(index == tabs.active),
+                )
+                .clicked()
+                && index != tabs.active
+            {
+                action = Some(TabAction::Switch(index));
+            }
+        }
+        if ui
+            .add_enabled(enabled, Button::new("➕"))
+            .on_hover_text(tr("new-tab"))
+            .clicked()
+        {
+            action = Some(TabAction::New);
+        }
+
+        ui.separator();
+        if let Some(tab) = tabs.tabs.get_mut(tabs.active) {
+            ui.add(TextEdit::singleline(&mut tab.name).desired_width(120.0));
+        }
+        if ui
+            .add_enabled(enabled && tabs.tabs.len() > 1, Button::new(tr("close-tab")))
+            .clicked()
+        {
+            action = Some(TabAction::Close);
+        }
+    });
+    action
+}
@@ -0,0 +1,118 @@
+//! Placeholder templates for customizing the generated events, eg. `{code} {type} ({room})`.
+
+use crate::parser::{Class, DateRange};
+
+/// Placeholders available in event templates, with a description of each.
+pub const PLACEHOLDERS: [(&str, &str); 12] = [
+    ("name", "Course name"),
+    ("code", "Course code, eg. CSCI 1200U"),
+    ("type", "Schedule type, eg. Lecture"),
+    ("crn", "CRN"),
+    ("instructor", "Instructor"),
+    ("url", "Meeting link for online classes"),
+    ("campus", "Campus"),
+    ("building", "Building"),
+    ("room", "Room"),
+    ("weekday", "Day of the week"),
+    ("start", "Start time"),
+    ("end", "End time"),
+];
+
+/// Replaces each `{placeholder}` in `template` with the result of `value`.
+///
+/// Unknown placeholders are left as-is, and `{{`/`}}` can be used for literal braces.
+pub fn render(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        output.push_str(&rest[..i]);
+        let brace = &rest[i..i + 1];
+        rest = &rest[i + 1..];
+
+        if let Some(after) = rest.strip_prefix(brace) {
+            output.push_str(brace);
+            rest = after;
+            continue;
+        }
+
+        let placeholder = (brace == "{")
+            .then(|| rest.find('}'))
+            .flatten()
+            .and_then(|end| Some((value(&rest[..end])?, end)));
+        match placeholder {
+            Some((value, end)) => {
+                output.push_str(&value);
+                rest = &rest[end + 1..];
+            }
+            None => output.push_str(brace),
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// The value of an event placeholder for one meeting of `class`. The meeting placeholders are
+/// empty for events without a [`DateRange`].
+pub fn event_value(
+    class: &Class,
+    date_range: Option<&DateRange>,
+    placeholder: &str,
+) -> Option<String> {
+    let Some(date_range) = date_range else {
+        return match placeholder {
+            "campus" | "building" | "room" | "weekday" | "start" | "end" => Some(String::new()),
+            _ => event_value_for_class(class, placeholder),
+        };
+    };
+    Some(match placeholder {
+        "campus" => date_range.location.clone().unwrap_or_default(),
+        "building" => date_range.building.clone().unwrap_or_default(),
+        "room" => date_range.room.clone().unwrap_or_default(),
+        "weekday" => date_range.weekday.to_string(),
+        "start" => date_range.start_time.format("%H:%M").to_string(),
+        "end" => date_range.end_time.format("%H:%M").to_string(),
+        _ => return event_value_for_class(class, placeholder),
+    })
+}
+
+fn event_value_for_class(class: &Class, placeholder: &str) -> Option<String> {
+    Some(match placeholder {
+        "name" => class.name.clone(),
+        "code" => class.code.clone(),
+        "type" => class.class_type.clone(),
+        "crn" => strip_label(&class.crn, "CRN:"),
+        "instructor" => class
+            .instructor
+            .as_deref()
+            .map_or_else(String::new, |instructor| {
+                strip_label(instructor, "Instructor:")
+            }),
+        "url" => class.meeting_url.clone().unwrap_or_default(),
+        _ => return None,
+    })
+}
+
+fn strip_label(line: &str, label: &str) -> String {
+    line.strip_prefix(label).unwrap_or(line).trim().to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let value = |placeholder: &str| match placeholder {
+            "code" => Some("CSCI 1200U".to_owned()),
+            "room" => Some("UA 1350".to_owned()),
+            _ => None,
+        };
+
+        assert_eq!(render("{code} ({room})", value), "CSCI 1200U (UA 1350)");
+        assert_eq!(
+            render("{{code}} {unknown} {code", value),
+            "{code} {unknown} {code"
+        );
+        assert_eq!(render("}{room}", value), "}UA 1350");
+    }
+}
@@ -0,0 +1,107 @@
+//! Academic terms, as in the "Class Schedule for Fall 2024" heading of the schedule.
+
+use std::{fmt, str::FromStr};
+
+/// A term of an academic year, in the order they happen in a calendar year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Season {
+    Winter,
+    Spring,
+    /// One term covering spring and summer, like at Ontario Tech.
+    SpringSummer,
+    Summer,
+    Fall,
+}
+
+/// An academic term, eg. Fall 2024.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Term {
+    pub year: i32,
+    pub season: Season,
+}
+
+impl FromStr for Season {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s
+            .to_lowercase()
+            .replace(['/', '-', '&'], " ")
+            .split_whitespace()
+            .filter(|word| *word != "and")
+            .collect::<Vec<_>>()[..]
+        {
+            ["winter"] => Ok(Season::Winter),
+            ["spring"] => Ok(Season::Spring),
+            ["spring", "summer"] => Ok(Season::SpringSummer),
+            ["summer"] => Ok(Season::Summer),
+            ["fall"] | ["autumn"] => Ok(Season::Fall),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Parses eg. "Fall 2024", "2024 Fall" or "Spring/Summer 2025".
+impl FromStr for Term {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (season, year) = match s.split_once(char::is_whitespace) {
+            Some((year, season)) if year.parse::<i32>().is_ok() => (season, year),
+            _ => s.rsplit_once(char::is_whitespace).ok_or(())?,
+        };
+        Ok(Term {
+            year: year.parse().map_err(|_| ())?,
+            season: season.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Season::Winter => "Winter",
+            Season::Spring => "Spring",
+            Season::SpringSummer => "Spring/Summer",
+            Season::Summer => "Summer",
+            Season::Fall => "Fall",
+        })
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.season, self.year)
+    }
+}
+
+/// `term` written the usual way if it's recognized, or else as it was.
+pub fn normalize(term: &str) -> String {
+    term.parse::<Term>()
+        .map_or_else(|()| term.trim().to_owned(), |term| term.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_term() {
+        let fall = Term {
+            year: 2024,
+            season: Season::Fall,
+        };
+        assert_eq!("Fall 2024".parse(), Ok(fall));
+        assert_eq!(" 2024  FALL ".parse(), Ok(fall));
+        assert_eq!(
+            "Spring/Summer 2025".parse::<Term>().map(|t| t.season),
+            Ok(Season::SpringSummer)
+        );
+        assert_eq!("Fall".parse::<Term>(), Err(()));
+        assert!(fall < "Winter 2025".parse().unwrap());
+
+        assert_eq!(normalize("2025 spring & summer"), "Spring/Summer 2025");
+        assert_eq!(normalize(" Intersession 2025 "), "Intersession 2025");
+    }
+}
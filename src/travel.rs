@@ -0,0 +1,193 @@
+//! Short "walk to class" events before classes, so assistants like Google's don't book the time
+//! it takes to get there.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Write,
+};
+
+use chrono::{Duration, NaiveDate, NaiveTime, Utc};
+use indoc::indoc;
+
+use crate::parser::{escape_text, tzid, GenerateOptions, Meeting};
+
+/// UIDs of travel events start with this, so they can be told apart from classes.
+pub const UID_PREFIX: &str = "travel-";
+
+/// Time to get to the class of a meeting on one date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TravelBuffer {
+    /// Index of the meeting in the list it was found in.
+    pub meeting: usize,
+    pub date: NaiveDate,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+/// The travel buffers for `meetings`, going by the travel options. Buffers go before the first
+/// class of each day, and before classes in a different building than the one before them. They
+/// never overlap the previous class, and online classes or ones without a building don't get one.
+pub(crate) fn travel_buffers(
+    meetings: &[Meeting],
+    exdate: &HashSet<NaiveDate>,
+    options: &GenerateOptions,
+) -> Vec<TravelBuffer> {
+    if options.travel_minutes == 0
+        || !(options.travel_before_first_class || options.travel_between_buildings)
+    {
+        return Vec::new();
+    }
+    let travel_time = Duration::minutes(options.travel_minutes.into());
+
+    let mut days = BTreeMap::<NaiveDate, Vec<_>>::new();
+    for (i, meeting) in meetings.iter().enumerate() {
+        let Some(date_range) = meeting.date_range else {
+            continue;
+        };
+        if meeting.class.is_custom() {
+            continue;
+        }
+        let (start, end) = options.event_times(date_range);
+        for date in meeting.occurrences(exdate, &options.swap_days) {
+            days.entry(date).or_default().push((start, end, i));
+        }
+    }
+
+    let mut buffers = Vec::new();
+    for (date, mut classes) in days {
+        classes.sort();
+        for (j, &(start, _, i)) in classes.iter().enumerate() {
+            let here = building(&meetings[i]);
+            if here.is_none() {
+                continue;
+            }
+            let buffer_start = match j.checked_sub(1).map(|previous| classes[previous]) {
+                None if options.travel_before_first_class => start - travel_time,
+                Some((_, previous_end, previous))
+                    if options.travel_between_buildings
+                        && here != building(&meetings[previous]) =>
+                {
+                    (start - travel_time).max(previous_end)
+                }
+                _ => continue,
+            };
+            if buffer_start < start {
+                buffers.push(TravelBuffer {
+                    meeting: i,
+                    date,
+                    start: buffer_start,
+                    end: start,
+                });
+            }
+        }
+    }
+    buffers
+}
+
+/// The building a meeting is in, or `None` if it's online or not known yet.
+fn building<'a>(meeting: &Meeting<'a>) -> Option<&'a str> {
+    meeting
+        .date_range
+        .filter(|date_range| !date_range.is_online())
+        .and_then(|date_range| date_range.building.as_deref())
+}
+
+/// Appends the `VEVENT` for `buffer` before `meeting` to `calendar`.
+pub(crate) fn write_buffer(
+    calendar: &mut String,
+    buffer: &TravelBuffer,
+    meeting: &Meeting,
+    timezone: &str,
+) {
+    let (uid, domain) = meeting.uid.split_once('@').unwrap_or((&meeting.uid, ""));
+    write!(
+        calendar,
+        indoc! {"
+            BEGIN:VEVENT
+            DTSTAMP:{dtstamp}
+            UID:{prefix}{uid}-{date}@{domain}
+            DTSTART;{dtstart}
+            DTEND;{dtend}
+            SUMMARY:{summary}
+            LOCATION:{location}
+            TRANSP:OPAQUE
+            X-MICROSOFT-CDO-BUSYSTATUS:BUSY
+            CATEGORIES:Travel
+            END:VEVENT
+        "},
+        dtstamp = Utc::now().format("%Y%m%dT%H%M%SZ"),
+        prefix = UID_PREFIX,
+        uid = uid,
+        date = buffer.date.format("%Y%m%d"),
+        domain = domain,
+        dtstart = tzid(timezone, buffer.date.and_time(buffer.start)),
+        dtend = tzid(timezone, buffer.date.and_time(buffer.end)),
+        summary = escape_text(&format!("Walk to {}", meeting.class.name)),
+        location = meeting.event.location,
+    )
+    .ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::meetings, InstitutionProfile, Parser};
+
+    #[test]
+    fn test_travel_buffers() {
+        let mut schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        // the lab right after Wednesday's lecture, in another building
+        let lab = &mut schedule.classes[1].date_ranges[0];
+        lab.weekday = chrono::Weekday::Wed;
+        lab.start_time = NaiveTime::from_hms_opt(12, 40, 0).unwrap();
+        let profile = InstitutionProfile::default();
+        let options = GenerateOptions {
+            travel_before_first_class: true,
+            travel_between_buildings: true,
+            travel_minutes: 15,
+            ..Default::default()
+        };
+        let meetings = meetings(&schedule, &profile, &options);
+        let buffers = travel_buffers(&meetings, &HashSet::new(), &options);
+
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let wednesday = NaiveDate::from_ymd_opt(2024, 9, 4).unwrap();
+        let on_wednesday = buffers
+            .iter()
+            .filter(|buffer| buffer.date == wednesday)
+            .map(|buffer| (buffer.start, buffer.end))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            on_wednesday,
+            [(time(10, 55), time(11, 10)), (time(12, 30), time(12, 40))]
+        );
+        // Mondays only have the lecture
+        assert!(buffers
+            .iter()
+            .filter(|buffer| buffer.date == NaiveDate::from_ymd_opt(2024, 9, 9).unwrap())
+            .all(|buffer| buffer.start == time(10, 55)));
+
+        let mut calendar = String::new();
+        let buffer = &buffers[0];
+        write_buffer(
+            &mut calendar,
+            buffer,
+            &meetings[buffer.meeting],
+            "America/Toronto",
+        );
+        assert!(calendar.contains("UID:travel-fall-2024-40001-wed-20240904@mycampus-calendar-rs\n"));
+        assert!(calendar.contains("SUMMARY:Walk to Discrete Mathematics for Comp. Sci\n"));
+    }
+
+    #[test]
+    fn test_no_travel_buffers_by_default() {
+        let schedule = Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let options = GenerateOptions::default();
+        let meetings = meetings(&schedule, &InstitutionProfile::default(), &options);
+        assert!(travel_buffers(&meetings, &HashSet::new(), &options).is_empty());
+    }
+}
@@ -0,0 +1,335 @@
+//! Comparing a newly parsed schedule against the calendars from a previous export, so only the
+//! meetings that actually changed get a new `SEQUENCE`.
+
+use std::{collections::HashMap, fs, io, path::Path};
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::{
+    import::{is_generated, unescape_text},
+    parser::{meetings, GenerateOptions, Schedule},
+    InstitutionProfile,
+};
+
+/// The properties of a `VEVENT` that are compared between exports.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Event {
+    pub summary: String,
+    /// `DTSTART` parameters and value, eg. `TZID=America/Toronto:20240909T111000`.
+    pub start: String,
+    pub end: String,
+    pub rrule: String,
+    pub location: String,
+    pub description: String,
+    pub sequence: i64,
+}
+
+/// A difference between the previous export and the calendars that are about to be written.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeetingChange {
+    Added(Event),
+    Removed(Event),
+    Changed {
+        /// The meeting as it was in the previous export.
+        meeting: Event,
+        changes: Vec<EventChange>,
+    },
+}
+
+/// Something about a meeting that changed since the previous export.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventChange {
+    Name {
+        from: String,
+        to: String,
+    },
+    Time {
+        from: EventTime,
+        to: EventTime,
+    },
+    Location {
+        from: String,
+        to: String,
+    },
+    /// The recurrence, eg. a different last day or excluded dates.
+    Dates,
+    Description,
+}
+
+/// When an event is, read from its `DTSTART` and `DTEND`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventTime {
+    Timed {
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    },
+    AllDay(NaiveDate),
+    /// A `DTSTART` value that couldn't be read, as is.
+    Other(String),
+}
+
+impl Event {
+    /// Everything that changed since `previous`, ignoring the sequence.
+    pub fn changes_from(&self, previous: &Event) -> Vec<EventChange> {
+        let mut changes = Vec::new();
+        if self.summary != previous.summary {
+            changes.push(EventChange::Name {
+                from: previous.summary.clone(),
+                to: self.summary.clone(),
+            });
+        }
+        if self.start != previous.start || self.end != previous.end {
+            changes.push(EventChange::Time {
+                from: previous.time(),
+                to: self.time(),
+            });
+        }
+        if self.location != previous.location {
+            changes.push(EventChange::Location {
+                from: previous.location.clone(),
+                to: self.location.clone(),
+            });
+        }
+        if self.rrule != previous.rrule {
+            changes.push(EventChange::Dates);
+        }
+        if self.description != previous.description {
+            changes.push(EventChange::Description);
+        }
+        changes
+    }
+
+    /// When the event is, or its `DTSTART` if that can't be read.
+    pub fn time(&self) -> EventTime {
+        match (local_time(&self.start), local_time(&self.end)) {
+            (Some(start), Some(end)) => EventTime::Timed { start, end },
+            _ => match self
+                .start
+                .rsplit(':')
+                .next()
+                .and_then(|value| NaiveDate::parse_from_str(value, "%Y%m%d").ok())
+            {
+                Some(date) => EventTime::AllDay(date),
+                None => EventTime::Other(self.start.clone()),
+            },
+        }
+    }
+}
+
+fn local_time(value: &str) -> Option<NaiveDateTime> {
+    let value = value.rsplit(':').next()?;
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()
+}
+
+/// Reads every `VEVENT` from the `.ics` files in `folder` that were written by this tool, keyed by
+/// UID.
+pub fn read_export(folder: impl AsRef<Path>) -> io::Result<HashMap<String, Event>> {
+    let mut events = HashMap::new();
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("ics"))
+        {
+            let calendar = fs::read_to_string(path)?;
+            if is_generated(&calendar) {
+                events.extend(parse_events(&calendar));
+            }
+        }
+    }
+    Ok(events)
+}
+
+/// Parses the `VEVENT`s in a calendar, keyed by UID. Events without a UID are skipped.
+pub fn parse_events(calendar: &str) -> HashMap<String, Event> {
+    let unfolded = calendar.replace("\r\n", "\n").replace("\n ", "");
+
+    let mut events = HashMap::new();
+    let mut current: Option<(Option<String>, bool, Event)> = None;
+    for line in unfolded.lines() {
+        match line {
+            "BEGIN:VEVENT" => current = Some((None, false, Event::default())),
+            "END:VEVENT" => {
+                if let Some((Some(uid), _, event)) = current.take() {
+                    events.insert(uid, event);
+                }
+            }
+            _ => {
+                let Some((uid, has_description, event)) = &mut current else {
+                    continue;
+                };
+                // keep the parameters along with the value, since DTSTART's TZID matters too
+                let Some(split) = line.find([';', ':']) else {
+                    continue;
+                };
+                let (name, value) = (&line[..split], line[split + 1..].to_owned());
+                match name {
+                    "UID" => *uid = Some(value),
+                    "SEQUENCE" => event.sequence = value.parse().unwrap_or_default(),
+                    "SUMMARY" => event.summary = unescape_text(&value),
+                    "DTSTART" => event.start = value,
+                    "DTEND" => event.end = value,
+                    "RRULE" => event.rrule = value,
+                    "LOCATION" => event.location = unescape_text(&value),
+                    // only the first, so VALARM's DESCRIPTION is left out
+                    "DESCRIPTION" if !*has_description => {
+                        event.description = unescape_text(&value);
+                        *has_description = true;
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+    events
+}
+
+/// Compares `current` (UID and event, in calendar order) against a previous export.
+pub fn diff(previous: &HashMap<String, Event>, current: &[(String, Event)]) -> Vec<MeetingChange> {
+    let mut changes = current
+        .iter()
+        .filter_map(|(uid, event)| match previous.get(uid) {
+            None => Some(MeetingChange::Added(event.clone())),
+            Some(previous) => {
+                let changes = event.changes_from(previous);
+                (!changes.is_empty()).then(|| MeetingChange::Changed {
+                    meeting: previous.clone(),
+                    changes,
+                })
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut removed = previous
+        .iter()
+        .filter(|(uid, _)| !current.iter().any(|(current_uid, _)| current_uid == *uid))
+        .map(|(_, event)| event.clone())
+        .collect::<Vec<_>>();
+    removed.sort_by(|a, b| (&a.summary, &a.start).cmp(&(&b.summary, &b.start)));
+    changes.extend(removed.into_iter().map(MeetingChange::Removed));
+
+    changes
+}
+
+/// Compares `schedule` against the calendars previously written to `output_folder`.
+pub fn diff_previous_export(
+    output_folder: impl AsRef<Path>,
+    schedule: &Schedule,
+    profile: &InstitutionProfile,
+    options: &GenerateOptions,
+) -> io::Result<Vec<MeetingChange>> {
+    let previous = read_export(output_folder)?;
+    let current = meetings(schedule, profile, options)
+        .into_iter()
+        .map(|meeting| (meeting.uid, meeting.event))
+        .collect::<Vec<_>>();
+    Ok(diff(&previous, &current))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_diff() {
+        let previous = parse_events(
+            &indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            UID:a
+            SEQUENCE:3
+            DTSTART;TZID=America/Toronto:20240909T111000
+            DTEND;TZID=America/Toronto:20240909T123000
+            SUMMARY:Calculus
+            LOCATION:Science Buil
+             ding - 1350
+            DESCRIPTION:Code: MATH 1010U
+            BEGIN:VALARM
+            DESCRIPTION:Reminder
+            END:VALARM
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:b
+            SUMMARY:Physics
+            END:VEVENT
+            END:VCALENDAR
+        "}
+            .replace('\n', "\r\n"),
+        );
+
+        assert_eq!(previous["a"].sequence, 3);
+        assert_eq!(previous["a"].location, "Science Building - 1350");
+        assert_eq!(previous["a"].description, "Code: MATH 1010U");
+
+        let moved = Event {
+            location: "Science Building - 2120".to_owned(),
+            ..previous["a"].clone()
+        };
+        let added = Event {
+            summary: "Chemistry".to_owned(),
+            ..Default::default()
+        };
+        let changes = diff(
+            &previous,
+            &[("a".to_owned(), moved.clone()), ("c".to_owned(), added)],
+        );
+
+        let described = Event {
+            description: "Code: MATH 1020U".to_owned(),
+            ..moved.clone()
+        };
+        assert_eq!(described.changes_from(&moved), [EventChange::Description]);
+
+        assert_eq!(changes.len(), 3);
+        assert!(matches!(
+            &changes[0],
+            MeetingChange::Changed { changes, .. } if changes[..] == [EventChange::Location {
+                from: "Science Building - 1350".to_owned(),
+                to: "Science Building - 2120".to_owned(),
+            }]
+        ));
+        assert!(matches!(&changes[1], MeetingChange::Added(m) if m.summary == "Chemistry"));
+        assert!(matches!(&changes[2], MeetingChange::Removed(m) if m.summary == "Physics"));
+        assert_eq!(
+            previous["a"].time(),
+            EventTime::Timed {
+                start: NaiveDate::from_ymd_opt(2024, 9, 9)
+                    .unwrap()
+                    .and_hms_opt(11, 10, 0)
+                    .unwrap(),
+                end: NaiveDate::from_ymd_opt(2024, 9, 9)
+                    .unwrap()
+                    .and_hms_opt(12, 30, 0)
+                    .unwrap(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_unchanged_export() {
+        let schedule = crate::Parser::new()
+            .parse_data(include_str!("../tests/data/chromium.txt"))
+            .unwrap();
+        let profile = crate::InstitutionProfile::default();
+        let folder = crate::test_folder("unchanged-export");
+
+        crate::write_calendars(
+            &folder,
+            &schedule,
+            Default::default(),
+            &profile,
+            &Default::default(),
+        );
+        // calendars from other apps in the same folder aren't part of the export
+        fs::write(
+            folder.join("other.ics"),
+            "BEGIN:VCALENDAR\r\nPRODID:-//Other//EN\r\nBEGIN:VEVENT\r\nUID:other\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+        )
+        .unwrap();
+        let changes = diff_previous_export(&folder, &schedule, &profile, &Default::default());
+        fs::remove_dir_all(&folder).ok();
+
+        assert_eq!(changes.unwrap(), Vec::new());
+    }
+}
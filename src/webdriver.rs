@@ -0,0 +1,216 @@
+//! Fetching the schedule by driving a local browser through WebDriver, eg. `chromedriver` or
+//! `geckodriver`, instead of copying and pasting it.
+//!
+//! The browser opens [`InstitutionProfile::login_url`], and the user signs in and opens their
+//! schedule as usual, since single sign-on and two-factor prompts can't be automated reliably.
+//! Once the page shows the schedule, its text is taken from the browser and the window closes.
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::{html::html_to_text, InstitutionProfile};
+
+/// Where `chromedriver` listens by default. `geckodriver` uses port 4444 instead.
+pub const DEFAULT_WEBDRIVER_URL: &str = "http://localhost:9515";
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// How long to wait for the user to open their schedule.
+const TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Error)]
+pub enum FetchError {
+    #[error(
+        "Couldn't connect to the WebDriver server. Is chromedriver or geckodriver running? ({0})"
+    )]
+    Http(#[from] ureq::Error),
+    #[error("Unexpected response from the WebDriver server: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("The browser reported an error: {0}")]
+    WebDriver(String),
+    #[error("Timed out waiting for the schedule to be opened")]
+    TimedOut,
+    #[error("Cancelled")]
+    Cancelled,
+}
+
+/// Opens a browser with the WebDriver server at `webdriver_url`, waits until the user has opened
+/// the schedule page, and returns its text in the same layout as when it's copied. Stops early if
+/// `cancelled` is set.
+pub fn fetch_schedule(
+    webdriver_url: &str,
+    profile: &InstitutionProfile,
+    cancelled: &AtomicBool,
+) -> Result<String, FetchError> {
+    let webdriver_url = webdriver_url.trim_end_matches('/');
+    let session = command(
+        "POST",
+        &format!("{webdriver_url}/session"),
+        Some(json!({ "capabilities": { "alwaysMatch": {} } })),
+    )?;
+    let session_url = format!(
+        "{webdriver_url}/session/{}",
+        session["sessionId"].as_str().unwrap_or_default()
+    );
+
+    let result = wait_for_schedule(&session_url, profile, cancelled);
+    // closes the browser; it may already be gone if the user closed it
+    command("DELETE", &session_url, None).ok();
+    result
+}
+
+fn wait_for_schedule(
+    session_url: &str,
+    profile: &InstitutionProfile,
+    cancelled: &AtomicBool,
+) -> Result<String, FetchError> {
+    command(
+        "POST",
+        &format!("{session_url}/url"),
+        Some(json!({ "url": profile.login_url })),
+    )?;
+
+    let started = Instant::now();
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(FetchError::Cancelled);
+        }
+        if started.elapsed() > TIMEOUT {
+            return Err(FetchError::TimedOut);
+        }
+        let source = command("GET", &format!("{session_url}/source"), None)?;
+        let text = html_to_text(source.as_str().unwrap_or_default());
+        if text
+            .lines()
+            .any(|line| line.starts_with(&profile.markers.schedule_start))
+        {
+            return Ok(text);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Sends a WebDriver command and returns its `value`.
+fn command(method: &str, url: &str, body: Option<Value>) -> Result<Value, FetchError> {
+    let mut response = match (method, body) {
+        ("GET", _) => ureq::get(url)
+            .config()
+            .http_status_as_error(false)
+            .build()
+            .call()?,
+        ("DELETE", _) => ureq::delete(url)
+            .config()
+            .http_status_as_error(false)
+            .build()
+            .call()?,
+        (_, body) => ureq::post(url)
+            .config()
+            .http_status_as_error(false)
+            .build()
+            .header("Content-Type", "application/json")
+            .send(body.unwrap_or_else(|| json!({})).to_string())?,
+    };
+    let value =
+        serde_json::from_str::<Value>(&response.body_mut().read_to_string()?)?["value"].take();
+    if response.status().is_success() {
+        Ok(value)
+    } else {
+        Err(FetchError::WebDriver(
+            value["message"]
+                .as_str()
+                .or(value["error"].as_str())
+                .unwrap_or("unknown error")
+                .to_owned(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+    };
+
+    /// A WebDriver server that answers each request with the next of `responses`, and returns the
+    /// request lines it got.
+    fn fake_webdriver(responses: Vec<Value>) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(&stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = header.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                reader
+                    .by_ref()
+                    .take(content_length)
+                    .read_to_end(&mut Vec::new())
+                    .unwrap();
+
+                let body = json!({ "value": response }).to_string();
+                write!(
+                    &stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+                requests.push(request_line.trim().to_owned());
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_fetch_schedule() {
+        let page = include_str!("../tests/data/schedule.html");
+        let (url, server) = fake_webdriver(vec![
+            json!({ "sessionId": "abc", "capabilities": {} }),
+            Value::Null,
+            json!("<html><body><h1>Sign in</h1></body></html>"),
+            json!(page),
+            Value::Null,
+        ]);
+
+        let text = fetch_schedule(
+            &url,
+            &InstitutionProfile::default(),
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        let requests = server.join().unwrap();
+
+        assert_eq!(text, html_to_text(page));
+        assert_eq!(
+            requests,
+            [
+                "POST /session HTTP/1.1",
+                "POST /session/abc/url HTTP/1.1",
+                "GET /session/abc/source HTTP/1.1",
+                "GET /session/abc/source HTTP/1.1",
+                "DELETE /session/abc HTTP/1.1",
+            ]
+        );
+    }
+}
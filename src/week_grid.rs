@@ -0,0 +1,391 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Cursor,
+};
+
+use chrono::{NaiveTime, Timelike, Weekday};
+use eframe::egui::{
+    self,
+    epaint::{ClippedShape, Primitive},
+    Align2, Color32, FontId, Pos2, Rect, Rounding, Sense, Shape, Stroke, Vec2, Visuals,
+};
+use image::ImageFormat;
+use mycampus_calendar_rs::parser::{Class, DateRange, Schedule};
+
+use crate::i18n::{tr, tr_args, weekday_name};
+
+const PALETTE: [[u8; 3]; 6] = [
+    [0x1e, 0x90, 0xff],
+    [0xff, 0x8c, 0x00],
+    [0x3c, 0xb3, 0x71],
+    [0xba, 0x55, 0xd3],
+    [0xdc, 0x14, 0x3c],
+    [0x20, 0xb2, 0xaa],
+];
+
+const HEADER_HEIGHT: f32 = 20.0;
+const HOUR_LABEL_WIDTH: f32 = 44.0;
+const HOUR_HEIGHT: f32 = 40.0;
+/// Width of the grid in saved images, in points.
+const IMAGE_WIDTH: f32 = 900.0;
+const IMAGE_FILE_NAME: &str = "schedule.png";
+
+/// Weekly timetable of the selected classes, colored by schedule type.
+///
+/// Overlapping meetings are drawn on top of each other with translucent colors, so they stand out.
+/// `status` is the result of the last "Save as PNG" click.
+pub fn week_grid(
+    ui: &mut egui::Ui,
+    schedule: &Schedule,
+    selected: &[bool],
+    colors: &BTreeMap<String, [u8; 3]>,
+    status: &mut Option<String>,
+) {
+    let Some(grid) = WeekGrid::new(schedule, selected, colors) else {
+        ui.label(tr("week-grid-empty"));
+        return;
+    };
+
+    let (response, painter) = ui.allocate_painter(
+        Vec2::new(ui.available_width(), grid.height()),
+        Sense::hover(),
+    );
+    let (shapes, blocks) = grid.shapes(ui.ctx(), ui.visuals(), response.rect);
+    painter.extend(shapes);
+
+    if let Some(pointer) = response.hover_pos() {
+        let hovered = blocks
+            .iter()
+            .filter(|(block, _, _)| block.contains(pointer))
+            .collect::<Vec<_>>();
+        if !hovered.is_empty() {
+            response.on_hover_ui_at_pointer(|ui| {
+                for (_, class, date_range) in hovered {
+                    ui.label(format!(
+                        "{} ({})\n{} {}–{}\n{}\n{}",
+                        class.name,
+                        class.class_type,
+                        weekday_name(date_range.weekday),
+                        date_range.start_time.format("%H:%M"),
+                        date_range.end_time.format("%H:%M"),
+                        date_range.place(),
+                        tr_args(
+                            "week-grid-dates",
+                            &[
+                                ("start", date_range.start_date.to_string().into()),
+                                ("end", date_range.end_date.to_string().into()),
+                            ],
+                        ),
+                    ));
+                }
+            });
+        }
+    }
+
+    ui.horizontal(|ui| {
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui.button(tr("week-grid-save-png")).clicked() {
+            if let Some(path) = rfd::FileDialog::new()
+                .set_file_name(IMAGE_FILE_NAME)
+                .add_filter(tr("week-grid-png-filter"), &["png"])
+                .save_file()
+            {
+                let saved = grid
+                    .png(ui.ctx(), ui.visuals())
+                    .and_then(|png| Ok(fs::write(&path, png)?));
+                *status = Some(match saved {
+                    Ok(()) => tr_args("saved", &[("path", path.display().to_string().into())]),
+                    Err(e) => tr_args("week-grid-save-failed", &[("error", e.to_string().into())]),
+                });
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        if ui.button(tr("week-grid-download-png")).clicked() {
+            let downloaded = grid
+                .png(ui.ctx(), ui.visuals())
+                .map_err(|e| e.to_string())
+                .and_then(|png| crate::web::download(IMAGE_FILE_NAME, &png));
+            *status = Some(match downloaded {
+                Ok(()) => tr_args("downloaded", &[("file", IMAGE_FILE_NAME.into())]),
+                Err(e) => tr_args("week-grid-save-failed", &[("error", e.into())]),
+            });
+        }
+        if let Some(status) = status {
+            ui.label(status.as_str());
+        }
+    });
+}
+
+/// The meetings to draw and the days and hours that they span.
+struct WeekGrid<'a> {
+    meetings: Vec<(&'a Class, &'a DateRange, Color32)>,
+    days: u32,
+    first_hour: u32,
+    last_hour: u32,
+}
+
+impl<'a> WeekGrid<'a> {
+    fn new(
+        schedule: &'a Schedule,
+        selected: &[bool],
+        colors: &BTreeMap<String, [u8; 3]>,
+    ) -> Option<Self> {
+        let meetings = schedule
+            .classes
+            .iter()
+            .zip(selected.iter().chain(std::iter::repeat(&true)))
+            .filter(|(_, &selected)| selected)
+            .flat_map(|(class, _)| class.date_ranges.iter().map(move |d| (class, d)))
+            .collect::<Vec<_>>();
+        if meetings.is_empty() {
+            return None;
+        }
+
+        let class_types = meetings
+            .iter()
+            .map(|(class, _)| &class.class_type)
+            .collect::<BTreeSet<_>>();
+        let color = |class_type: &String| {
+            let [r, g, b] = colors.get(class_type).copied().unwrap_or_else(|| {
+                let i = class_types
+                    .iter()
+                    .position(|t| *t == class_type)
+                    .unwrap_or(0);
+                PALETTE[i % PALETTE.len()]
+            });
+            Color32::from_rgb(r, g, b)
+        };
+
+        // hide the weekend unless something's on it
+        let days = if meetings
+            .iter()
+            .any(|(_, d)| matches!(d.weekday, Weekday::Sat | Weekday::Sun))
+        {
+            7
+        } else {
+            5
+        };
+        let first_hour = meetings
+            .iter()
+            .map(|(_, d)| d.start_time.hour())
+            .min()
+            .unwrap_or(8)
+            .min(8);
+        let last_hour = meetings
+            .iter()
+            .map(|(_, d)| d.end_time.hour() + (d.end_time.minute() > 0) as u32)
+            .max()
+            .unwrap_or(18)
+            .max(18);
+
+        Some(Self {
+            meetings: meetings
+                .into_iter()
+                .map(|(class, date_range)| (class, date_range, color(&class.class_type)))
+                .collect(),
+            days,
+            first_hour,
+            last_hour,
+        })
+    }
+
+    fn height(&self) -> f32 {
+        HEADER_HEIGHT + HOUR_HEIGHT * (self.last_hour - self.first_hour) as f32
+    }
+
+    /// The shapes of the grid drawn in `rect`, and the rect of each meeting.
+    #[allow(clippy::type_complexity)]
+    fn shapes(
+        &self,
+        ctx: &egui::Context,
+        visuals: &Visuals,
+        rect: Rect,
+    ) -> (Vec<Shape>, Vec<(Rect, &'a Class, &'a DateRange)>) {
+        let grid_stroke = Stroke::new(1.0, visuals.widgets.noninteractive.bg_stroke.color);
+        let text_color = visuals.text_color();
+        let day_width = (rect.width() - HOUR_LABEL_WIDTH) / self.days as f32;
+
+        let y = |hours: f32| {
+            rect.top() + HEADER_HEIGHT + (hours - self.first_hour as f32) * HOUR_HEIGHT
+        };
+        let hours = |time: NaiveTime| time.num_seconds_from_midnight() as f32 / 3600.0;
+        let x = |day: u32| rect.left() + HOUR_LABEL_WIDTH + day as f32 * day_width;
+        let text = |pos: Pos2, align: Align2, text: String, size: f32, color: Color32| {
+            let galley = ctx.fonts(|f| f.layout_no_wrap(text, FontId::proportional(size), color));
+            Shape::galley(align.anchor_size(pos, galley.size()).min, galley, color)
+        };
+
+        let mut shapes = Vec::new();
+        for day in 0..self.days {
+            shapes.push(text(
+                Pos2::new(x(day) + day_width / 2.0, rect.top() + HEADER_HEIGHT / 2.0),
+                Align2::CENTER_CENTER,
+                Weekday::try_from(day as u8).map_or_else(|_| String::new(), weekday_name),
+                14.0,
+                text_color,
+            ));
+            shapes.push(Shape::vline(
+                x(day),
+                rect.top()..=rect.bottom(),
+                grid_stroke,
+            ));
+        }
+        for hour in self.first_hour..=self.last_hour {
+            let top = y(hour as f32);
+            shapes.push(Shape::hline(rect.left()..=rect.right(), top, grid_stroke));
+            if hour < self.last_hour {
+                shapes.push(text(
+                    Pos2::new(rect.left() + 4.0, top + 2.0),
+                    Align2::LEFT_TOP,
+                    format!("{hour}:00"),
+                    12.0,
+                    text_color,
+                ));
+            }
+        }
+
+        let mut blocks = Vec::new();
+        for &(class, date_range, color) in &self.meetings {
+            let day = date_range.weekday.num_days_from_monday();
+            if day >= self.days {
+                continue;
+            }
+            let block = Rect::from_min_max(
+                Pos2::new(x(day) + 2.0, y(hours(date_range.start_time))),
+                Pos2::new(x(day + 1) - 2.0, y(hours(date_range.end_time))),
+            );
+            shapes.push(Shape::rect_filled(
+                block,
+                Rounding::same(3.0),
+                color.gamma_multiply(0.6),
+            ));
+            shapes.push(Shape::rect_stroke(
+                block,
+                Rounding::same(3.0),
+                Stroke::new(1.0, color),
+            ));
+            shapes.push(text(
+                block.left_top() + Vec2::new(4.0, 2.0),
+                Align2::LEFT_TOP,
+                format!("{}\n{}", class.code, class.class_type),
+                12.0,
+                Color32::WHITE,
+            ));
+            blocks.push((block, class, date_range));
+        }
+
+        (shapes, blocks)
+    }
+
+    /// Renders the grid to a PNG by tessellating the same shapes that are drawn on screen, then
+    /// filling in the triangles.
+    fn png(&self, ctx: &egui::Context, visuals: &Visuals) -> image::ImageResult<Vec<u8>> {
+        let pixels_per_point = ctx.pixels_per_point();
+        // leave room for the outer grid lines
+        let rect = Rect::from_min_size(
+            Pos2::ZERO,
+            Vec2::new(IMAGE_WIDTH, self.height()) + Vec2::splat(2.0),
+        );
+        let (shapes, _) = self.shapes(ctx, visuals, rect.shrink(1.0));
+        let primitives = ctx.tessellate(
+            shapes
+                .into_iter()
+                .map(|shape| ClippedShape {
+                    clip_rect: rect,
+                    shape,
+                })
+                .collect(),
+            pixels_per_point,
+        );
+
+        let font_image = ctx.fonts(|f| f.image());
+        let font_pixels = font_image.srgba_pixels(None).collect::<Vec<_>>();
+        let [font_width, font_height] = font_image.size;
+
+        let width = (rect.width() * pixels_per_point).ceil() as u32;
+        let height = (rect.height() * pixels_per_point).ceil() as u32;
+        let mut pixels = vec![visuals.panel_fill; (width * height) as usize];
+
+        for primitive in primitives {
+            let Primitive::Mesh(mesh) = primitive.primitive else {
+                continue;
+            };
+            for triangle in mesh.indices.chunks_exact(3) {
+                let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize]);
+                let [pa, pb, pc] = [a.pos, b.pos, c.pos].map(|p| p.to_vec2() * pixels_per_point);
+                let area = (pb - pa).x * (pc - pa).y - (pb - pa).y * (pc - pa).x;
+                if area.abs() < f32::EPSILON {
+                    continue;
+                }
+
+                let min = pa.min(pb).min(pc).max(Vec2::ZERO);
+                let max = pa.max(pb).max(pc);
+                for py in min.y as u32..(max.y.ceil() as u32).min(height) {
+                    for px in min.x as u32..(max.x.ceil() as u32).min(width) {
+                        // barycentric weights of the pixel center
+                        let p = Vec2::new(px as f32 + 0.5, py as f32 + 0.5);
+                        let edge = |from: Vec2, to: Vec2| {
+                            ((to - from).x * (p - from).y - (to - from).y * (p - from).x) / area
+                        };
+                        let weights = [edge(pb, pc), edge(pc, pa), edge(pa, pb)];
+                        if weights.iter().any(|&w| w < 0.0) {
+                            continue;
+                        }
+
+                        let uv = [a.uv, b.uv, c.uv]
+                            .iter()
+                            .zip(weights)
+                            .fold(Vec2::ZERO, |sum, (uv, w)| sum + uv.to_vec2() * w);
+                        let texel = font_pixels[((uv.y * font_height as f32) as usize)
+                            .min(font_height - 1)
+                            * font_width
+                            + ((uv.x * font_width as f32) as usize).min(font_width - 1)];
+                        let [r, g, b, alpha] = [a.color, b.color, c.color]
+                            .iter()
+                            .zip(weights)
+                            .fold([0.0; 4], |mut sum, (color, w)| {
+                                for (sum, channel) in sum.iter_mut().zip(color.to_array()) {
+                                    *sum += channel as f32 * w;
+                                }
+                                sum
+                            })
+                            .map(|channel| channel.round() as u8);
+                        let tint =
+                            |channel: u8, texel: u8| (channel as u32 * texel as u32 / 255) as u8;
+                        let source = Color32::from_rgba_premultiplied(
+                            tint(r, texel.r()),
+                            tint(g, texel.g()),
+                            tint(b, texel.b()),
+                            tint(alpha, texel.a()),
+                        );
+
+                        // premultiplied alpha "over" blending
+                        let pixel = &mut pixels[(py * width + px) as usize];
+                        let keep = 255 - source.a() as u32;
+                        let blend = |source: u8, destination: u8| {
+                            source.saturating_add((destination as u32 * keep / 255) as u8)
+                        };
+                        *pixel = Color32::from_rgba_premultiplied(
+                            blend(source.r(), pixel.r()),
+                            blend(source.g(), pixel.g()),
+                            blend(source.b(), pixel.b()),
+                            blend(source.a(), pixel.a()),
+                        );
+                    }
+                }
+            }
+        }
+
+        let mut png = Vec::new();
+        image::RgbaImage::from_raw(
+            width,
+            height,
+            pixels.iter().flat_map(|pixel| pixel.to_array()).collect(),
+        )
+        .expect("buffer matches the image size")
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+        Ok(png)
+    }
+}